@@ -0,0 +1,376 @@
+//! Contract ABI JSON.
+//!
+//! [`JsonAbi`] deserializes the standard ABI array emitted by solc and
+//! friends and resolves every parameter into a [`DynType`], so calldata,
+//! return data and logs of contracts unknown at compile time can be decoded
+//! with [`crate::dynamic`]:
+//!
+//! ```json
+//! [{ "type": "function", "name": "transfer",
+//!    "inputs": [{ "name": "to", "type": "address" }, { "name": "amount", "type": "uint256" }],
+//!    "outputs": [{ "name": "", "type": "bool" }] }]
+//! ```
+//!
+//! Constructors, fallback and receive entries carry no name or selector and
+//! are skipped.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use serde::Deserialize;
+
+use crate::dynamic::{decode_dyn_params, DynType, DynValue};
+use crate::error::ZError;
+
+/// A function, event or error parameter.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawParam")]
+pub struct AbiParam {
+    /// May be empty, e.g. for unnamed outputs.
+    pub name: String,
+    pub ty: DynType,
+    /// Whether an event parameter is stored in a topic.
+    pub indexed: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct AbiFunction {
+    pub name: String,
+    #[serde(default)]
+    pub inputs: Vec<AbiParam>,
+    #[serde(default)]
+    pub outputs: Vec<AbiParam>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct AbiEvent {
+    pub name: String,
+    #[serde(default)]
+    pub inputs: Vec<AbiParam>,
+    /// Anonymous events do not put their signature hash in topic 0.
+    #[serde(default)]
+    pub anonymous: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct AbiError {
+    pub name: String,
+    #[serde(default)]
+    pub inputs: Vec<AbiParam>,
+}
+
+/// The functions, events and errors of a contract ABI, in file order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(from = "Vec<RawItem>")]
+pub struct JsonAbi {
+    pub functions: Vec<AbiFunction>,
+    pub events: Vec<AbiEvent>,
+    pub errors: Vec<AbiError>,
+}
+
+impl JsonAbi {
+    /// The first function called `name`. Overloads share a name; iterate
+    /// [`JsonAbi::functions`] to tell them apart.
+    pub fn function(&self, name: &str) -> Option<&AbiFunction> {
+        self.functions.iter().find(|f| f.name == name)
+    }
+
+    /// The first event called `name`.
+    pub fn event(&self, name: &str) -> Option<&AbiEvent> {
+        self.events.iter().find(|e| e.name == name)
+    }
+
+    /// The first error called `name`.
+    pub fn error(&self, name: &str) -> Option<&AbiError> {
+        self.errors.iter().find(|e| e.name == name)
+    }
+
+    /// The function whose selector is `selector`.
+    #[cfg(feature = "keccak")]
+    pub fn function_by_selector(&self, selector: &[u8; 4]) -> Option<&AbiFunction> {
+        self.functions.iter().find(|f| f.selector() == *selector)
+    }
+}
+
+impl AbiFunction {
+    /// Canonical signature, e.g. `"transfer(address,uint256)"`.
+    pub fn signature(&self) -> String {
+        signature(&self.name, &self.inputs)
+    }
+
+    #[cfg(feature = "keccak")]
+    pub fn selector(&self) -> [u8; 4] {
+        selector(&self.signature())
+    }
+
+    /// The argument list as a tuple type.
+    pub fn input_type(&self) -> DynType {
+        tuple(&self.inputs)
+    }
+
+    /// The return values as a tuple type.
+    pub fn output_type(&self) -> DynType {
+        tuple(&self.outputs)
+    }
+
+    /// Decode calldata, including the selector, into a [`DynValue::Tuple`]
+    /// of the arguments. The selector is checked when `keccak` is enabled.
+    pub fn decode_input<'a>(&self, calldata: &'a [u8]) -> Result<DynValue<'a>, ZError> {
+        let found = crate::decoder::read_selector(calldata)?;
+        #[cfg(feature = "keccak")]
+        {
+            let expected = self.selector();
+            if *found != expected {
+                return Err(ZError::SelectorMismatch {
+                    expected,
+                    found: *found,
+                });
+            }
+        }
+        #[cfg(not(feature = "keccak"))]
+        let _ = found;
+        decode_dyn_params(&self.input_type(), &calldata[4..])
+    }
+
+    /// Decode return data into a [`DynValue::Tuple`] of the outputs.
+    pub fn decode_output<'a>(&self, data: &'a [u8]) -> Result<DynValue<'a>, ZError> {
+        decode_dyn_params(&self.output_type(), data)
+    }
+}
+
+impl AbiEvent {
+    /// Canonical signature, e.g. `"Transfer(address,address,uint256)"`.
+    pub fn signature(&self) -> String {
+        signature(&self.name, &self.inputs)
+    }
+
+    /// The expected topic 0; meaningless for anonymous events.
+    #[cfg(feature = "keccak")]
+    pub fn topic0(&self) -> [u8; 32] {
+        crate::hash::keccak256(self.signature().as_bytes())
+    }
+
+    /// The non-indexed parameters, which make up the log data, as a tuple type.
+    pub fn data_type(&self) -> DynType {
+        DynType::Tuple(
+            self.inputs
+                .iter()
+                .filter(|p| !p.indexed)
+                .map(|p| p.ty.clone())
+                .collect(),
+        )
+    }
+}
+
+impl AbiError {
+    /// Canonical signature, e.g. `"InsufficientBalance(uint256,uint256)"`.
+    pub fn signature(&self) -> String {
+        signature(&self.name, &self.inputs)
+    }
+
+    #[cfg(feature = "keccak")]
+    pub fn selector(&self) -> [u8; 4] {
+        selector(&self.signature())
+    }
+
+    /// The error arguments as a tuple type.
+    pub fn input_type(&self) -> DynType {
+        tuple(&self.inputs)
+    }
+}
+
+fn tuple(params: &[AbiParam]) -> DynType {
+    DynType::Tuple(params.iter().map(|p| p.ty.clone()).collect())
+}
+
+fn signature(name: &str, params: &[AbiParam]) -> String {
+    format!("{}{}", name, tuple(params))
+}
+
+#[cfg(feature = "keccak")]
+fn selector(signature: &str) -> [u8; 4] {
+    let hash = crate::hash::keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// A parameter before its type is resolved.
+#[derive(Deserialize)]
+struct RawParam {
+    #[serde(default)]
+    name: String,
+    #[serde(rename = "type")]
+    ty: String,
+    #[serde(default)]
+    components: Vec<AbiParam>,
+    #[serde(default)]
+    indexed: bool,
+}
+
+impl TryFrom<RawParam> for AbiParam {
+    type Error = ZError;
+
+    fn try_from(raw: RawParam) -> Result<Self, Self::Error> {
+        // `tuple`, `tuple[]`, `tuple[2][]`...: the members come from
+        // `components`, any array suffix from the type string.
+        let ty = match raw.ty.strip_prefix("tuple") {
+            Some(suffix) => DynType::parse(&format!("{}{}", tuple(&raw.components), suffix))?,
+            None => DynType::parse(&raw.ty)?,
+        };
+        Ok(AbiParam {
+            name: raw.name,
+            ty,
+            indexed: raw.indexed,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum RawItem {
+    Function(AbiFunction),
+    Event(AbiEvent),
+    Error(AbiError),
+    #[serde(other)]
+    Other,
+}
+
+impl From<Vec<RawItem>> for JsonAbi {
+    fn from(items: Vec<RawItem>) -> Self {
+        let mut abi = JsonAbi::default();
+        for item in items {
+            match item {
+                RawItem::Function(f) => abi.functions.push(f),
+                RawItem::Event(e) => abi.events.push(e),
+                RawItem::Error(e) => abi.errors.push(e),
+                RawItem::Other => {}
+            }
+        }
+        abi
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    const ERC20_ABI: &str = r#"[
+        { "type": "constructor", "inputs": [{ "name": "supply", "type": "uint256" }], "stateMutability": "nonpayable" },
+        { "type": "function", "name": "transfer", "stateMutability": "nonpayable",
+          "inputs": [{ "name": "to", "type": "address", "internalType": "address" },
+                     { "name": "amount", "type": "uint256", "internalType": "uint256" }],
+          "outputs": [{ "name": "", "type": "bool", "internalType": "bool" }] },
+        { "type": "function", "name": "settle",
+          "inputs": [{ "name": "orders", "type": "tuple[]", "internalType": "struct Order[]",
+                       "components": [{ "name": "maker", "type": "address" },
+                                      { "name": "legs", "type": "tuple[2]",
+                                        "components": [{ "name": "amount", "type": "uint128" }] },
+                                      { "name": "memo", "type": "bytes" }] }],
+          "outputs": [] },
+        { "type": "event", "name": "Transfer", "anonymous": false,
+          "inputs": [{ "name": "from", "type": "address", "indexed": true },
+                     { "name": "to", "type": "address", "indexed": true },
+                     { "name": "value", "type": "uint256", "indexed": false }] },
+        { "type": "error", "name": "InsufficientBalance",
+          "inputs": [{ "name": "available", "type": "uint256" }, { "name": "required", "type": "uint256" }] },
+        { "type": "receive", "stateMutability": "payable" }
+    ]"#;
+
+    #[test]
+    fn test_parse_abi() {
+        let abi: JsonAbi = serde_json::from_str(ERC20_ABI).unwrap();
+        assert_eq!(abi.functions.len(), 2);
+        assert_eq!(abi.events.len(), 1);
+        assert_eq!(abi.errors.len(), 1);
+
+        let transfer = abi.function("transfer").unwrap();
+        assert_eq!(transfer.signature(), "transfer(address,uint256)");
+        assert_eq!(transfer.inputs[1].name, "amount");
+        assert_eq!(
+            transfer.output_type(),
+            DynType::Tuple(alloc::vec![DynType::Bool])
+        );
+
+        let settle = abi.function("settle").unwrap();
+        assert_eq!(settle.signature(), "settle((address,(uint128)[2],bytes)[])");
+        assert!(settle.inputs[0].ty.is_dynamic());
+
+        let event = abi.event("Transfer").unwrap();
+        assert_eq!(event.signature(), "Transfer(address,address,uint256)");
+        assert!(event.inputs[0].indexed);
+        assert_eq!(event.data_type().to_string(), "(uint256)");
+        assert_eq!(
+            abi.error("InsufficientBalance").unwrap().signature(),
+            "InsufficientBalance(uint256,uint256)"
+        );
+        assert!(abi.function("approve").is_none());
+    }
+
+    #[test]
+    fn test_rejects_bad_types() {
+        let bad = r#"[{ "type": "function", "name": "f", "inputs": [{ "name": "x", "type": "uint7" }] }]"#;
+        let result = serde_json::from_str::<JsonAbi>(bad);
+        assert!(result.is_err());
+        #[cfg(not(feature = "tiny-errors"))]
+        assert!(result.unwrap_err().to_string().contains("uint7"));
+    }
+
+    #[test]
+    fn test_decode_with_abi() {
+        let abi: JsonAbi = serde_json::from_str(ERC20_ABI).unwrap();
+        let transfer = abi.function("transfer").unwrap();
+
+        let mut calldata = [0u8; 68];
+        calldata[..4].copy_from_slice(&[0xa9, 0x05, 0x9c, 0xbb]);
+        calldata[16..36].copy_from_slice(&[0x22; 20]);
+        calldata[67] = 5;
+        let args = transfer.decode_input(&calldata).unwrap();
+        assert_eq!(
+            args.get(0)
+                .and_then(DynValue::as_address)
+                .unwrap()
+                .as_bytes(),
+            &[0x22; 20]
+        );
+        assert_eq!(args.get(1).unwrap().to_string(), "5");
+
+        let mut ret = [0u8; 32];
+        ret[31] = 1;
+        assert_eq!(
+            transfer
+                .decode_output(&ret)
+                .unwrap()
+                .get(0)
+                .and_then(DynValue::as_bool),
+            Some(true)
+        );
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn test_selectors() {
+        let abi: JsonAbi = serde_json::from_str(ERC20_ABI).unwrap();
+        assert_eq!(
+            abi.function("transfer").unwrap().selector(),
+            [0xa9, 0x05, 0x9c, 0xbb]
+        );
+        assert_eq!(
+            abi.function_by_selector(&[0xa9, 0x05, 0x9c, 0xbb])
+                .unwrap()
+                .name,
+            "transfer"
+        );
+        assert_eq!(
+            abi.event("Transfer").unwrap().topic0()[..4],
+            [0xdd, 0xf2, 0x52, 0xad]
+        );
+
+        let calldata = [0x09, 0x5e, 0xa7, 0xb3, 0, 0, 0, 0];
+        assert!(matches!(
+            abi.function("transfer").unwrap().decode_input(&calldata),
+            Err(ZError::SelectorMismatch { .. })
+        ));
+    }
+}
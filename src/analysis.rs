@@ -0,0 +1,182 @@
+//! Correlation of decoded calls with the events they emit.
+//!
+//! Standard contracts emit a predictable event for each state-changing call:
+//! an ERC-20 `transferFrom(from, to, value)` logs
+//! `Transfer(from, to, value)`, and so on. [`Correlate`] expresses that link
+//! between a decoded call and a decoded event. [`find_correlated`] searches
+//! a transaction's logs for the event that a call produced.
+//!
+//! ```
+//! use zabi_rs::analysis::{find_correlated, TxContext};
+//! use zabi_rs::presets::erc20::{transferCall, Transfer};
+//! use zabi_rs::ZEventLog;
+//!
+//! let sender = [0x11u8; 20];
+//! let token = [0x22u8; 20];
+//! let mut calldata = transferCall::SELECTOR.to_vec();
+//! calldata.extend_from_slice(&[0u8; 31]);
+//! calldata.push(0x33); // to
+//! calldata.extend_from_slice(&[0u8; 31]);
+//! calldata.push(100); // value
+//! let call = transferCall::decode_calldata(&calldata).unwrap();
+//!
+//! let mut from = [0u8; 32];
+//! from[12..].copy_from_slice(&sender);
+//! let topics = [Transfer::TOPIC0, from, calldata[4..36].try_into().unwrap()];
+//! let log = ZEventLog::from_topics(&topics, &calldata[36..]).with_address(&token);
+//!
+//! let tx = TxContext::new(&sender, &token);
+//! let (index, event): (usize, Transfer) = find_correlated(&call, &tx, [log]).unwrap();
+//! assert_eq!(index, 0);
+//! assert_eq!(event.value.to_u64(), Some(100));
+//! ```
+
+use crate::event::{ZEventDecode, ZEventLog};
+use crate::presets::{erc20, erc721, weth};
+use crate::types::{ZAddress, ZU256};
+
+/// The parts of a transaction needed to tie its call to its logs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TxContext<'t> {
+    /// The account that sent the call (`msg.sender` of the outer call).
+    pub from: &'t [u8; 20],
+    /// The contract that was called.
+    pub to: &'t [u8; 20],
+}
+
+impl<'t> TxContext<'t> {
+    #[inline]
+    pub fn new(from: &'t [u8; 20], to: &'t [u8; 20]) -> Self {
+        Self { from, to }
+    }
+}
+
+/// A decoded call that produces events of type `E`.
+pub trait Correlate<E> {
+    /// Returns true if `event` is the one this call emits when sent in `tx`.
+    fn correlates(&self, event: &E, tx: &TxContext<'_>) -> bool;
+}
+
+/// Find the first log that decodes as `E` and correlates with `call`.
+///
+/// Logs with an attached address are only considered when it equals
+/// `tx.to`; logs without one are considered regardless. Logs that fail to
+/// decode as `E` are skipped. Returns the log's position in `logs` and the
+/// decoded event.
+pub fn find_correlated<'a, C, E, I>(call: &C, tx: &TxContext<'_>, logs: I) -> Option<(usize, E)>
+where
+    C: Correlate<E>,
+    E: ZEventDecode<'a>,
+    I: IntoIterator<Item = ZEventLog<'a>>,
+{
+    logs.into_iter().enumerate().find_map(|(index, log)| {
+        if matches!(log.address(), Some(address) if address.0 != tx.to) {
+            return None;
+        }
+        let event = log.try_decode::<E>().ok()?;
+        call.correlates(&event, tx).then_some((index, event))
+    })
+}
+
+#[inline]
+fn same_address(a: &ZAddress<'_>, b: &ZAddress<'_>) -> bool {
+    a.0 == b.0
+}
+
+#[inline]
+fn same_word(a: &ZU256<'_>, b: &ZU256<'_>) -> bool {
+    a.0 == b.0
+}
+
+impl<'e> Correlate<erc20::Transfer<'e>> for erc20::transferCall<'_> {
+    fn correlates(&self, event: &erc20::Transfer<'e>, tx: &TxContext<'_>) -> bool {
+        event.from.0 == tx.from
+            && same_address(&event.to, &self.to)
+            && same_word(&event.value, &self.value)
+    }
+}
+
+impl<'e> Correlate<erc20::Transfer<'e>> for erc20::transferFromCall<'_> {
+    fn correlates(&self, event: &erc20::Transfer<'e>, _tx: &TxContext<'_>) -> bool {
+        same_address(&event.from, &self.from)
+            && same_address(&event.to, &self.to)
+            && same_word(&event.value, &self.value)
+    }
+}
+
+impl<'e> Correlate<erc20::Approval<'e>> for erc20::approveCall<'_> {
+    fn correlates(&self, event: &erc20::Approval<'e>, tx: &TxContext<'_>) -> bool {
+        event.owner.0 == tx.from
+            && same_address(&event.spender, &self.spender)
+            && same_word(&event.value, &self.value)
+    }
+}
+
+impl<'e> Correlate<erc721::Transfer<'e>> for erc721::transferFromCall<'_> {
+    fn correlates(&self, event: &erc721::Transfer<'e>, _tx: &TxContext<'_>) -> bool {
+        same_address(&event.from, &self.from)
+            && same_address(&event.to, &self.to)
+            && same_word(&event.tokenId, &self.tokenId)
+    }
+}
+
+impl<'e> Correlate<weth::Withdrawal<'e>> for weth::withdrawCall<'_> {
+    fn correlates(&self, event: &weth::Withdrawal<'e>, tx: &TxContext<'_>) -> bool {
+        event.src.0 == tx.from && same_word(&event.wad, &self.wad)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{calldata, word};
+
+    #[test]
+    fn test_transfer_from_correlation() {
+        let sender = [0u8; 20];
+        let token = [0x22u8; 20];
+        let tx = TxContext::new(&sender, &token);
+        let data = calldata(
+            erc20::transferFromCall::SELECTOR,
+            &[word(1), word(2), word(7)],
+        );
+        let call = erc20::transferFromCall::decode_calldata(&data).unwrap();
+
+        let other_token = [0x33u8; 20];
+        let matching = [erc20::Transfer::TOPIC0, word(1), word(2)];
+        let wrong_to = [erc20::Transfer::TOPIC0, word(1), word(3)];
+        let approval = [erc20::Approval::TOPIC0, word(1), word(2)];
+        let amount = word(7);
+        let logs = [
+            ZEventLog::from_topics(&approval, &amount).with_address(&token),
+            ZEventLog::from_topics(&matching, &amount).with_address(&other_token),
+            ZEventLog::from_topics(&wrong_to, &amount).with_address(&token),
+            ZEventLog::from_topics(&matching, &amount).with_address(&token),
+        ];
+
+        let (index, event) = find_correlated::<_, erc20::Transfer, _>(&call, &tx, logs).unwrap();
+        assert_eq!(index, 3);
+        assert_eq!(event.to.as_bytes()[19], 2);
+        assert!(
+            find_correlated::<_, erc20::Transfer, _>(&call, &tx, logs[..3].iter().copied())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_sender_bound_correlation() {
+        let mut sender = [0u8; 20];
+        sender[19] = 1;
+        let stranger = [0x44u8; 20];
+        let token = [0x22u8; 20];
+        let data = calldata(erc20::approveCall::SELECTOR, &[word(2), word(9)]);
+        let call = erc20::approveCall::decode_calldata(&data).unwrap();
+
+        let topics = [erc20::Approval::TOPIC0, word(1), word(2)];
+        let amount = word(9);
+        let log = ZEventLog::from_topics(&topics, &amount);
+        let event: erc20::Approval = log.try_decode().unwrap();
+        assert!(call.correlates(&event, &TxContext::new(&sender, &token)));
+        assert!(!call.correlates(&event, &TxContext::new(&stranger, &token)));
+    }
+}
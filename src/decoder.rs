@@ -3,6 +3,26 @@ use crate::types::{ZAddress, ZU256, ZBytes, ZBool, ZString, ZArray};
 use core::str;
 use core::convert::TryInto;
 
+// With the `tracing` feature, the dynamic readers emit `trace` events for
+// each offset they follow and length they read, and a `debug` event when
+// they reject the input. Without it these compile to nothing.
+macro_rules! trace_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::trace!($($arg)*);
+    };
+}
+
+/// Report a rejected dynamic value before returning `err`.
+#[inline(always)]
+fn rejected(kind: &'static str, at: usize, err: ZError) -> ZError {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(kind, at, error = %err, "dynamic decode failed");
+    #[cfg(not(feature = "tracing"))]
+    let _ = (kind, at);
+    err
+}
+
 /// Read the 4-byte function selector from calldata.
 /// Returns a reference to the first 4 bytes.
 /// 
@@ -45,8 +65,9 @@ pub fn skip_selector(data: &[u8]) -> Result<&[u8], ZError> {
 /// Returns reference to the array to avoid copying.
 #[inline(always)]
 pub fn peek_word(data: &[u8], offset: usize) -> Result<&[u8; 32], ZError> {
-    if offset + 32 > data.len() {
-        return Err(ZError::OutOfBounds(offset + 32, data.len()));
+    match offset.checked_add(32) {
+        Some(end) if end <= data.len() => {}
+        _ => return Err(ZError::OutOfBounds(offset.saturating_add(32), data.len())),
     }
     // SAFETY: We checked bounds above. The slice matches the size of [u8; 32].
     // We cast the pointer to &[u8; 32].
@@ -59,6 +80,16 @@ pub fn peek_word(data: &[u8], offset: usize) -> Result<&[u8; 32], ZError> {
     Ok(array_ref)
 }
 
+/// Interprets a length or offset word as a `usize`. `None` if any of the
+/// upper 24 bytes are set or the value does not fit this target's `usize`.
+#[inline(always)]
+pub(crate) fn word_to_usize(word: &[u8; 32]) -> Option<usize> {
+    if word[..24].iter().any(|&b| b != 0) {
+        return None;
+    }
+    usize::try_from(u64::from_be_bytes(word[24..].try_into().unwrap())).ok()
+}
+
 /// Helper to read address (last 20 bytes of a 32-byte word).
 #[inline(always)]
 pub fn read_address_from_word(data: &[u8], offset: usize) -> Result<ZAddress<'_>, ZError> {
@@ -81,12 +112,59 @@ pub fn read_int256(data: &[u8], offset: usize) -> Result<crate::types::ZInt256<'
     Ok(crate::types::ZInt256(word))
 }
 
+/// Reads a `uintN` word for any width `bits` (a multiple of 8 up to 256),
+/// rejecting bits set above the low `bits`. Widths without a native Rust
+/// integer, such as `uint24` or `uint160`, decode this way.
+///
+/// # Panics
+/// If `bits` is not a multiple of 8 between 8 and 256.
+pub fn read_uint_bits(data: &[u8], offset: usize, bits: usize) -> Result<ZU256<'_>, ZError> {
+    assert!(bits.is_multiple_of(8) && (8..=256).contains(&bits), "invalid uint width");
+    let word = peek_word(data, offset)?;
+    if word[..32 - bits / 8].iter().any(|&b| b != 0) {
+        return Err(ZError::InvalidPadding { offset, ty: UINT_NAMES[bits / 8 - 1] });
+    }
+    Ok(ZU256(word))
+}
+
+/// Reads an `intN` word for any width `bits` (a multiple of 8 up to 256),
+/// rejecting words that are not the sign extension of their low `bits`.
+///
+/// # Panics
+/// If `bits` is not a multiple of 8 between 8 and 256.
+pub fn read_int_bits(data: &[u8], offset: usize, bits: usize) -> Result<crate::types::ZInt256<'_>, ZError> {
+    assert!(bits.is_multiple_of(8) && (8..=256).contains(&bits), "invalid int width");
+    let word = peek_word(data, offset)?;
+    let pad = 32 - bits / 8;
+    let fill = if word[pad] & 0x80 != 0 { 0xff } else { 0x00 };
+    if word[..pad].iter().any(|&b| b != fill) {
+        return Err(ZError::InvalidPadding { offset, ty: INT_NAMES[bits / 8 - 1] });
+    }
+    Ok(crate::types::ZInt256(word))
+}
+
+#[rustfmt::skip]
+pub(crate) const UINT_NAMES: [&str; 32] = [
+    "uint8", "uint16", "uint24", "uint32", "uint40", "uint48", "uint56", "uint64",
+    "uint72", "uint80", "uint88", "uint96", "uint104", "uint112", "uint120", "uint128",
+    "uint136", "uint144", "uint152", "uint160", "uint168", "uint176", "uint184", "uint192",
+    "uint200", "uint208", "uint216", "uint224", "uint232", "uint240", "uint248", "uint256",
+];
+
+#[rustfmt::skip]
+pub(crate) const INT_NAMES: [&str; 32] = [
+    "int8", "int16", "int24", "int32", "int40", "int48", "int56", "int64",
+    "int72", "int80", "int88", "int96", "int104", "int112", "int120", "int128",
+    "int136", "int144", "int152", "int160", "int168", "int176", "int184", "int192",
+    "int200", "int208", "int216", "int224", "int232", "int240", "int248", "int256",
+];
+
 #[inline(always)]
 pub fn read_u8(data: &[u8], offset: usize) -> Result<u8, ZError> {
     let word = peek_word(data, offset)?;
     // Check padding (bytes 0..31 must be 0)
     if word.iter().take(31).any(|&b| b != 0) {
-        return Err(ZError::Custom("u8 value invalid (high bits set)"));
+        return Err(ZError::InvalidPadding { offset, ty: "uint8" });
     }
     Ok(word[31])
 }
@@ -97,7 +175,7 @@ pub fn read_i8(data: &[u8], offset: usize) -> Result<i8, ZError> {
     let val = word[31] as i8;
     let padding_byte = if val < 0 { 0xff } else { 0x00 };
     if word.iter().take(31).any(|&b| b != padding_byte) {
-        return Err(ZError::Custom("i8 value invalid (bad padding)"));
+        return Err(ZError::InvalidPadding { offset, ty: "int8" });
     }
     Ok(val)
 }
@@ -106,7 +184,7 @@ pub fn read_i8(data: &[u8], offset: usize) -> Result<i8, ZError> {
 pub fn read_u16(data: &[u8], offset: usize) -> Result<u16, ZError> {
     let word = peek_word(data, offset)?;
     if !word[0..30].iter().all(|&b| b == 0) {
-        return Err(ZError::Custom("u16 value invalid (high bits set)"));
+        return Err(ZError::InvalidPadding { offset, ty: "uint16" });
     }
     Ok(u16::from_be_bytes([word[30], word[31]]))
 }
@@ -117,7 +195,7 @@ pub fn read_i16(data: &[u8], offset: usize) -> Result<i16, ZError> {
     let val = i16::from_be_bytes([word[30], word[31]]);
     let padding_byte = if val < 0 { 0xff } else { 0x00 };
     if !word[0..30].iter().all(|&b| b == padding_byte) {
-        return Err(ZError::Custom("i16 value invalid (bad padding)"));
+        return Err(ZError::InvalidPadding { offset, ty: "int16" });
     }
     Ok(val)
 }
@@ -126,7 +204,7 @@ pub fn read_i16(data: &[u8], offset: usize) -> Result<i16, ZError> {
 pub fn read_u32(data: &[u8], offset: usize) -> Result<u32, ZError> {
     let word = peek_word(data, offset)?;
     if !word[0..28].iter().all(|&b| b == 0) {
-        return Err(ZError::Custom("u32 value invalid (high bits set)"));
+        return Err(ZError::InvalidPadding { offset, ty: "uint32" });
     }
     // Safe slice access
     Ok(u32::from_be_bytes(word[28..32].try_into().unwrap()))
@@ -138,7 +216,7 @@ pub fn read_i32(data: &[u8], offset: usize) -> Result<i32, ZError> {
     let val = i32::from_be_bytes(word[28..32].try_into().unwrap());
     let padding_byte = if val < 0 { 0xff } else { 0x00 };
     if !word[0..28].iter().all(|&b| b == padding_byte) {
-        return Err(ZError::Custom("i32 value invalid (bad padding)"));
+        return Err(ZError::InvalidPadding { offset, ty: "int32" });
     }
     Ok(val)
 }
@@ -147,7 +225,7 @@ pub fn read_i32(data: &[u8], offset: usize) -> Result<i32, ZError> {
 pub fn read_u64(data: &[u8], offset: usize) -> Result<u64, ZError> {
     let word = peek_word(data, offset)?;
     if !word[0..24].iter().all(|&b| b == 0) {
-        return Err(ZError::Custom("u64 value invalid (high bits set)"));
+        return Err(ZError::InvalidPadding { offset, ty: "uint64" });
     }
     Ok(u64::from_be_bytes(word[24..32].try_into().unwrap()))
 }
@@ -158,7 +236,7 @@ pub fn read_i64(data: &[u8], offset: usize) -> Result<i64, ZError> {
     let val = i64::from_be_bytes(word[24..32].try_into().unwrap());
     let padding_byte = if val < 0 { 0xff } else { 0x00 };
     if !word[0..24].iter().all(|&b| b == padding_byte) {
-        return Err(ZError::Custom("i64 value invalid (bad padding)"));
+        return Err(ZError::InvalidPadding { offset, ty: "int64" });
     }
     Ok(val)
 }
@@ -167,7 +245,7 @@ pub fn read_i64(data: &[u8], offset: usize) -> Result<i64, ZError> {
 pub fn read_u128(data: &[u8], offset: usize) -> Result<u128, ZError> {
     let word = peek_word(data, offset)?;
     if !word[0..16].iter().all(|&b| b == 0) {
-        return Err(ZError::Custom("u128 value invalid (high bits set)"));
+        return Err(ZError::InvalidPadding { offset, ty: "uint128" });
     }
     Ok(u128::from_be_bytes(word[16..32].try_into().unwrap()))
 }
@@ -178,7 +256,7 @@ pub fn read_i128(data: &[u8], offset: usize) -> Result<i128, ZError> {
     let val = i128::from_be_bytes(word[16..32].try_into().unwrap());
     let padding_byte = if val < 0 { 0xff } else { 0x00 };
     if !word[0..16].iter().all(|&b| b == padding_byte) {
-        return Err(ZError::Custom("i128 value invalid (bad padding)"));
+        return Err(ZError::InvalidPadding { offset, ty: "int128" });
     }
     Ok(val)
 }
@@ -192,13 +270,13 @@ pub fn read_bool(data: &[u8], offset: usize) -> Result<ZBool, ZError> {
     
     let is_zero = word[0..31].iter().all(|&b| b == 0);
     if !is_zero {
-        return Err(ZError::Custom("Boolean value has dirty high bits"));
+        return Err(ZError::InvalidBool { offset });
     }
     
     match word[31] {
         0 => Ok(ZBool(false)),
         1 => Ok(ZBool(true)),
-        _ => Err(ZError::Custom("Boolean value invalid (not 0 or 1)")),
+        _ => Err(ZError::InvalidBool { offset }),
     }
 }
 
@@ -207,41 +285,49 @@ pub fn read_bool(data: &[u8], offset: usize) -> Result<ZBool, ZError> {
 /// We follow the pointer to find the length word, then the data.
 pub fn read_bytes(data: &[u8], initial_offset: usize) -> Result<ZBytes<'_>, ZError> {
     // 1. Read the relative offset from the head.
-    let offset_word = peek_word(data, initial_offset)?;
-    let data_offset_usize = usize::from_be_bytes(offset_word[24..32].try_into().unwrap()); // Last 8 bytes for usize is safe assumption for now < 2^64
+    let offset_word = peek_word(data, initial_offset).map_err(|e| rejected("bytes", initial_offset, e))?;
+    let data_offset_usize = word_to_usize(offset_word).ok_or_else(|| {
+        rejected("bytes", initial_offset, ZError::OffsetOutOfRange { at: initial_offset, target: usize::MAX, len: data.len() })
+    })?;
+    trace_event!(at = initial_offset, target = data_offset_usize, "bytes: following offset");
     
     // ABI encoding offsets are usually absolute from the start of the encoded tuple? 
     // Wait, in dynamic types, the value in the "static" part is the offset from the START of the current encoding.
     // If we assume `data` is the full encoding block.
     
     if data_offset_usize >= data.len() {
-        return Err(ZError::OutOfBounds(data_offset_usize, data.len()));
+        return Err(rejected("bytes", initial_offset, ZError::OffsetOutOfRange { at: initial_offset, target: data_offset_usize, len: data.len() }));
     }
 
     // 2. Read length of bytes at the data location.
-    let len_word = peek_word(data, data_offset_usize)?;
-    let length = usize::from_be_bytes(len_word[24..32].try_into().unwrap());
+    let len_word = peek_word(data, data_offset_usize).map_err(|e| rejected("bytes", initial_offset, e))?;
+    let length = word_to_usize(len_word)
+        .ok_or_else(|| rejected("bytes", initial_offset, ZError::OutOfBounds(usize::MAX, data.len())))?;
+    trace_event!(at = data_offset_usize, length, "bytes: read length");
 
     // 3. Read the actual data bytes.
     let start = data_offset_usize + 32;
-    let end = start + length;
-    
-    if end > data.len() {
-        return Err(ZError::OutOfBounds(end, data.len()));
-    }
+    let end = match start.checked_add(length) {
+        Some(end) if end <= data.len() => end,
+        _ => return Err(rejected("bytes", initial_offset, ZError::OutOfBounds(start.saturating_add(length), data.len()))),
+    };
     
     Ok(ZBytes(&data[start..end]))
 }
 
 pub fn read_string(data: &[u8], initial_offset: usize) -> Result<ZString<'_>, ZError> {
     let zbytes = read_bytes(data, initial_offset)?;
-    let s = str::from_utf8(zbytes.0).map_err(|_| ZError::Custom("Invalid UTF-8 string"))?;
+    let s = str::from_utf8(zbytes.0).map_err(|e| {
+        // Report the first invalid byte relative to `data`.
+        let start = zbytes.0.as_ptr() as usize - data.as_ptr() as usize;
+        rejected("string", initial_offset, ZError::InvalidUtf8 { offset: start + e.valid_up_to() })
+    })?;
     Ok(ZString(s))
 }
 
 pub fn read_array_fixed<'a, T>(data: &'a [u8], offset: usize, length: usize) -> Result<ZArray<'a, T>, ZError> {
     // Basic bounds check for the whole block
-    let end = offset + length * 32;
+    let end = block_end(offset, length);
     if end > data.len() {
         return Err(ZError::OutOfBounds(end, data.len()));
     }
@@ -251,25 +337,137 @@ pub fn read_array_fixed<'a, T>(data: &'a [u8], offset: usize, length: usize) ->
 pub fn read_array_dyn<'a, T>(data: &'a [u8], initial_offset: usize) -> Result<ZArray<'a, T>, ZError> {
     // 1. Read offset to array (relative to current position in tuple, usually passed as offset 0?)
     // No, initial_offset points to the 'Head' word containing the offset.
-    let offset_word = peek_word(data, initial_offset)?;
-    let data_offset_usize = usize::from_be_bytes(offset_word[24..32].try_into().unwrap());
+    let offset_word = peek_word(data, initial_offset).map_err(|e| rejected("array", initial_offset, e))?;
+    let data_offset_usize = word_to_usize(offset_word).ok_or_else(|| {
+        rejected("array", initial_offset, ZError::OffsetOutOfRange { at: initial_offset, target: usize::MAX, len: data.len() })
+    })?;
+    trace_event!(at = initial_offset, target = data_offset_usize, "array: following offset");
     
     if data_offset_usize >= data.len() {
-        return Err(ZError::OutOfBounds(data_offset_usize, data.len()));
+        return Err(rejected("array", initial_offset, ZError::OffsetOutOfRange { at: initial_offset, target: data_offset_usize, len: data.len() }));
     }
 
     // 2. Read length
-    let len_word = peek_word(data, data_offset_usize)?;
-    let length = usize::from_be_bytes(len_word[24..32].try_into().unwrap());
+    let len_word = peek_word(data, data_offset_usize).map_err(|e| rejected("array", initial_offset, e))?;
+    let length = word_to_usize(len_word)
+        .ok_or_else(|| rejected("array", initial_offset, ZError::OutOfBounds(usize::MAX, data.len())))?;
+    trace_event!(at = data_offset_usize, length, "array: read length");
 
     // 3. Start of data is 32 bytes after the length word
     let start_offset = data_offset_usize + 32;
     
     // Bounds check?
     // start_offset + length * 32
-    if start_offset + length * 32 > data.len() {
-        return Err(ZError::OutOfBounds(start_offset + length * 32, data.len()));
+    let end = block_end(start_offset, length);
+    if end > data.len() {
+        return Err(rejected("array", initial_offset, ZError::OutOfBounds(end, data.len())));
     }
 
     Ok(ZArray::new(data, start_offset, length))
 }
+
+/// End of `length` words starting at `start`, saturating so a forged length
+/// is reported as out of bounds instead of overflowing.
+#[inline(always)]
+fn block_end(start: usize, length: usize) -> usize {
+    length.saturating_mul(32).saturating_add(start)
+}
+
+/// Returns the encoding of a tuple whose head slot is at `offset`.
+///
+/// Static tuples are stored inline, so their encoding starts at `offset`.
+/// Dynamic tuples store an offset to their encoding instead. Either way the
+/// returned slice starts at the tuple, so member tails resolve relative to it.
+pub fn read_tuple_body(data: &[u8], offset: usize, dynamic: bool) -> Result<&[u8], ZError> {
+    let start = if dynamic {
+        let offset_word = peek_word(data, offset).map_err(|e| rejected("tuple", offset, e))?;
+        let target = word_to_usize(offset_word).ok_or_else(|| {
+            rejected("tuple", offset, ZError::OffsetOutOfRange { at: offset, target: usize::MAX, len: data.len() })
+        })?;
+        trace_event!(at = offset, target, "tuple: following offset");
+        target
+    } else {
+        offset
+    };
+    if start > data.len() {
+        return Err(rejected("tuple", offset, if dynamic {
+            ZError::OffsetOutOfRange { at: offset, target: start, len: data.len() }
+        } else {
+            ZError::OutOfBounds(start, data.len())
+        }));
+    }
+    Ok(&data[start..])
+}
+
+// Lenient readers.
+//
+// Some contracts emit non-canonical words (dirty high bits, sign padding that
+// does not match the value). These variants read the value bytes and ignore
+// the padding, matching how the EVM itself truncates narrower types.
+
+#[inline(always)]
+pub fn read_u8_lenient(data: &[u8], offset: usize) -> Result<u8, ZError> {
+    let word = peek_word(data, offset)?;
+    Ok(word[31])
+}
+
+#[inline(always)]
+pub fn read_i8_lenient(data: &[u8], offset: usize) -> Result<i8, ZError> {
+    let word = peek_word(data, offset)?;
+    Ok(word[31] as i8)
+}
+
+#[inline(always)]
+pub fn read_u16_lenient(data: &[u8], offset: usize) -> Result<u16, ZError> {
+    let word = peek_word(data, offset)?;
+    Ok(u16::from_be_bytes([word[30], word[31]]))
+}
+
+#[inline(always)]
+pub fn read_i16_lenient(data: &[u8], offset: usize) -> Result<i16, ZError> {
+    let word = peek_word(data, offset)?;
+    Ok(i16::from_be_bytes([word[30], word[31]]))
+}
+
+#[inline(always)]
+pub fn read_u32_lenient(data: &[u8], offset: usize) -> Result<u32, ZError> {
+    let word = peek_word(data, offset)?;
+    Ok(u32::from_be_bytes(word[28..32].try_into().unwrap()))
+}
+
+#[inline(always)]
+pub fn read_i32_lenient(data: &[u8], offset: usize) -> Result<i32, ZError> {
+    let word = peek_word(data, offset)?;
+    Ok(i32::from_be_bytes(word[28..32].try_into().unwrap()))
+}
+
+#[inline(always)]
+pub fn read_u64_lenient(data: &[u8], offset: usize) -> Result<u64, ZError> {
+    let word = peek_word(data, offset)?;
+    Ok(u64::from_be_bytes(word[24..32].try_into().unwrap()))
+}
+
+#[inline(always)]
+pub fn read_i64_lenient(data: &[u8], offset: usize) -> Result<i64, ZError> {
+    let word = peek_word(data, offset)?;
+    Ok(i64::from_be_bytes(word[24..32].try_into().unwrap()))
+}
+
+#[inline(always)]
+pub fn read_u128_lenient(data: &[u8], offset: usize) -> Result<u128, ZError> {
+    let word = peek_word(data, offset)?;
+    Ok(u128::from_be_bytes(word[16..32].try_into().unwrap()))
+}
+
+#[inline(always)]
+pub fn read_i128_lenient(data: &[u8], offset: usize) -> Result<i128, ZError> {
+    let word = peek_word(data, offset)?;
+    Ok(i128::from_be_bytes(word[16..32].try_into().unwrap()))
+}
+
+/// Any non-zero word decodes as `true`.
+#[inline(always)]
+pub fn read_bool_lenient(data: &[u8], offset: usize) -> Result<ZBool, ZError> {
+    let word = peek_word(data, offset)?;
+    Ok(ZBool(word.iter().any(|&b| b != 0)))
+}
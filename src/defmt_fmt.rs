@@ -0,0 +1,199 @@
+//! `defmt::Format` for the error and value types (requires the `defmt` feature).
+//!
+//! Embedded targets log over RTT without `core::fmt`: words go out as raw
+//! bytes and the host prints them as hex. Integer words that fit in 128 bits
+//! are sent as a `u128`/`i128` instead, so small amounts stay readable.
+
+use defmt::{write, Format, Formatter};
+
+use crate::error::ZError;
+use crate::owned::{Address, Bytes32, I256, U256};
+use crate::types::{ZAddress, ZArray, ZBool, ZBytes, ZFixedArray, ZInt256, ZString, ZU256};
+use crate::zbytes_fixed::ZBytesN;
+
+impl Format for ZError {
+    fn format(&self, f: Formatter<'_>) {
+        match self {
+            ZError::InvalidLength(expected, actual) => {
+                write!(
+                    f,
+                    "Invalid length: expected {=usize}, got {=usize}",
+                    expected, actual
+                )
+            }
+            ZError::OutOfBounds(idx, len) => {
+                write!(
+                    f,
+                    "Index out of bounds: index {=usize}, len {=usize}",
+                    idx, len
+                )
+            }
+            ZError::InvalidPadding { offset, ty } => {
+                write!(
+                    f,
+                    "Invalid {=str} padding in word at offset {=usize}",
+                    ty, offset
+                )
+            }
+            ZError::InvalidBool { offset } => {
+                write!(
+                    f,
+                    "Invalid bool (not 0 or 1) in word at offset {=usize}",
+                    offset
+                )
+            }
+            ZError::InvalidUtf8 { offset } => {
+                write!(f, "Invalid UTF-8 sequence at offset {=usize}", offset)
+            }
+            ZError::OffsetOutOfRange { at, target, len } => write!(
+                f,
+                "Offset at {=usize} points to {=usize}, beyond len {=usize}",
+                at, target, len
+            ),
+            ZError::SelectorMismatch { expected, found } => write!(
+                f,
+                "Selector mismatch: expected {=[u8]:02x}, found {=[u8]:02x}",
+                &expected[..],
+                &found[..]
+            ),
+            ZError::TypeMismatch { expected, found } => write!(
+                f,
+                "Event type mismatch: expected topic {=[u8]:02x}, found {=[u8]:02x}",
+                &expected[..],
+                &found[..]
+            ),
+            ZError::UnknownSelector(selector) => {
+                write!(f, "Unknown selector {=[u8]:02x}", &selector[..])
+            }
+            ZError::UnknownTopic(topic) => write!(f, "Unknown event topic {=[u8]:02x}", &topic[..]),
+            ZError::UnknownSchema(id) => write!(f, "Unknown schema id {=u32}", id),
+            ZError::Overflow { ty } => write!(f, "Value does not fit in {=str}", ty),
+            #[cfg(feature = "alloc")]
+            ZError::Field { index, source } => write!(f, "In field {=usize}: {}", index, **source),
+            #[cfg(feature = "alloc")]
+            ZError::NamedField { name, source } => {
+                write!(f, "In field `{=str}`: {}", name, **source)
+            }
+            #[cfg(feature = "alloc")]
+            ZError::AtOffset { offset, source } => {
+                write!(f, "At offset {=usize}: {}", offset, **source)
+            }
+            ZError::Custom(msg) => write!(f, "Error: {=str}", msg),
+            #[cfg(feature = "alloc")]
+            ZError::Message(msg) => write!(f, "Error: {=str}", msg.as_str()),
+        }
+    }
+}
+
+impl Format for ZAddress<'_> {
+    fn format(&self, f: Formatter<'_>) {
+        write!(f, "ZAddress({=[u8]:02x})", &self.0[..])
+    }
+}
+
+impl Format for ZU256<'_> {
+    fn format(&self, f: Formatter<'_>) {
+        match self.to_u128() {
+            Some(value) => write!(f, "ZU256({=u128})", value),
+            None => write!(f, "ZU256({=[u8]:02x})", &self.0[..]),
+        }
+    }
+}
+
+impl Format for ZInt256<'_> {
+    fn format(&self, f: Formatter<'_>) {
+        match self.to_i128() {
+            Some(value) => write!(f, "ZInt256({=i128})", value),
+            None => write!(f, "ZInt256({=[u8]:02x})", &self.0[..]),
+        }
+    }
+}
+
+impl Format for ZBool {
+    fn format(&self, f: Formatter<'_>) {
+        write!(f, "ZBool({=bool})", self.0)
+    }
+}
+
+impl Format for ZBytes<'_> {
+    fn format(&self, f: Formatter<'_>) {
+        write!(f, "ZBytes({=[u8]:02x})", self.0)
+    }
+}
+
+impl Format for ZString<'_> {
+    fn format(&self, f: Formatter<'_>) {
+        write!(f, "ZString({=str})", self.0)
+    }
+}
+
+impl<const N: usize> Format for ZBytesN<'_, N> {
+    fn format(&self, f: Formatter<'_>) {
+        write!(f, "ZBytes{=usize}({=[u8]:02x})", N, &self.0[..])
+    }
+}
+
+/// Elements are decoded lazily, so only the length is logged, as `Debug` does.
+impl<T> Format for ZArray<'_, T> {
+    fn format(&self, f: Formatter<'_>) {
+        write!(f, "ZArray(len={=usize})", self.length)
+    }
+}
+
+impl<T, const N: usize> Format for ZFixedArray<'_, T, N> {
+    fn format(&self, f: Formatter<'_>) {
+        write!(f, "ZFixedArray(len={=usize})", N)
+    }
+}
+
+impl Format for Address {
+    fn format(&self, f: Formatter<'_>) {
+        write!(f, "Address({=[u8]:02x})", &self.0[..])
+    }
+}
+
+impl Format for U256 {
+    fn format(&self, f: Formatter<'_>) {
+        match self.to_u128() {
+            Some(value) => write!(f, "U256({=u128})", value),
+            None => write!(f, "U256({=[u8]:02x})", &self.0[..]),
+        }
+    }
+}
+
+impl Format for I256 {
+    fn format(&self, f: Formatter<'_>) {
+        match self.as_view().to_i128() {
+            Some(value) => write!(f, "I256({=i128})", value),
+            None => write!(f, "I256({=[u8]:02x})", &self.0[..]),
+        }
+    }
+}
+
+impl Format for Bytes32 {
+    fn format(&self, f: Formatter<'_>) {
+        write!(f, "Bytes32({=[u8]:02x})", &self.0[..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_format<T: Format>() {}
+
+    #[test]
+    fn test_types_are_format() {
+        assert_format::<ZError>();
+        assert_format::<ZAddress<'static>>();
+        assert_format::<ZU256<'static>>();
+        assert_format::<ZInt256<'static>>();
+        assert_format::<ZBool>();
+        assert_format::<ZBytes<'static>>();
+        assert_format::<ZString<'static>>();
+        assert_format::<ZBytesN<'static, 4>>();
+        assert_format::<ZArray<'static, ZU256<'static>>>();
+        assert_format::<ZFixedArray<'static, ZBool, 2>>();
+        assert_format::<(Address, U256, I256, Bytes32)>();
+    }
+}
@@ -0,0 +1,1169 @@
+//! Runtime-typed decoding.
+//!
+//! When the contracts are not known at compile time, parse the Solidity
+//! types at runtime into a [`DynType`] and decode against it with
+//! [`decode_dyn`], or go straight from a function signature with
+//! [`decode_by_signature`]. The resulting [`DynValue`] tree still borrows
+//! words, byte strings and strings from the input; only the tree itself is
+//! allocated.
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::decoder;
+use crate::error::ZError;
+use crate::types::{ZAddress, ZBytes, ZInt256, ZString, ZU256};
+
+/// A Solidity type, parsed at runtime.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DynType {
+    /// `uintN`, holding `N` (8 to 256 in steps of 8).
+    Uint(usize),
+    /// `intN`, holding `N` (8 to 256 in steps of 8).
+    Int(usize),
+    Address,
+    Bool,
+    /// Dynamic `bytes`.
+    Bytes,
+    String,
+    /// `bytesN`, holding `N` (1 to 32).
+    FixedBytes(usize),
+    /// `T[]`.
+    Array(Box<DynType>),
+    /// `T[N]`.
+    FixedArray(Box<DynType>, usize),
+    /// `(T1,T2,...)`.
+    Tuple(Vec<DynType>),
+}
+
+impl DynType {
+    /// Parse a Solidity type such as `"uint256"`, `"(address,bytes)[]"` or
+    /// `"tuple(uint8,string)[2]"`.
+    ///
+    /// `uint` and `int` are accepted as aliases for their 256-bit forms and
+    /// whitespace between tokens is ignored; [`Display`](fmt::Display)
+    /// writes the canonical form back out. Tuples and array suffixes nested
+    /// more than [`MAX_TYPE_DEPTH`] levels deep are rejected, so untrusted
+    /// input cannot exhaust the stack.
+    pub fn parse(s: &str) -> Result<DynType, ZError> {
+        let mut parser = Parser {
+            input: s.as_bytes(),
+            pos: 0,
+            depth: 0,
+            too_deep: false,
+        };
+        match parser.parse_type() {
+            Some(ty) if parser.at_end() => Ok(ty),
+            _ if parser.too_deep => Err(ZError::Message(format!(
+                "Solidity type nested more than {} levels deep",
+                MAX_TYPE_DEPTH
+            ))),
+            _ => Err(ZError::Message(format!("invalid Solidity type: {}", s))),
+        }
+    }
+
+    /// Check the sizes in the descriptor tree: `uintN`/`intN` with `N` a
+    /// multiple of 8 up to 256, `bytesN` with `N` from 1 to 32, and fixed
+    /// arrays of at least one element. [`DynType::parse`] only produces
+    /// valid trees; this is for descriptors built by hand.
+    pub fn validate(&self) -> Result<(), ZError> {
+        check_leaf(self)?;
+        match self {
+            DynType::FixedArray(_, 0) => Err(invalid_type(self)),
+            DynType::Array(elem) | DynType::FixedArray(elem, _) => elem.validate(),
+            DynType::Tuple(members) => members.iter().try_for_each(DynType::validate),
+            _ => Ok(()),
+        }
+    }
+
+    /// The exact string Solidity hashes for a function, event or error
+    /// called `name` with this parameter tuple, e.g.
+    /// `"transfer(address,uint256)"`. Fails if `self` is not a tuple or does
+    /// not [`validate`](DynType::validate).
+    pub fn canonical_signature(&self, name: &str) -> Result<String, ZError> {
+        if !matches!(self, DynType::Tuple(_)) {
+            return Err(ZError::Message(format!(
+                "parameter list is not a tuple: {}",
+                self
+            )));
+        }
+        self.validate()?;
+        Ok(format!("{}{}", name, self))
+    }
+
+    /// Whether values of this type are stored behind an offset word.
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            DynType::Bytes | DynType::String | DynType::Array(_) => true,
+            DynType::FixedArray(elem, _) => elem.is_dynamic(),
+            DynType::Tuple(members) => members.iter().any(DynType::is_dynamic),
+            _ => false,
+        }
+    }
+
+    /// Bytes the type occupies in the head of an enclosing tuple, saturating
+    /// at `usize::MAX` for fixed arrays too large to ever be encoded.
+    pub fn head_size(&self) -> usize {
+        if self.is_dynamic() {
+            return 32;
+        }
+        match self {
+            DynType::FixedArray(elem, n) => elem.head_size().saturating_mul(*n),
+            DynType::Tuple(members) => members
+                .iter()
+                .map(DynType::head_size)
+                .fold(0, usize::saturating_add),
+            _ => 32,
+        }
+    }
+}
+
+/// Writes the canonical type string, as used in signatures.
+impl fmt::Display for DynType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DynType::Uint(bits) => write!(f, "uint{}", bits),
+            DynType::Int(bits) => write!(f, "int{}", bits),
+            DynType::Address => f.write_str("address"),
+            DynType::Bool => f.write_str("bool"),
+            DynType::Bytes => f.write_str("bytes"),
+            DynType::String => f.write_str("string"),
+            DynType::FixedBytes(n) => write!(f, "bytes{}", n),
+            DynType::Array(elem) => write!(f, "{}[]", elem),
+            DynType::FixedArray(elem, n) => write!(f, "{}[{}]", elem, n),
+            DynType::Tuple(members) => {
+                f.write_str("(")?;
+                for (i, member) in members.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}", member)?;
+                }
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+/// Deepest nesting of tuples and arrays [`DynType::parse`] accepts.
+pub const MAX_TYPE_DEPTH: usize = 64;
+
+/// A decoded value of a [`DynType`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DynValue<'a> {
+    Uint(ZU256<'a>),
+    Int(ZInt256<'a>),
+    Address(ZAddress<'a>),
+    Bool(bool),
+    Bytes(ZBytes<'a>),
+    String(ZString<'a>),
+    /// The `N` value bytes of a `bytesN`, without padding.
+    FixedBytes(&'a [u8]),
+    /// Elements of a `T[]` or `T[N]`.
+    Array(Vec<DynValue<'a>>),
+    Tuple(Vec<DynValue<'a>>),
+}
+
+impl<'a> DynValue<'a> {
+    /// The word of a `uintN`.
+    pub fn as_uint(&self) -> Option<ZU256<'a>> {
+        match self {
+            DynValue::Uint(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// The word of an `intN`.
+    pub fn as_int(&self) -> Option<ZInt256<'a>> {
+        match self {
+            DynValue::Int(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_address(&self) -> Option<ZAddress<'a>> {
+        match self {
+            DynValue::Address(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            DynValue::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// The contents of a `bytes` or `bytesN`.
+    pub fn as_bytes(&self) -> Option<&'a [u8]> {
+        match self {
+            DynValue::Bytes(v) => Some(v.0),
+            DynValue::FixedBytes(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&'a str> {
+        match self {
+            DynValue::String(v) => Some(v.0),
+            _ => None,
+        }
+    }
+
+    /// The elements of an array or the members of a tuple.
+    pub fn as_slice(&self) -> Option<&[DynValue<'a>]> {
+        match self {
+            DynValue::Array(items) | DynValue::Tuple(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Element or member `index` of an array or tuple.
+    pub fn get(&self, index: usize) -> Option<&DynValue<'a>> {
+        self.as_slice()?.get(index)
+    }
+}
+
+/// Human-readable form for explorers and logs: integers in decimal, addresses
+/// and byte strings as `0x` hex, strings quoted, e.g.
+/// `(0x1111…1111, 100, [true, false], "gm")`.
+impl<'a> fmt::Display for DynValue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DynValue::Uint(v) => write!(f, "{}", v),
+            DynValue::Int(v) => write!(f, "{}", v),
+            DynValue::Address(v) => write!(f, "{}", v),
+            DynValue::Bool(v) => write!(f, "{}", v),
+            DynValue::Bytes(v) => write!(f, "{}", v),
+            DynValue::String(v) => write!(f, "{:?}", v.0),
+            DynValue::FixedBytes(v) => write!(f, "{}", ZBytes(v)),
+            DynValue::Array(items) => write_list(f, "[", items, "]"),
+            DynValue::Tuple(items) => write_list(f, "(", items, ")"),
+        }
+    }
+}
+
+fn write_list(
+    f: &mut fmt::Formatter<'_>,
+    open: &str,
+    items: &[DynValue<'_>],
+    close: &str,
+) -> fmt::Result {
+    f.write_str(open)?;
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{}", item)?;
+    }
+    f.write_str(close)
+}
+
+impl<'a> DynValue<'a> {
+    /// Multi-line rendering for display to users: one element per line,
+    /// nested values indented by two spaces, addresses EIP-55 checksummed
+    /// when the `keccak` feature is enabled.
+    pub fn pretty(&self) -> PrettyValue<'_, 'a> {
+        PrettyValue(self)
+    }
+
+    /// Render as JSON text. Integers become decimal strings, since they
+    /// routinely exceed what JSON numbers carry exactly; addresses and byte
+    /// strings become hex strings, arrays and tuples become arrays.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        write_json(&mut out, self).expect("writing to a String cannot fail");
+        out
+    }
+}
+
+/// See [`DynValue::pretty`].
+pub struct PrettyValue<'v, 'a>(&'v DynValue<'a>);
+
+impl fmt::Display for PrettyValue<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_pretty(f, self.0, 0)
+    }
+}
+
+pub(crate) fn write_pretty<W: fmt::Write>(
+    out: &mut W,
+    value: &DynValue<'_>,
+    depth: usize,
+) -> fmt::Result {
+    let (open, items, close) = match value {
+        DynValue::Array(items) => ("[", items, "]"),
+        DynValue::Tuple(items) => ("(", items, ")"),
+        DynValue::Address(address) => return write_address(out, *address),
+        scalar => return write!(out, "{}", scalar),
+    };
+    if items.is_empty() {
+        return write!(out, "{}{}", open, close);
+    }
+    out.write_str(open)?;
+    for (i, item) in items.iter().enumerate() {
+        out.write_str(if i == 0 { "\n" } else { ",\n" })?;
+        write!(out, "{:1$}", "", (depth + 1) * 2)?;
+        write_pretty(out, item, depth + 1)?;
+    }
+    write!(out, "\n{:1$}{2}", "", depth * 2, close)
+}
+
+fn write_json<W: fmt::Write>(out: &mut W, value: &DynValue<'_>) -> fmt::Result {
+    match value {
+        DynValue::Uint(v) => write!(out, "\"{}\"", v),
+        DynValue::Int(v) => write!(out, "\"{}\"", v),
+        DynValue::Address(v) => {
+            out.write_char('"')?;
+            write_address(out, *v)?;
+            out.write_char('"')
+        }
+        DynValue::Bool(v) => write!(out, "{}", v),
+        DynValue::Bytes(v) => write!(out, "\"{}\"", v),
+        DynValue::FixedBytes(v) => write!(out, "\"{}\"", ZBytes(v)),
+        DynValue::String(v) => {
+            out.write_char('"')?;
+            for c in v.0.chars() {
+                match c {
+                    '"' => out.write_str("\\\"")?,
+                    '\\' => out.write_str("\\\\")?,
+                    '\n' => out.write_str("\\n")?,
+                    '\r' => out.write_str("\\r")?,
+                    '\t' => out.write_str("\\t")?,
+                    c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+                    c => out.write_char(c)?,
+                }
+            }
+            out.write_char('"')
+        }
+        DynValue::Array(items) | DynValue::Tuple(items) => {
+            out.write_char('[')?;
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.write_char(',')?;
+                }
+                write_json(out, item)?;
+            }
+            out.write_char(']')
+        }
+    }
+}
+
+fn write_address<W: fmt::Write>(out: &mut W, address: ZAddress<'_>) -> fmt::Result {
+    #[cfg(feature = "keccak")]
+    {
+        out.write_str(address.to_checksum(&mut [0u8; 42]))
+    }
+    #[cfg(not(feature = "keccak"))]
+    {
+        write!(out, "{}", address)
+    }
+}
+
+/// Decode a value of type `ty` whose head slot is at `offset`, with the same
+/// conventions and checks as [`ZDecode::decode`](crate::ZDecode::decode).
+pub fn decode_dyn<'a>(ty: &DynType, data: &'a [u8], offset: usize) -> Result<DynValue<'a>, ZError> {
+    check_leaf(ty)?;
+    match ty {
+        DynType::Uint(bits) => decoder::read_uint_bits(data, offset, *bits).map(DynValue::Uint),
+        DynType::Int(bits) => decoder::read_int_bits(data, offset, *bits).map(DynValue::Int),
+        DynType::Address => decoder::read_address_from_word(data, offset).map(DynValue::Address),
+        DynType::Bool => decoder::read_bool(data, offset).map(|b| DynValue::Bool(b.0)),
+        DynType::Bytes => decoder::read_bytes(data, offset).map(DynValue::Bytes),
+        DynType::String => decoder::read_string(data, offset).map(DynValue::String),
+        DynType::FixedBytes(n) => {
+            let word = decoder::peek_word(data, offset)?;
+            if word[*n..].iter().any(|&b| b != 0) {
+                return Err(ZError::InvalidPadding {
+                    offset,
+                    ty: BYTES_NAMES[n - 1],
+                });
+            }
+            Ok(DynValue::FixedBytes(&word[..*n]))
+        }
+        DynType::FixedArray(elem, n) => {
+            let body = decoder::read_tuple_body(data, offset, ty.is_dynamic())?;
+            check_heads(elem, *n, body, 0)?;
+            decode_sequence(core::iter::repeat_n(&**elem, *n), body).map(DynValue::Array)
+        }
+        DynType::Array(elem) => {
+            let body = decoder::read_tuple_body(data, offset, true)?;
+            let len_word = decoder::peek_word(body, 0)?;
+            let length = decoder::word_to_usize(len_word)
+                .ok_or(ZError::OutOfBounds(usize::MAX, body.len()))?;
+            check_heads(elem, length, body, 32)?;
+            decode_sequence(core::iter::repeat_n(&**elem, length), &body[32..]).map(DynValue::Array)
+        }
+        DynType::Tuple(members) => {
+            let body = decoder::read_tuple_body(data, offset, ty.is_dynamic())?;
+            decode_sequence(members.iter(), body).map(DynValue::Tuple)
+        }
+    }
+}
+
+/// Decode a complete parameter encoding (calldata after the selector, return
+/// data or event data) against a tuple type, like
+/// [`ZDecode::decode_params`](crate::ZDecode::decode_params): the tuple starts
+/// at `data[0]` even when it is dynamic. Other types decode at offset 0.
+pub fn decode_dyn_params<'a>(ty: &DynType, data: &'a [u8]) -> Result<DynValue<'a>, ZError> {
+    match ty {
+        DynType::Tuple(members) => decode_sequence(members.iter(), data).map(DynValue::Tuple),
+        _ => decode_dyn(ty, data, 0),
+    }
+}
+
+/// Decode calldata against a function signature such as
+/// `"transfer(address,uint256)"`, returning the arguments as a
+/// [`DynValue::Tuple`].
+///
+/// The types are parsed with [`DynType::parse`]. With the `keccak` feature
+/// the calldata selector must match the signature's, otherwise it is skipped
+/// unchecked.
+pub fn decode_by_signature<'a>(
+    signature: &str,
+    calldata: &'a [u8],
+) -> Result<DynValue<'a>, ZError> {
+    let (name, params) = parse_signature(signature)?;
+    let selector = decoder::read_selector(calldata)?;
+    #[cfg(feature = "keccak")]
+    {
+        let canonical = params.canonical_signature(name)?;
+        let hash = crate::hash::keccak256(canonical.as_bytes());
+        let expected: [u8; 4] = hash[..4].try_into().unwrap();
+        if *selector != expected {
+            return Err(ZError::SelectorMismatch {
+                expected,
+                found: *selector,
+            });
+        }
+    }
+    #[cfg(not(feature = "keccak"))]
+    let _ = (name, selector);
+
+    decode_dyn_params(&params, &calldata[4..])
+}
+
+/// Split a signature such as `"transfer(address,uint256)"` into its trimmed
+/// name and parameter tuple.
+pub(crate) fn parse_signature(signature: &str) -> Result<(&str, DynType), ZError> {
+    let invalid = || ZError::Message(format!("invalid signature: {}", signature));
+    let open = signature.find('(').ok_or_else(invalid)?;
+    let name = signature[..open].trim();
+    if name.is_empty() {
+        return Err(invalid());
+    }
+    match DynType::parse(&signature[open..])? {
+        params @ DynType::Tuple(_) => Ok((name, params)),
+        _ => Err(invalid()),
+    }
+}
+
+/// Append the canonical encoding of `value` to `buf`, the inverse of
+/// [`decode_dyn`] at offset 0: a dynamic value is preceded by its offset
+/// word, as it would be as the only member of a tuple.
+///
+/// The type is needed alongside the value since a [`DynValue::Array`] does
+/// not say whether it is a `T[]` or a `T[N]`. Fails if the value does not
+/// fit the type, e.g. a `uint8` over 255 or an array of the wrong length;
+/// `buf` is left unchanged then.
+pub fn encode_dyn(ty: &DynType, value: &DynValue<'_>, buf: &mut Vec<u8>) -> Result<(), ZError> {
+    buf.extend(encode_sequence(
+        core::iter::once(ty),
+        core::slice::from_ref(value),
+    )?);
+    Ok(())
+}
+
+/// Append a complete parameter encoding, the inverse of
+/// [`decode_dyn_params`]: a tuple's members start at the first byte.
+/// Prefix a selector to build calldata.
+pub fn encode_dyn_params(
+    ty: &DynType,
+    value: &DynValue<'_>,
+    buf: &mut Vec<u8>,
+) -> Result<(), ZError> {
+    match (ty, value) {
+        (DynType::Tuple(members), DynValue::Tuple(items)) if members.len() == items.len() => {
+            buf.extend(encode_sequence(members.iter(), items)?);
+            Ok(())
+        }
+        (DynType::Tuple(_), _) => Err(mismatch(ty)),
+        _ => encode_dyn(ty, value, buf),
+    }
+}
+
+/// Encode values as consecutive tuple members: heads first, then the tails
+/// of the dynamic ones in order.
+fn encode_sequence<'t>(
+    types: impl Iterator<Item = &'t DynType> + Clone,
+    values: &[DynValue<'_>],
+) -> Result<Vec<u8>, ZError> {
+    let head_len = types
+        .clone()
+        .map(DynType::head_size)
+        .fold(0, usize::saturating_add);
+    // Values are checked against the types below, so an absurd head size
+    // must not be preallocated up front.
+    let mut heads = Vec::with_capacity(head_len.min(values.len().saturating_mul(32)));
+    let mut tails = Vec::new();
+    for (i, (ty, value)) in types.zip(values).enumerate() {
+        if ty.is_dynamic() {
+            heads.extend_from_slice(&usize_word(head_len.saturating_add(tails.len())));
+            encode_tail(ty, value, &mut tails).map_err(|e| e.in_field(i))?;
+        } else {
+            encode_static(ty, value, &mut heads).map_err(|e| e.in_field(i))?;
+        }
+    }
+    heads.extend(tails);
+    Ok(heads)
+}
+
+fn encode_static(ty: &DynType, value: &DynValue<'_>, out: &mut Vec<u8>) -> Result<(), ZError> {
+    check_leaf(ty)?;
+    match (ty, value) {
+        (DynType::Uint(bits), DynValue::Uint(v))
+            if v.0[..32 - bits / 8].iter().all(|&b| b == 0) =>
+        {
+            out.extend_from_slice(v.0);
+        }
+        (DynType::Int(bits), DynValue::Int(v)) => {
+            let pad = 32 - bits / 8;
+            let fill = if v.0[pad] & 0x80 != 0 { 0xff } else { 0x00 };
+            if v.0[..pad].iter().any(|&b| b != fill) {
+                return Err(mismatch(ty));
+            }
+            out.extend_from_slice(v.0);
+        }
+        (DynType::Address, DynValue::Address(v)) => {
+            out.extend_from_slice(&[0u8; 12]);
+            out.extend_from_slice(v.0);
+        }
+        (DynType::Bool, DynValue::Bool(v)) => out.extend_from_slice(&usize_word(*v as usize)),
+        (DynType::FixedBytes(n), DynValue::FixedBytes(v)) if v.len() == *n => {
+            let mut word = [0u8; 32];
+            word[..*n].copy_from_slice(v);
+            out.extend_from_slice(&word);
+        }
+        (DynType::FixedArray(elem, n), DynValue::Array(items)) if items.len() == *n => {
+            out.extend(encode_sequence(core::iter::repeat_n(&**elem, *n), items)?);
+        }
+        (DynType::Tuple(members), DynValue::Tuple(items)) if members.len() == items.len() => {
+            out.extend(encode_sequence(members.iter(), items)?);
+        }
+        _ => return Err(mismatch(ty)),
+    }
+    Ok(())
+}
+
+fn encode_tail(ty: &DynType, value: &DynValue<'_>, out: &mut Vec<u8>) -> Result<(), ZError> {
+    let bytes = match (ty, value) {
+        (DynType::Bytes, DynValue::Bytes(v)) => v.0,
+        (DynType::String, DynValue::String(v)) => v.0.as_bytes(),
+        (DynType::Array(elem), DynValue::Array(items)) => {
+            out.extend_from_slice(&usize_word(items.len()));
+            out.extend(encode_sequence(
+                core::iter::repeat_n(&**elem, items.len()),
+                items,
+            )?);
+            return Ok(());
+        }
+        // Dynamic fixed arrays and tuples have no length word.
+        _ => return encode_static(ty, value, out),
+    };
+    out.extend_from_slice(&usize_word(bytes.len()));
+    out.extend_from_slice(bytes);
+    out.resize(out.len() + (32 - bytes.len() % 32) % 32, 0);
+    Ok(())
+}
+
+/// Reject out-of-range sizes on this node only, so the readers and writers
+/// can index by them.
+fn check_leaf(ty: &DynType) -> Result<(), ZError> {
+    match ty {
+        DynType::Uint(bits) | DynType::Int(bits) if *bits == 0 || *bits > 256 || bits % 8 != 0 => {
+            Err(invalid_type(ty))
+        }
+        DynType::FixedBytes(n) if *n == 0 || *n > 32 => Err(invalid_type(ty)),
+        _ => Ok(()),
+    }
+}
+
+fn invalid_type(ty: &DynType) -> ZError {
+    ZError::Message(format!("invalid Solidity type: {}", ty))
+}
+
+fn usize_word(n: usize) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&(n as u64).to_be_bytes());
+    word
+}
+
+fn mismatch(ty: &DynType) -> ZError {
+    ZError::Message(format!("value does not fit type {}", ty))
+}
+
+/// Length of the canonical encoding of `values` as consecutive members of a
+/// tuple, i.e. what a standard encoder would produce for them.
+pub(crate) fn canonical_len<'t>(
+    types: impl Iterator<Item = &'t DynType>,
+    values: &[DynValue<'_>],
+) -> usize {
+    types
+        .zip(values)
+        .map(|(ty, value)| {
+            ty.head_size()
+                + if ty.is_dynamic() {
+                    tail_len(ty, value)
+                } else {
+                    0
+                }
+        })
+        .sum()
+}
+
+/// Bytes a dynamic value occupies behind its offset word.
+fn tail_len(ty: &DynType, value: &DynValue<'_>) -> usize {
+    match (ty, value) {
+        (DynType::Bytes, DynValue::Bytes(v)) => 32 + v.0.len().div_ceil(32) * 32,
+        (DynType::String, DynValue::String(v)) => 32 + v.0.len().div_ceil(32) * 32,
+        (DynType::Array(elem), DynValue::Array(items)) => {
+            32 + canonical_len(core::iter::repeat_n(&**elem, items.len()), items)
+        }
+        (DynType::FixedArray(elem, n), DynValue::Array(items)) => {
+            canonical_len(core::iter::repeat_n(&**elem, *n), items)
+        }
+        (DynType::Tuple(members), DynValue::Tuple(items)) => canonical_len(members.iter(), items),
+        _ => 0,
+    }
+}
+
+/// Decode consecutive head slots starting at `body[0]`; dynamic members
+/// resolve their offsets relative to `body`.
+fn decode_sequence<'a, 't>(
+    types: impl Iterator<Item = &'t DynType>,
+    body: &'a [u8],
+) -> Result<Vec<DynValue<'a>>, ZError> {
+    let mut values = Vec::new();
+    let mut head = 0;
+    for (i, ty) in types.enumerate() {
+        values.push(decode_dyn(ty, body, head).map_err(|e| e.in_field(i))?);
+        head = head.saturating_add(ty.head_size());
+    }
+    Ok(values)
+}
+
+/// Reject element counts whose heads cannot fit after `start`, before
+/// allocating for them. Zero-sized elements count as one byte so a forged
+/// length cannot spin on an empty tuple.
+fn check_heads(elem: &DynType, count: usize, body: &[u8], start: usize) -> Result<(), ZError> {
+    let end = count
+        .saturating_mul(elem.head_size().max(1))
+        .saturating_add(start);
+    if end > body.len() {
+        return Err(ZError::OutOfBounds(end, body.len()));
+    }
+    Ok(())
+}
+
+#[rustfmt::skip]
+const BYTES_NAMES: [&str; 32] = [
+    "bytes1", "bytes2", "bytes3", "bytes4", "bytes5", "bytes6", "bytes7", "bytes8",
+    "bytes9", "bytes10", "bytes11", "bytes12", "bytes13", "bytes14", "bytes15", "bytes16",
+    "bytes17", "bytes18", "bytes19", "bytes20", "bytes21", "bytes22", "bytes23", "bytes24",
+    "bytes25", "bytes26", "bytes27", "bytes28", "bytes29", "bytes30", "bytes31", "bytes32",
+];
+
+/// Recursive-descent parser over a type string.
+struct Parser<'s> {
+    input: &'s [u8],
+    pos: usize,
+    /// Tuples and array suffixes currently open.
+    depth: usize,
+    too_deep: bool,
+}
+
+impl<'s> Parser<'s> {
+    fn skip_whitespace(&mut self) {
+        while self
+            .input
+            .get(self.pos)
+            .is_some_and(u8::is_ascii_whitespace)
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_whitespace();
+        self.pos == self.input.len()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        if self.input.get(self.pos) == Some(&byte) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// A run of bytes matching `pred`, possibly empty.
+    fn take_while(&mut self, pred: impl Fn(u8) -> bool) -> &'s [u8] {
+        let start = self.pos;
+        while self.input.get(self.pos).is_some_and(|&b| pred(b)) {
+            self.pos += 1;
+        }
+        &self.input[start..self.pos]
+    }
+
+    fn parse_number(&mut self) -> Option<usize> {
+        let digits = self.take_while(|b| b.is_ascii_digit());
+        if digits.is_empty() || (digits[0] == b'0' && digits.len() > 1) {
+            return None;
+        }
+        core::str::from_utf8(digits).ok()?.parse().ok()
+    }
+
+    /// Open one more level of nesting; `None` past [`MAX_TYPE_DEPTH`].
+    fn descend(&mut self) -> Option<()> {
+        self.depth += 1;
+        self.too_deep |= self.depth > MAX_TYPE_DEPTH;
+        (!self.too_deep).then_some(())
+    }
+
+    fn parse_type(&mut self) -> Option<DynType> {
+        let outer = self.depth;
+        let mut ty = self.parse_base()?;
+        while self.eat(b'[') {
+            self.descend()?;
+            self.skip_whitespace();
+            if self.eat(b']') {
+                ty = DynType::Array(Box::new(ty));
+            } else {
+                let n = self.parse_number().filter(|&n| n > 0)?;
+                if !self.eat(b']') {
+                    return None;
+                }
+                ty = DynType::FixedArray(Box::new(ty), n);
+            }
+        }
+        self.depth = outer;
+        Some(ty)
+    }
+
+    fn parse_base(&mut self) -> Option<DynType> {
+        self.skip_whitespace();
+        let name = self.take_while(|b| b.is_ascii_lowercase());
+        let ty = match name {
+            b"" | b"tuple" => return self.parse_tuple(),
+            b"address" => DynType::Address,
+            b"bool" => DynType::Bool,
+            b"string" => DynType::String,
+            b"uint" | b"int" => {
+                let bits = if self.input.get(self.pos).is_some_and(u8::is_ascii_digit) {
+                    self.parse_number()
+                        .filter(|&n| n > 0 && n <= 256 && n % 8 == 0)?
+                } else {
+                    256
+                };
+                if name == b"uint" {
+                    DynType::Uint(bits)
+                } else {
+                    DynType::Int(bits)
+                }
+            }
+            b"bytes" => {
+                if self.input.get(self.pos).is_some_and(u8::is_ascii_digit) {
+                    DynType::FixedBytes(self.parse_number().filter(|&n| n > 0 && n <= 32)?)
+                } else {
+                    DynType::Bytes
+                }
+            }
+            _ => return None,
+        };
+        Some(ty)
+    }
+
+    fn parse_tuple(&mut self) -> Option<DynType> {
+        if !self.eat(b'(') {
+            return None;
+        }
+        self.descend()?;
+        let mut members = Vec::new();
+        if self.eat(b')') {
+            self.depth -= 1;
+            return Some(DynType::Tuple(members));
+        }
+        loop {
+            members.push(self.parse_type()?);
+            if self.eat(b')') {
+                self.depth -= 1;
+                return Some(DynType::Tuple(members));
+            }
+            if !self.eat(b',') {
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::word;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[test]
+    fn test_parse_types() {
+        assert_eq!(DynType::parse("uint").unwrap(), DynType::Uint(256));
+        assert_eq!(DynType::parse("int8").unwrap(), DynType::Int(8));
+        assert_eq!(DynType::parse("bytes4").unwrap(), DynType::FixedBytes(4));
+        let ty = DynType::parse("tuple(address, bytes)[2][]").unwrap();
+        assert_eq!(ty.to_string(), "(address,bytes)[2][]");
+        assert!(ty.is_dynamic());
+        assert_eq!(DynType::parse("(uint8,bool[3])").unwrap().head_size(), 128);
+        assert_eq!(DynType::parse("()").unwrap(), DynType::Tuple(vec![]));
+
+        for bad in [
+            "uint7",
+            "uint264",
+            "bytes0",
+            "bytes33",
+            "uint256[0]",
+            "(uint256",
+            "uint256,",
+            "foo",
+            "uint08",
+            "",
+        ] {
+            assert!(DynType::parse(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_huge_fixed_arrays() {
+        let ty = DynType::parse("(uint256[18446744073709551615],uint256[18446744073709551615])")
+            .unwrap();
+        assert_eq!(ty.head_size(), usize::MAX);
+        assert!(matches!(
+            decode_dyn_params(&ty, &[0u8; 64]),
+            Err(ZError::Field { index: 0, .. })
+        ));
+        let value = DynValue::Tuple(vec![DynValue::Array(vec![]), DynValue::Array(vec![])]);
+        assert!(encode_dyn_params(&ty, &value, &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_parse_depth_limit() {
+        let nested = |n: usize| format!("{}uint8{}", "(".repeat(n), ")".repeat(n));
+        assert!(DynType::parse(&nested(MAX_TYPE_DEPTH)).is_ok());
+        assert!(DynType::parse(&format!("uint8{}", "[]".repeat(MAX_TYPE_DEPTH))).is_ok());
+        // Siblings do not add up.
+        assert!(DynType::parse(&format!(
+            "({},{})",
+            nested(MAX_TYPE_DEPTH - 1),
+            nested(MAX_TYPE_DEPTH - 1)
+        ))
+        .is_ok());
+
+        for deep in [
+            nested(MAX_TYPE_DEPTH + 1),
+            nested(200_000),
+            format!("uint8{}", "[]".repeat(200_000)),
+        ] {
+            match DynType::parse(&deep) {
+                Err(ZError::Message(msg)) => assert!(msg.contains("nested"), "{}", msg),
+                other => panic!("{:?}", other),
+            }
+        }
+        assert!(parse_signature(&format!("f{}", nested(200_000))).is_err());
+    }
+
+    #[test]
+    fn test_decode_dyn() {
+        // (uint8, bytes, int16[2]) = (7, 0xbeef, [-1, 2])
+        let mut data = [0u8; 192];
+        data[..32].copy_from_slice(&word(7));
+        data[32..64].copy_from_slice(&word(128));
+        data[64..96].copy_from_slice(&[0xff; 32]);
+        data[96..128].copy_from_slice(&word(2));
+        data[128..160].copy_from_slice(&word(2));
+        data[160..162].copy_from_slice(&[0xbe, 0xef]);
+
+        // The tuple is dynamic, so at offset 0 it sits behind an offset word.
+        let mut wrapped = [0u8; 224];
+        wrapped[..32].copy_from_slice(&word(32));
+        wrapped[32..].copy_from_slice(&data);
+
+        let ty = DynType::parse("(uint8,bytes,int16[2])").unwrap();
+        let value = decode_dyn(&ty, &wrapped, 0).unwrap();
+        let DynValue::Tuple(members) = value else {
+            panic!("not a tuple")
+        };
+        assert_eq!(members[0], DynValue::Uint(ZU256(&word(7))));
+        assert_eq!(members[1], DynValue::Bytes(ZBytes(&[0xbe, 0xef])));
+        let DynValue::Array(elems) = &members[2] else {
+            panic!("not an array")
+        };
+        assert_eq!(elems[0], DynValue::Int(ZInt256(&[0xff; 32])));
+        assert_eq!(elems[1], DynValue::Int(ZInt256(&word(2))));
+
+        wrapped[62] = 1; // uint8 with dirty padding
+        assert!(matches!(
+            decode_dyn(&ty, &wrapped, 0),
+            Err(ZError::Field { index: 0, ref source }) if matches!(**source, ZError::InvalidPadding { ty: "uint8", .. })
+        ));
+    }
+
+    #[test]
+    fn test_accessors_and_display() {
+        // (bool[], string) params = ([true, false], "gm")
+        let mut data = [0u8; 224];
+        data[31] = 64;
+        data[63] = 160;
+        data[95] = 2;
+        data[127] = 1;
+        data[191] = 2;
+        data[192..194].copy_from_slice(b"gm");
+
+        let ty = DynType::parse("(bool[],string)").unwrap();
+        let value = decode_dyn_params(&ty, &data).unwrap();
+        assert_eq!(
+            value
+                .get(0)
+                .and_then(|v| v.get(0))
+                .and_then(DynValue::as_bool),
+            Some(true)
+        );
+        assert_eq!(value.get(1).and_then(DynValue::as_str), Some("gm"));
+        assert_eq!(
+            value.get(0).and_then(DynValue::as_slice).map(<[_]>::len),
+            Some(2)
+        );
+        assert_eq!(value.get(1).and_then(DynValue::as_uint), None);
+        assert_eq!(value.to_string(), "([true, false], \"gm\")");
+
+        let amount = word(100);
+        assert_eq!(DynValue::Uint(ZU256(&amount)).to_string(), "100");
+        assert_eq!(DynValue::Int(ZInt256(&[0xff; 32])).to_string(), "-1");
+        assert_eq!(DynValue::FixedBytes(&[0xca, 0xfe]).to_string(), "0xcafe");
+        assert_eq!(
+            DynValue::FixedBytes(&[0xca, 0xfe]).as_bytes(),
+            Some(&[0xca, 0xfe][..])
+        );
+    }
+
+    #[test]
+    fn test_pretty_and_json() {
+        let amount = word(100);
+        let address = [0x5a; 20];
+        let value = DynValue::Tuple(vec![
+            DynValue::Uint(ZU256(&amount)),
+            DynValue::Array(vec![DynValue::Bool(true), DynValue::Bool(false)]),
+            DynValue::Array(vec![]),
+            DynValue::String(ZString("say \"gm\"\n")),
+            DynValue::FixedBytes(&[0xca, 0xfe]),
+            DynValue::Int(ZInt256(&[0xff; 32])),
+        ]);
+        assert_eq!(
+            value.pretty().to_string(),
+            "(\n  100,\n  [\n    true,\n    false\n  ],\n  [],\n  \"say \\\"gm\\\"\\n\",\n  0xcafe,\n  -1\n)"
+        );
+        assert_eq!(
+            value.to_json(),
+            r#"["100",[true,false],[],"say \"gm\"\n","0xcafe","-1"]"#
+        );
+        assert_eq!(DynValue::Bool(true).pretty().to_string(), "true");
+
+        let rendered = DynValue::Address(ZAddress(&address)).to_json();
+        #[cfg(feature = "keccak")]
+        {
+            let mut buf = [0u8; 42];
+            assert_eq!(
+                rendered,
+                format!("\"{}\"", ZAddress(&address).to_checksum(&mut buf))
+            );
+        }
+        assert!(rendered.eq_ignore_ascii_case(&format!("\"0x{}\"", "5a".repeat(20))));
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let ty = DynType::parse("(uint8,string,(bool,bytes3)[2],int16[],bytes)").unwrap();
+        let seven = word(7);
+        let mut minus_two = [0xff; 32];
+        minus_two[31] = 0xfe;
+        let memo = [0xab; 33];
+        let value = DynValue::Tuple(vec![
+            DynValue::Uint(ZU256(&seven)),
+            DynValue::String(ZString("gm")),
+            DynValue::Array(vec![
+                DynValue::Tuple(vec![DynValue::Bool(true), DynValue::FixedBytes(b"abc")]),
+                DynValue::Tuple(vec![DynValue::Bool(false), DynValue::FixedBytes(b"xyz")]),
+            ]),
+            DynValue::Array(vec![DynValue::Int(ZInt256(&minus_two))]),
+            DynValue::Bytes(ZBytes(&memo)),
+        ]);
+
+        let mut params = Vec::new();
+        encode_dyn_params(&ty, &value, &mut params).unwrap();
+        let DynType::Tuple(members) = &ty else {
+            unreachable!()
+        };
+        let DynValue::Tuple(items) = &value else {
+            unreachable!()
+        };
+        assert_eq!(params.len(), canonical_len(members.iter(), items));
+        assert_eq!(decode_dyn_params(&ty, &params).unwrap(), value);
+
+        // Wrapped in an offset word, as `decode_dyn` expects at offset 0.
+        let mut wrapped = Vec::new();
+        encode_dyn(&ty, &value, &mut wrapped).unwrap();
+        assert_eq!(wrapped[..32], word(32));
+        assert_eq!(wrapped[32..], params[..]);
+        assert_eq!(decode_dyn(&ty, &wrapped, 0).unwrap(), value);
+    }
+
+    #[test]
+    fn test_encode_rejects_mismatch() {
+        let big = word(0xff);
+        let mut buf = Vec::new();
+        assert!(encode_dyn(&DynType::Uint(8), &DynValue::Uint(ZU256(&big)), &mut buf).is_ok());
+        let mut wide = word(0);
+        wide[30] = 1;
+        assert!(encode_dyn(&DynType::Uint(8), &DynValue::Uint(ZU256(&wide)), &mut buf).is_err());
+        assert!(encode_dyn(&DynType::Bool, &DynValue::Uint(ZU256(&big)), &mut buf).is_err());
+        assert!(encode_dyn(
+            &DynType::FixedBytes(4),
+            &DynValue::FixedBytes(b"abc"),
+            &mut buf
+        )
+        .is_err());
+        let pair = DynType::parse("uint256[2]").unwrap();
+        let single = DynValue::Array(vec![DynValue::Uint(ZU256(&big))]);
+        assert!(matches!(
+            encode_dyn(&pair, &single, &mut buf),
+            Err(ZError::Field { index: 0, .. })
+        ));
+        assert_eq!(buf.len(), 32);
+    }
+
+    #[test]
+    fn test_validate_and_canonicalize() {
+        let ty = DynType::parse("(uint, tuple(bytes32, int)[2])").unwrap();
+        assert_eq!(
+            ty.canonical_signature("f").unwrap(),
+            "f(uint256,(bytes32,int256)[2])"
+        );
+        assert!(ty.validate().is_ok());
+        assert!(DynType::Uint(256).canonical_signature("f").is_err());
+
+        for bad in [
+            DynType::Uint(0),
+            DynType::Int(257),
+            DynType::Uint(12),
+            DynType::FixedBytes(33),
+            DynType::FixedArray(Box::new(DynType::Bool), 0),
+            DynType::Tuple(vec![DynType::Array(Box::new(DynType::FixedBytes(0)))]),
+        ] {
+            assert!(bad.validate().is_err(), "{}", bad);
+        }
+        let bad = DynType::Tuple(vec![DynType::Uint(300)]);
+        assert!(bad.canonical_signature("f").is_err());
+        // Invalid nodes fail cleanly instead of indexing out of range.
+        assert!(decode_dyn(&bad, &[0u8; 32], 0).is_err());
+        let max = [0xff; 32];
+        assert!(encode_dyn(
+            &DynType::Uint(300),
+            &DynValue::Uint(ZU256(&max)),
+            &mut Vec::new()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_forged_array_length() {
+        // A `()[]` claiming 2^40 elements must fail on bounds, not allocate or spin.
+        let mut data = [0u8; 64];
+        data[..32].copy_from_slice(&word(32));
+        data[59] = 1;
+        let ty = DynType::parse("()[]").unwrap();
+        assert!(matches!(
+            decode_dyn(&ty, &data, 0),
+            Err(ZError::OutOfBounds(..))
+        ));
+    }
+
+    #[test]
+    fn test_oversized_length_word() {
+        // bytes/string claiming u64::MAX bytes, and a head offset near usize::MAX.
+        let mut data = [0u8; 64];
+        data[..32].copy_from_slice(&word(32));
+        data[56..].fill(0xff);
+        for ty in ["bytes", "string", "uint8[]"] {
+            let ty = DynType::parse(ty).unwrap();
+            assert!(
+                matches!(decode_dyn(&ty, &data, 0), Err(ZError::OutOfBounds(..))),
+                "{:?}",
+                ty
+            );
+        }
+        assert!(matches!(
+            decode_dyn(&DynType::Uint(256), &data, usize::MAX - 8),
+            Err(ZError::OutOfBounds(..))
+        ));
+
+        // uint8[] of length 2^192 + 1 is not an array of one element.
+        let mut array = [0u8; 96];
+        array[..32].copy_from_slice(&word(32));
+        array[32..64].copy_from_slice(&word(1));
+        array[39] = 1;
+        let ty = DynType::parse("uint8[]").unwrap();
+        assert!(matches!(
+            decode_dyn(&ty, &array, 0),
+            Err(ZError::OutOfBounds(..))
+        ));
+        array[39] = 0;
+        assert_eq!(
+            decode_dyn(&ty, &array, 0).unwrap(),
+            DynValue::Array(vec![DynValue::Uint(ZU256(&[0u8; 32]))])
+        );
+
+        // f(bytes): selector d45754f8.
+        let mut calldata = [0u8; 68];
+        calldata[..4].copy_from_slice(&[0xd4, 0x57, 0x54, 0xf8]);
+        calldata[4..].copy_from_slice(&data);
+        let err = decode_by_signature("f(bytes)", &calldata).unwrap_err();
+        assert!(matches!(err.root_cause(), ZError::OutOfBounds(..)));
+    }
+
+    #[test]
+    fn test_decode_by_signature() {
+        // transfer(address,uint256): selector a9059cbb.
+        let mut calldata = [0u8; 68];
+        calldata[..4].copy_from_slice(&[0xa9, 0x05, 0x9c, 0xbb]);
+        calldata[16..36].copy_from_slice(&[0x11; 20]);
+        calldata[67] = 100;
+
+        let value = decode_by_signature("transfer(address, uint)", &calldata).unwrap();
+        let DynValue::Tuple(args) = value else {
+            panic!("not a tuple")
+        };
+        assert_eq!(args[0], DynValue::Address(ZAddress(&[0x11; 20])));
+        assert_eq!(args[1], DynValue::Uint(ZU256(&word(100))));
+
+        #[cfg(feature = "keccak")]
+        assert!(matches!(
+            decode_by_signature("approve(address,uint256)", &calldata),
+            Err(ZError::SelectorMismatch { .. })
+        ));
+        assert!(decode_by_signature("(address,uint256)", &calldata).is_err());
+        assert!(decode_by_signature("transfer(address,uint256", &calldata).is_err());
+    }
+}
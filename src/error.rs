@@ -1,23 +1,412 @@
+//! The decode error type.
+//!
+//! With the `alloc` feature, a failure inside a struct field or array element
+//! is wrapped in [`ZError::Field`] (see [`ZError::in_field`]), which boxes the
+//! inner error: one small allocation per level, on the error path only.
+//! Successful decodes never allocate. Without `alloc` the leaf error is
+//! returned as is.
+//!
+//! `ZError` is `#[non_exhaustive]`: the context variants only exist with
+//! `alloc`, and new failure kinds may be added, so matches need a `_` arm.
+//!
+//! With the `tiny-errors` feature, `Debug` and `Display` print only the
+//! numeric [`ZError::code`], which drops the formatting machinery and the
+//! message templates. The variants and their fields are unchanged, so the
+//! `&'static str` payloads of [`ZError::Custom`] and
+//! [`ZError::InvalidPadding`] are still stored in the binary.
+
 use core::fmt;
 
-#[derive(Debug)]
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+#[cfg_attr(not(feature = "tiny-errors"), derive(Debug))]
+#[non_exhaustive]
 pub enum ZError {
     InvalidLength(usize, usize),
     OutOfBounds(usize, usize),
-    InvalidUtf8,
+    /// The padding around a value narrower than a word is not canonical:
+    /// dirty high bits, a wrong sign extension, or non-zero `bytesN` tail.
+    InvalidPadding { offset: usize, ty: &'static str },
+    /// A `bool` word is neither 0 nor 1.
+    InvalidBool { offset: usize },
+    /// A `string` is not valid UTF-8. `offset` is the first invalid byte.
+    InvalidUtf8 { offset: usize },
+    /// The dynamic offset stored in the head word at `at` points to `target`,
+    /// outside the `len`-byte buffer.
+    OffsetOutOfRange { at: usize, target: usize, len: usize },
+    /// Calldata or revert data starts with another function's or error's
+    /// selector: well-formed input for a different entry point.
+    SelectorMismatch { expected: [u8; 4], found: [u8; 4] },
+    /// A log's topic 0 is the signature hash of a different event type.
+    TypeMismatch { expected: [u8; 32], found: [u8; 32] },
+    /// No function or custom error is known under this selector.
+    UnknownSelector([u8; 4]),
+    /// No event is known under this topic 0.
+    UnknownTopic([u8; 32]),
+    /// No decoder is registered under this schema id (see `service`).
+    UnknownSchema(u32),
+    /// A decoded value does not fit in the Rust integer type `ty` it was
+    /// converted to, e.g. `u8::try_from(ZU256)` of 256.
+    Overflow { ty: &'static str },
+    /// Field or element `index` of a struct or array failed with `source`.
+    /// Nested failures form a chain down to the leaf cause.
+    #[cfg(feature = "alloc")]
+    Field { index: usize, source: Box<ZError> },
+    /// The field `name` failed with `source`.
+    #[cfg(feature = "alloc")]
+    NamedField { name: &'static str, source: Box<ZError> },
+    /// Decoding at byte `offset` failed with `source`.
+    #[cfg(feature = "alloc")]
+    AtOffset { offset: usize, source: Box<ZError> },
     Custom(&'static str),
+    /// Like `Custom`, but built at runtime, e.g. to name a field or value.
+    #[cfg(feature = "alloc")]
+    Message(String),
 }
 
+#[cfg(not(feature = "tiny-errors"))]
 impl fmt::Display for ZError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ZError::InvalidLength(expected, actual) => write!(f, "Invalid length: expected {}, got {}", expected, actual),
             ZError::OutOfBounds(idx, len) => write!(f, "Index out of bounds: index {}, len {}", idx, len),
-            ZError::InvalidUtf8 => write!(f, "Invalid UTF-8 sequence"),
+            ZError::InvalidPadding { offset, ty } => write!(f, "Invalid {} padding in word at offset {}", ty, offset),
+            ZError::InvalidBool { offset } => write!(f, "Invalid bool (not 0 or 1) in word at offset {}", offset),
+            ZError::InvalidUtf8 { offset } => write!(f, "Invalid UTF-8 sequence at offset {}", offset),
+            ZError::OffsetOutOfRange { at, target, len } => {
+                write!(f, "Offset at {} points to {}, beyond len {}", at, target, len)
+            }
+            ZError::SelectorMismatch { expected, found } => {
+                write!(f, "Selector mismatch: expected 0x")?;
+                write_hex(f, expected)?;
+                write!(f, ", found 0x")?;
+                write_hex(f, found)
+            }
+            ZError::TypeMismatch { expected, found } => {
+                write!(f, "Event type mismatch: expected topic 0x")?;
+                write_hex(f, expected)?;
+                write!(f, ", found 0x")?;
+                write_hex(f, found)
+            }
+            ZError::UnknownSelector(selector) => {
+                write!(f, "Unknown selector 0x")?;
+                write_hex(f, selector)
+            }
+            ZError::UnknownTopic(topic) => {
+                write!(f, "Unknown event topic 0x")?;
+                write_hex(f, topic)
+            }
+            ZError::UnknownSchema(id) => write!(f, "Unknown schema id {}", id),
+            ZError::Overflow { ty } => write!(f, "Value does not fit in {}", ty),
+            #[cfg(feature = "alloc")]
+            ZError::Field { index, source } => write!(f, "In field {}: {}", index, source),
+            #[cfg(feature = "alloc")]
+            ZError::NamedField { name, source } => write!(f, "In field `{}`: {}", name, source),
+            #[cfg(feature = "alloc")]
+            ZError::AtOffset { offset, source } => write!(f, "At offset {}: {}", offset, source),
             ZError::Custom(msg) => write!(f, "Error: {}", msg),
+            #[cfg(feature = "alloc")]
+            ZError::Message(msg) => write!(f, "Error: {}", msg),
         }
     }
 }
 
-// In no_std, we don't have std::error::Error, so we just stick to Display + Debug.
-// If we wanted to support standard Error trait when std is enabled, we could use cfg features.
+#[cfg(feature = "tiny-errors")]
+impl fmt::Debug for ZError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ZError(")?;
+        write_code(f, self.code())?;
+        f.write_str(")")
+    }
+}
+
+#[cfg(feature = "tiny-errors")]
+impl fmt::Display for ZError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("zabi error ")?;
+        write_code(f, self.code())
+    }
+}
+
+/// Writes `code` in decimal without going through `core::fmt` integer formatting.
+#[cfg(feature = "tiny-errors")]
+fn write_code(f: &mut fmt::Formatter<'_>, code: u16) -> fmt::Result {
+    let mut buf = [0u8; 5];
+    let mut start = buf.len();
+    let mut n = code;
+    loop {
+        start -= 1;
+        buf[start] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    f.write_str(core::str::from_utf8(&buf[start..]).unwrap_or("?"))
+}
+
+impl ZError {
+    /// A stable numeric code for the variant, for FFI and compact logging.
+    ///
+    /// | code | variant              |
+    /// |------|----------------------|
+    /// | 1    | `InvalidLength`      |
+    /// | 2    | `OutOfBounds`        |
+    /// | 3    | `InvalidPadding`     |
+    /// | 4    | `InvalidBool`        |
+    /// | 5    | `InvalidUtf8`        |
+    /// | 6    | `OffsetOutOfRange`   |
+    /// | 7    | `SelectorMismatch`   |
+    /// | 8    | `TypeMismatch`       |
+    /// | 9    | `Field`              |
+    /// | 10   | `Message`            |
+    /// | 11   | `NamedField`         |
+    /// | 12   | `AtOffset`           |
+    /// | 13   | `UnknownSelector`    |
+    /// | 14   | `UnknownTopic`       |
+    /// | 15   | `UnknownSchema`      |
+    /// | 16   | `Overflow`           |
+    /// | 255  | `Custom`             |
+    ///
+    /// Codes are never reused; new variants take the next free number.
+    pub const fn code(&self) -> u16 {
+        match self {
+            ZError::InvalidLength(..) => 1,
+            ZError::OutOfBounds(..) => 2,
+            ZError::InvalidPadding { .. } => 3,
+            ZError::InvalidBool { .. } => 4,
+            ZError::InvalidUtf8 { .. } => 5,
+            ZError::OffsetOutOfRange { .. } => 6,
+            ZError::SelectorMismatch { .. } => 7,
+            ZError::TypeMismatch { .. } => 8,
+            #[cfg(feature = "alloc")]
+            ZError::Field { .. } => 9,
+            #[cfg(feature = "alloc")]
+            ZError::Message(_) => 10,
+            #[cfg(feature = "alloc")]
+            ZError::NamedField { .. } => 11,
+            #[cfg(feature = "alloc")]
+            ZError::AtOffset { .. } => 12,
+            ZError::UnknownSelector(_) => 13,
+            ZError::UnknownTopic(_) => 14,
+            ZError::UnknownSchema(_) => 15,
+            ZError::Overflow { .. } => 16,
+            ZError::Custom(_) => 255,
+        }
+    }
+
+    /// Offset of the 32-byte word the error points at, if it points at one.
+    pub fn word_offset(&self) -> Option<usize> {
+        match self {
+            ZError::InvalidPadding { offset, .. } | ZError::InvalidBool { offset } => Some(*offset),
+            ZError::OffsetOutOfRange { at, .. } => Some(*at),
+            ZError::InvalidUtf8 { offset } => Some(offset & !31),
+            #[cfg(feature = "alloc")]
+            ZError::AtOffset { offset, source } => source.word_offset().or(Some(*offset)),
+            _ => self.inner().and_then(ZError::word_offset),
+        }
+    }
+
+    /// A [`ZError::Message`] error.
+    ///
+    /// ```
+    /// use zabi_rs::ZError;
+    ///
+    /// let err = ZError::message(format!("amount {} above cap", 4196));
+    /// # if cfg!(not(feature = "tiny-errors")) {
+    /// assert_eq!(err.to_string(), "Error: amount 4196 above cap");
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn message(msg: impl Into<String>) -> ZError {
+        ZError::Message(msg.into())
+    }
+
+    /// Wrap the error as coming from field or element `index`.
+    /// With `alloc` this boxes `self`; without it there is nowhere to keep
+    /// the chain, so the error is returned unchanged.
+    #[inline]
+    pub fn in_field(self, index: usize) -> ZError {
+        #[cfg(feature = "alloc")]
+        {
+            ZError::Field { index, source: Box::new(self) }
+        }
+        #[cfg(not(feature = "alloc"))]
+        {
+            let _ = index;
+            self
+        }
+    }
+
+    /// Wrap the error as coming from the field `name`.
+    /// Returned unchanged without the `alloc` feature.
+    #[inline]
+    pub fn in_named_field(self, name: &'static str) -> ZError {
+        #[cfg(feature = "alloc")]
+        {
+            ZError::NamedField { name, source: Box::new(self) }
+        }
+        #[cfg(not(feature = "alloc"))]
+        {
+            let _ = name;
+            self
+        }
+    }
+
+    /// Wrap the error as coming from a read at byte `offset`.
+    /// Returned unchanged without the `alloc` feature.
+    #[inline]
+    pub fn at_offset(self, offset: usize) -> ZError {
+        #[cfg(feature = "alloc")]
+        {
+            ZError::AtOffset { offset, source: Box::new(self) }
+        }
+        #[cfg(not(feature = "alloc"))]
+        {
+            let _ = offset;
+            self
+        }
+    }
+
+    /// The innermost error of a context chain, or `self`.
+    pub fn root_cause(&self) -> &ZError {
+        match self.inner() {
+            Some(source) => source.root_cause(),
+            None => self,
+        }
+    }
+
+    /// The wrapped error, for the context variants.
+    fn inner(&self) -> Option<&ZError> {
+        match self {
+            #[cfg(feature = "alloc")]
+            ZError::Field { source, .. } | ZError::NamedField { source, .. } | ZError::AtOffset { source, .. } => {
+                Some(source)
+            }
+            _ => None,
+        }
+    }
+
+    /// Display the error followed by the offending word of `data` in hex.
+    ///
+    /// `data` must be the buffer the failing decoder was given. Errors that do
+    /// not point at a word, or whose word is not in `data`, display as usual,
+    /// as does every error with the `tiny-errors` feature.
+    ///
+    /// ```
+    /// use zabi_rs::decoder::read_u8;
+    ///
+    /// let mut data = [0u8; 32];
+    /// data[30] = 1;
+    /// let err = read_u8(&data, 0).unwrap_err();
+    /// let shown = err.with_context(&data).to_string();
+    /// # if cfg!(not(feature = "tiny-errors")) {
+    /// assert!(shown.starts_with("Invalid uint8 padding in word at offset 0 (word at 0: 0x0000"));
+    /// assert!(shown.ends_with("0100)"));
+    /// # }
+    /// ```
+    pub fn with_context<'e, 'd>(&'e self, data: &'d [u8]) -> ZErrorContext<'e, 'd> {
+        ZErrorContext { error: self, data }
+    }
+}
+
+/// Returned by [`ZError::with_context`].
+#[derive(Clone, Copy, Debug)]
+pub struct ZErrorContext<'e, 'd> {
+    error: &'e ZError,
+    data: &'d [u8],
+}
+
+impl fmt::Display for ZErrorContext<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+        #[cfg(feature = "tiny-errors")]
+        let _ = self.data;
+        #[cfg(not(feature = "tiny-errors"))]
+        {
+            let word = self.error.word_offset().and_then(|offset| Some((offset, self.data.get(offset..offset.checked_add(32)?)?)));
+            if let Some((offset, word)) = word {
+                write!(f, " (word at {}: 0x", offset)?;
+                write_hex(f, word)?;
+                write!(f, ")")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<ZError> for std::io::Error {
+    fn from(err: ZError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+    }
+}
+
+#[cfg(not(feature = "tiny-errors"))]
+fn write_hex(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    for byte in bytes {
+        write!(f, "{:02x}", byte)?;
+    }
+    Ok(())
+}
+
+/// `core::error::Error` is re-exported as `std::error::Error`, so this also
+/// covers `Box<dyn Error>`, `anyhow` and `eyre` without a `std` feature.
+impl core::error::Error for ZError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        self.inner().map(|source| source as _)
+    }
+}
+
+/// Context helpers for `Result<T, ZError>` in hand-written decoders.
+///
+/// ```
+/// use zabi_rs::decoder::read_u64;
+/// use zabi_rs::{ZError, ZResultExt};
+///
+/// fn amount(data: &[u8]) -> Result<u64, ZError> {
+///     read_u64(data, 32).context_offset(32).context_field("amount")
+/// }
+///
+/// let err = amount(&[0xff; 64]).unwrap_err();
+/// assert!(matches!(err.root_cause(), ZError::InvalidPadding { offset: 32, .. }));
+/// ```
+///
+/// Without the `alloc` feature the context is dropped and the error passes
+/// through unchanged.
+pub trait ZResultExt<T> {
+    /// Attach the name of the field being decoded.
+    fn context_field(self, name: &'static str) -> Result<T, ZError>;
+    /// Attach the index of the field or element being decoded.
+    fn context_index(self, index: usize) -> Result<T, ZError>;
+    /// Attach the byte offset being decoded.
+    fn context_offset(self, offset: usize) -> Result<T, ZError>;
+}
+
+impl<T> ZResultExt<T> for Result<T, ZError> {
+    #[inline]
+    fn context_field(self, name: &'static str) -> Result<T, ZError> {
+        self.map_err(|e| e.in_named_field(name))
+    }
+
+    #[inline]
+    fn context_index(self, index: usize) -> Result<T, ZError> {
+        self.map_err(|e| e.in_field(index))
+    }
+
+    #[inline]
+    fn context_offset(self, offset: usize) -> Result<T, ZError> {
+        self.map_err(|e| e.at_offset(offset))
+    }
+}
+
+/// Run `decode` and wrap its error with field or element `index`.
+///
+/// Used by `#[derive(ZDecode)]`; see [`ZError::in_field`].
+#[inline(always)]
+pub fn in_field<T>(index: usize, decode: impl FnOnce() -> Result<T, ZError>) -> Result<T, ZError> {
+    decode().context_index(index)
+}
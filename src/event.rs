@@ -7,30 +7,162 @@
 //! This module provides zero-copy decoding for both.
 
 use core::convert::TryInto;
+use core::fmt;
+use core::marker::PhantomData;
 use crate::error::ZError;
+#[cfg(feature = "keccak")]
+use crate::hash::{keccak256, Keccak256};
+use crate::ZDecode;
 use crate::types::{ZAddress, ZU256, ZInt256};
+use crate::zbytes_fixed::{read_bytes_n, ZBytesN};
+
+#[cfg(feature = "alloc")]
+pub mod stats;
+
+/// An indexed `string`, `bytes`, array or struct parameter.
+///
+/// Such values are too large for a topic, so the log carries their keccak256
+/// hash instead of the value. The preimage is the raw bytes for `string` and
+/// `bytes`, and the in-place ABI encoding of the elements for arrays and structs.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ZIndexedHash<'a>(pub &'a [u8; 32]);
+
+impl<'a> ZIndexedHash<'a> {
+    /// Returns the hash bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8; 32] {
+        self.0
+    }
+
+    /// Check the hash against a preimage using a caller-supplied keccak256.
+    #[inline]
+    pub fn verify_with<H>(&self, preimage: &[u8], keccak256: H) -> bool
+    where
+        H: FnOnce(&[u8]) -> [u8; 32],
+    {
+        keccak256(preimage) == *self.0
+    }
+
+    /// Check the hash against a preimage.
+    #[cfg(feature = "keccak")]
+    pub fn verify(&self, preimage: &[u8]) -> bool {
+        self.verify_with(preimage, keccak256)
+    }
+}
+
+impl<'a> fmt::Debug for ZIndexedHash<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ZIndexedHash(0x")?;
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl<'a> ZDecode<'a> for ZIndexedHash<'a> {
+    const HEAD_SIZE: usize = 32;
+    const SOL_TYPE: &'static str = "bytes32";
+    fn decode(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
+        crate::decoder::peek_word(data, offset).map(ZIndexedHash)
+    }
+}
+
+/// A typed event decoded from a whole log.
+///
+/// Implemented by the event structs generated by `sol!`, `contract!` and
+/// `abigen!`. Use [`ZEventLog::try_decode`] to check topic 0 first.
+pub trait ZEventDecode<'a>: Sized {
+    /// `keccak256` of the canonical event signature, expected in topic 0.
+    const TOPIC0: [u8; 32];
+
+    /// Anonymous events do not emit [`Self::TOPIC0`]; their indexed
+    /// parameters start at topic 0.
+    const ANONYMOUS: bool = false;
+
+    /// Decode the indexed topics and the data section.
+    /// Topic 0 is not checked here.
+    fn decode(log: &ZEventLog<'a>) -> Result<Self, ZError>;
+}
+
+/// Borrowed topics, in whichever layout the caller has them.
+#[derive(Clone, Copy)]
+enum Topics<'a> {
+    Refs(&'a [&'a [u8; 32]]),
+    /// Topics concatenated back to back; the length is a multiple of 32.
+    Flat(&'a [u8]),
+    /// Topics of a revm log.
+    #[cfg(feature = "revm")]
+    Revm(&'a [revm_interpreter::primitives::B256]),
+}
 
 /// Wrapper for Ethereum event log data.
 /// Provides access to topics and non-indexed data.
 #[derive(Clone, Copy)]
 pub struct ZEventLog<'a> {
     /// The topics array (up to 4 topics, first is event signature)
-    topics: &'a [&'a [u8; 32]],
+    topics: Topics<'a>,
     /// The non-indexed data
     data: &'a [u8],
+    /// The emitting contract, when known
+    address: Option<&'a [u8; 20]>,
 }
 
 impl<'a> ZEventLog<'a> {
     /// Create a new event log wrapper.
     #[inline]
     pub fn new(topics: &'a [&'a [u8; 32]], data: &'a [u8]) -> Self {
-        Self { topics, data }
+        Self { topics: Topics::Refs(topics), data, address: None }
+    }
+
+    /// Create an event log from topics concatenated into one buffer,
+    /// as RPC byte blobs usually carry them.
+    /// Fails with [`ZError::InvalidLength`] if the buffer length is not a
+    /// multiple of 32.
+    #[inline]
+    pub fn from_raw(topics_concat: &'a [u8], data: &'a [u8]) -> Result<Self, ZError> {
+        if !topics_concat.len().is_multiple_of(32) {
+            return Err(ZError::InvalidLength(topics_concat.len().next_multiple_of(32), topics_concat.len()));
+        }
+        Ok(Self { topics: Topics::Flat(topics_concat), data, address: None })
+    }
+
+    /// Create an event log from topics stored as owned arrays,
+    /// the layout most RPC client log types use.
+    #[inline]
+    pub fn from_topics(topics: &'a [[u8; 32]], data: &'a [u8]) -> Self {
+        Self { topics: Topics::Flat(topics.as_flattened()), data, address: None }
+    }
+
+    /// Create an event log from revm's topic words.
+    #[cfg(feature = "revm")]
+    #[inline]
+    pub(crate) fn from_revm(topics: &'a [revm_interpreter::primitives::B256], data: &'a [u8]) -> Self {
+        Self { topics: Topics::Revm(topics), data, address: None }
+    }
+
+    /// Attach the address of the contract that emitted the log.
+    #[inline]
+    pub fn with_address(mut self, address: &'a [u8; 20]) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    /// Returns the emitting contract, if it was attached.
+    #[inline]
+    pub fn address(&self) -> Option<ZAddress<'a>> {
+        self.address.map(ZAddress)
     }
 
     /// Returns the number of topics.
     #[inline]
     pub fn topic_count(&self) -> usize {
-        self.topics.len()
+        match self.topics {
+            Topics::Refs(topics) => topics.len(),
+            Topics::Flat(bytes) => bytes.len() / 32,
+            #[cfg(feature = "revm")]
+            Topics::Revm(topics) => topics.len(),
+        }
     }
 
     /// Returns the raw data slice.
@@ -42,10 +174,16 @@ impl<'a> ZEventLog<'a> {
     /// Get raw topic bytes at index.
     #[inline]
     pub fn raw_topic(&self, index: usize) -> Result<&'a [u8; 32], ZError> {
-        if index >= self.topics.len() {
-            return Err(ZError::OutOfBounds(index, self.topics.len()));
+        let count = self.topic_count();
+        if index >= count {
+            return Err(ZError::OutOfBounds(index, count));
+        }
+        match self.topics {
+            Topics::Refs(topics) => Ok(topics[index]),
+            Topics::Flat(bytes) => Ok(bytes[index * 32..index * 32 + 32].try_into().unwrap()),
+            #[cfg(feature = "revm")]
+            Topics::Revm(topics) => Ok(&topics[index].0),
         }
-        Ok(self.topics[index])
     }
 
     /// Get the event signature (topic[0]) as bytes32.
@@ -79,6 +217,88 @@ impl<'a> ZEventLog<'a> {
         Ok(ZAddress(addr_ref))
     }
 
+    /// Decode an indexed topic as a `uint` of at most 64 bits.
+    /// The high 24 bytes must be zero.
+    #[inline]
+    pub fn topic_as_u64(&self, index: usize) -> Result<u64, ZError> {
+        crate::decoder::read_u64(self.raw_topic(index)?, 0)
+    }
+
+    /// Decode an indexed topic as an `int` of at most 128 bits.
+    /// The high 16 bytes must be the sign extension of the value.
+    #[inline]
+    pub fn topic_as_i128(&self, index: usize) -> Result<i128, ZError> {
+        crate::decoder::read_i128(self.raw_topic(index)?, 0)
+    }
+
+    /// Decode an indexed topic as `bytesN`.
+    /// The value is left-aligned; the trailing `32 - N` bytes must be zero.
+    #[inline]
+    pub fn topic_as_bytes<const N: usize>(&self, index: usize) -> Result<ZBytesN<'a, N>, ZError> {
+        read_bytes_n::<N>(self.raw_topic(index)?, 0)
+    }
+
+    /// Returns true if topic 0 is `T`'s signature hash.
+    /// Always false for anonymous events, which cannot be recognized by topic.
+    #[inline]
+    pub fn is<T: ZEventDecode<'a>>(&self) -> bool {
+        !T::ANONYMOUS && matches!(self.event_signature(), Ok(topic0) if *topic0 == T::TOPIC0)
+    }
+
+    /// Decode the log as `T` after checking topic 0 against `T::TOPIC0`.
+    /// Anonymous events are decoded without the check.
+    #[inline]
+    pub fn try_decode<T: ZEventDecode<'a>>(&self) -> Result<T, ZError> {
+        if !T::ANONYMOUS {
+            let topic0 = self.event_signature()?;
+            if topic0 != &T::TOPIC0 {
+                return Err(ZError::TypeMismatch { expected: T::TOPIC0, found: *topic0 });
+            }
+        }
+        T::decode(self)
+    }
+
+    /// Returns true if topic `index` holds `address`.
+    /// The 12 padding bytes must be zero; a missing topic never matches.
+    #[inline]
+    pub fn topic_eq_address(&self, index: usize, address: &[u8; 20]) -> bool {
+        match self.raw_topic(index) {
+            Ok(topic) => topic[..12].iter().all(|&b| b == 0) && topic[12..] == address[..],
+            Err(_) => false,
+        }
+    }
+
+    /// Returns true if topic `index` equals the 32-byte big-endian word `value`.
+    /// A missing topic never matches.
+    #[inline]
+    pub fn topic_eq_u256(&self, index: usize, value: &[u8; 32]) -> bool {
+        matches!(self.raw_topic(index), Ok(topic) if topic == value)
+    }
+
+    /// Read an indexed dynamic parameter (`string`, `bytes`, arrays, structs)
+    /// as the hash stored in the topic.
+    #[inline]
+    pub fn topic_as_hash(&self, index: usize) -> Result<ZIndexedHash<'a>, ZError> {
+        self.raw_topic(index).map(ZIndexedHash)
+    }
+
+    /// Decode the whole data section as `T`.
+    ///
+    /// The section must be a whole number of words and at least
+    /// `T::INLINE_HEAD_SIZE` long; a tuple `T` is read as the parameter list
+    /// itself, so a dynamic tuple needs all of its member heads.
+    #[inline]
+    pub fn decode_data_as<T: ZDecode<'a>>(&self) -> Result<T, ZError> {
+        let len = self.data.len();
+        if len < T::INLINE_HEAD_SIZE {
+            return Err(ZError::InvalidLength(T::INLINE_HEAD_SIZE, len));
+        }
+        if !len.is_multiple_of(32) {
+            return Err(ZError::InvalidLength(len.next_multiple_of(32), len));
+        }
+        T::decode_params(self.data)
+    }
+
     /// Decode data field using standard ABI decoding at offset.
     /// This allows reusing all existing decoder functions.
     #[inline]
@@ -90,6 +310,82 @@ impl<'a> ZEventLog<'a> {
     }
 }
 
+/// Decode every log whose topic 0 is `T::TOPIC0`, skipping the others.
+///
+/// Matching logs that fail to decode are yielded as errors. For anonymous
+/// events no log can be skipped by topic, so every log is decoded.
+#[inline]
+pub fn decode_logs<'a, T, I>(logs: I) -> DecodeLogs<I::IntoIter, T>
+where
+    T: ZEventDecode<'a>,
+    I: IntoIterator<Item = ZEventLog<'a>>,
+{
+    DecodeLogs { logs: logs.into_iter(), _marker: PhantomData }
+}
+
+/// Iterator returned by [`decode_logs`].
+pub struct DecodeLogs<I, T> {
+    logs: I,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, I, T> Iterator for DecodeLogs<I, T>
+where
+    I: Iterator<Item = ZEventLog<'a>>,
+    T: ZEventDecode<'a>,
+{
+    type Item = Result<T, ZError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for log in self.logs.by_ref() {
+            if T::ANONYMOUS || log.is::<T>() {
+                return Some(T::decode(&log));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.logs.size_hint().1)
+    }
+}
+
+/// The topic 0 of an event signature such as
+/// `"Transfer(address,address,uint256)"`, computed at runtime.
+///
+/// Whitespace is ignored, so `"Transfer(address, address, uint256)"` hashes
+/// the same. For signatures known at compile time prefer `event_topic!`.
+#[cfg(feature = "keccak")]
+pub fn signature_hash(signature: &str) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    for part in signature.split_ascii_whitespace() {
+        hasher.update(part.as_bytes());
+    }
+    hasher.finalize()
+}
+
+/// Returns true if a 2048-bit `logsBloom` may contain the value whose
+/// keccak256 hash is `hash`.
+///
+/// Each value sets three bits, taken from the first three byte pairs of its
+/// hash. A `false` result means no log in the block or receipt carries the
+/// value; `true` may be a false positive.
+pub fn bloom_contains_hash(bloom: &[u8; 256], hash: &[u8; 32]) -> bool {
+    (0..3).all(|i| {
+        let bit = ((hash[2 * i] as usize) << 8 | hash[2 * i + 1] as usize) & 2047;
+        bloom[255 - bit / 8] & (1 << (bit % 8)) != 0
+    })
+}
+
+/// Returns true if a `logsBloom` may contain `topic_or_address`: a 32-byte
+/// topic or the 20-byte address of the emitting contract.
+/// See [`bloom_contains_hash`] for callers that already have the hash.
+#[cfg(feature = "keccak")]
+#[inline]
+pub fn bloom_contains(bloom: &[u8; 256], topic_or_address: &[u8]) -> bool {
+    bloom_contains_hash(bloom, &keccak256(topic_or_address))
+}
+
 /// Read a topic from raw topic bytes as ZU256.
 #[inline]
 pub fn read_topic_u256<'a>(topic: &'a [u8; 32]) -> ZU256<'a> {
@@ -112,22 +408,22 @@ pub fn read_topic_address(topic: &[u8; 32]) -> Result<ZAddress<'_>, ZError> {
     Ok(ZAddress(addr_ref))
 }
 
+/// Read a topic holding an indexed dynamic parameter as its hash.
+#[inline]
+pub fn read_topic_hash<'a>(topic: &'a [u8; 32]) -> ZIndexedHash<'a> {
+    ZIndexedHash(topic)
+}
+
 /// Read a topic from raw topic bytes as bool.
 /// Bool is stored as uint256, only last byte matters (0 or 1).
 #[inline]
 pub fn read_topic_bool(topic: &[u8; 32]) -> Result<bool, ZError> {
-    // Check that all bytes except the last are zero
-    if topic[0..31].iter().any(|&b| b != 0) {
-        return Err(ZError::Custom("Boolean topic has dirty high bits"));
-    }
-    match topic[31] {
-        0 => Ok(false),
-        1 => Ok(true),
-        _ => Err(ZError::Custom("Boolean topic value invalid (not 0 or 1)")),
-    }
+    crate::decoder::read_bool(topic, 0).map(|b| b.0)
 }
 
 #[cfg(test)]
+// These unit tests predate the lints and keep their original form.
+#[allow(clippy::needless_range_loop, clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
     extern crate alloc;
@@ -157,6 +453,221 @@ mod tests {
         assert_eq!(val.0[31], 0x01);
     }
 
+    #[test]
+    fn test_event_log_from_raw() {
+        let mut topics = [0u8; 64];
+        topics[0] = 0xde;
+        topics[63] = 0x01;
+        let data = [0u8; 32];
+
+        let event = ZEventLog::from_raw(&topics, &data).unwrap();
+        assert_eq!(event.topic_count(), 2);
+        assert_eq!(event.event_signature().unwrap()[0], 0xde);
+        assert_eq!(event.topic_as_u256(1).unwrap().0[31], 0x01);
+        assert!(event.raw_topic(2).is_err());
+
+        assert_eq!(ZEventLog::from_raw(&[], &data).unwrap().topic_count(), 0);
+        assert!(matches!(ZEventLog::from_raw(&topics[..40], &data), Err(ZError::InvalidLength(64, 40))));
+    }
+
+    #[test]
+    fn test_event_log_from_topics() {
+        let mut topics = [[0u8; 32]; 3];
+        topics[0][0] = 0xde;
+        topics[2][31] = 0x07;
+        let data = [0u8; 32];
+
+        let event = ZEventLog::from_topics(&topics, &data);
+        assert_eq!(event.topic_count(), 3);
+        assert_eq!(event.event_signature().unwrap()[0], 0xde);
+        assert_eq!(event.raw_topic(2).unwrap(), &topics[2]);
+        assert!(event.raw_topic(3).is_err());
+    }
+
+    struct Ping(u8);
+
+    impl<'a> ZEventDecode<'a> for Ping {
+        const TOPIC0: [u8; 32] = [0xAB; 32];
+
+        fn decode(log: &ZEventLog<'a>) -> Result<Self, ZError> {
+            crate::decoder::read_u8(log.data(), 0).map(Ping)
+        }
+    }
+
+    #[test]
+    fn test_try_decode() {
+        let mut data = [0u8; 32];
+        data[31] = 9;
+        let topics = [[0xAB; 32]];
+        let event = ZEventLog::from_topics(&topics, &data);
+        assert!(event.is::<Ping>());
+        assert_eq!(event.try_decode::<Ping>().unwrap().0, 9);
+
+        let other = [[0xCD; 32]];
+        let event = ZEventLog::from_topics(&other, &data);
+        assert!(!event.is::<Ping>());
+        assert!(event.try_decode::<Ping>().is_err());
+        assert!(ZEventLog::from_topics(&[], &data).try_decode::<Ping>().is_err());
+    }
+
+    #[test]
+    fn test_indexed_hash() {
+        let mut topic = [0u8; 32];
+        topic[0] = 0x5a;
+        let topics = [topic];
+        let event = ZEventLog::from_topics(&topics, &[]);
+        let hash = event.topic_as_hash(0).unwrap();
+        assert_eq!(hash.as_bytes()[0], 0x5a);
+        assert!(hash.verify_with(b"tag", |_| topic));
+        assert!(!hash.verify_with(b"tag", |_| [0u8; 32]));
+        assert!(event.topic_as_hash(1).is_err());
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn test_indexed_hash_verify() {
+        // keccak256("")
+        let empty: [u8; 32] = [
+            0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7, 0x03, 0xc0,
+            0xe5, 0x00, 0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04, 0x5d, 0x85, 0xa4, 0x70,
+        ];
+        assert!(read_topic_hash(&empty).verify(b""));
+        assert!(!read_topic_hash(&empty).verify(b"x"));
+    }
+
+    #[test]
+    fn test_decode_logs() {
+        let ping = [[0xAB; 32]];
+        let other = [[0xCD; 32]];
+        let mut data = [[0u8; 32]; 3];
+        data[0][31] = 1;
+        data[2][31] = 3;
+        data[1][0] = 0xFF; // not a valid uint8
+        let logs = [
+            ZEventLog::from_topics(&ping, &data[0]),
+            ZEventLog::from_topics(&other, &data[0]),
+            ZEventLog::from_topics(&ping, &data[1]),
+            ZEventLog::from_topics(&[], &data[0]),
+            ZEventLog::from_topics(&ping, &data[2]),
+        ];
+
+        let mut decoded = decode_logs::<Ping, _>(logs);
+        assert_eq!(decoded.next().unwrap().unwrap().0, 1);
+        assert!(decoded.next().unwrap().is_err());
+        assert_eq!(decoded.next().unwrap().unwrap().0, 3);
+        assert!(decoded.next().is_none());
+    }
+
+    fn accrue(bloom: &mut [u8; 256], hash: &[u8; 32]) {
+        for i in 0..3 {
+            let bit = ((hash[2 * i] as usize) << 8 | hash[2 * i + 1] as usize) & 2047;
+            bloom[255 - bit / 8] |= 1 << (bit % 8);
+        }
+    }
+
+    #[test]
+    fn test_bloom_contains_hash() {
+        let mut hash = [0u8; 32];
+        hash[..6].copy_from_slice(&[0x00, 0x00, 0x07, 0xFF, 0x01, 0x08]);
+        let mut bloom = [0u8; 256];
+        accrue(&mut bloom, &hash);
+        // Bits 0, 2047 and 264.
+        assert_eq!(bloom[255], 0x01);
+        assert_eq!(bloom[0], 0x80);
+        assert_eq!(bloom[222], 0x01);
+        assert!(bloom_contains_hash(&bloom, &hash));
+
+        let mut other = hash;
+        other[5] = 0x09;
+        assert!(!bloom_contains_hash(&bloom, &other));
+        assert!(!bloom_contains_hash(&[0u8; 256], &hash));
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn test_signature_hash() {
+        let topic0 = signature_hash("Transfer(address,address,uint256)");
+        assert_eq!(topic0[..4], [0xdd, 0xf2, 0x52, 0xad]);
+        assert_eq!(signature_hash(" Transfer(address, address,\tuint256)"), topic0);
+        assert_eq!(signature_hash(""), keccak256(b""));
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn test_bloom_contains() {
+        let address = [0x11u8; 20];
+        let mut bloom = [0u8; 256];
+        accrue(&mut bloom, &keccak256(&address));
+        assert!(bloom_contains(&bloom, &address));
+        assert!(!bloom_contains(&bloom, &[0x22u8; 20]));
+    }
+
+    #[test]
+    fn test_decode_data_as() {
+        let topics = [[0u8; 32]];
+        let mut data = [0u8; 128];
+        data[31] = 42; // uint64
+        data[63] = 64; // offset to string
+        data[95] = 2; // string length
+        data[96..98].copy_from_slice(b"ok");
+        let event = ZEventLog::from_topics(&topics, &data);
+
+        let (value, note) = event.decode_data_as::<(u64, crate::ZString)>().unwrap();
+        assert_eq!(value, 42);
+        assert_eq!(note.as_str(), "ok");
+        assert_eq!(event.decode_data_as::<u64>().unwrap(), 42);
+
+        let short = ZEventLog::from_topics(&topics, &data[..32]);
+        assert!(matches!(short.decode_data_as::<(u64, u64)>(), Err(ZError::InvalidLength(64, 32))));
+        // A dynamic tuple's heads are read inline, not behind an offset word.
+        assert!(matches!(short.decode_data_as::<(u64, crate::ZString)>(), Err(ZError::InvalidLength(64, 32))));
+        let ragged = ZEventLog::from_topics(&topics, &data[..40]);
+        assert!(matches!(ragged.decode_data_as::<u64>(), Err(ZError::InvalidLength(64, 40))));
+    }
+
+    #[test]
+    fn test_topic_eq_helpers() {
+        let address = [0x11u8; 20];
+        let mut padded = [0u8; 32];
+        padded[12..].copy_from_slice(&address);
+        let mut amount = [0u8; 32];
+        amount[31] = 5;
+        let topics = [[0xAB; 32], padded, amount];
+        let event = ZEventLog::from_topics(&topics, &[]);
+
+        assert!(event.topic_eq_address(1, &address));
+        assert!(!event.topic_eq_address(1, &[0x22; 20]));
+        assert!(!event.topic_eq_address(0, &[0xAB; 20]));
+        assert!(!event.topic_eq_address(3, &address));
+        assert!(event.topic_eq_u256(2, &amount));
+        assert!(!event.topic_eq_u256(1, &amount));
+        assert!(!event.topic_eq_u256(3, &amount));
+    }
+
+    #[test]
+    fn test_topic_small_values() {
+        let mut small = [0u8; 32];
+        small[30..].copy_from_slice(&[0x01, 0x02]);
+        let mut negative = [0xFFu8; 32];
+        negative[31] = 0xFE;
+        let mut selector = [0u8; 32];
+        selector[..4].copy_from_slice(&[0xa9, 0x05, 0x9c, 0xbb]);
+        let topics = [small, negative, selector];
+        let event = ZEventLog::from_topics(&topics, &[]);
+
+        assert_eq!(event.topic_as_u64(0).unwrap(), 0x0102);
+        assert_eq!(event.topic_as_i128(0).unwrap(), 0x0102);
+        assert_eq!(event.topic_as_i128(1).unwrap(), -2);
+        assert_eq!(event.topic_as_bytes::<4>(2).unwrap().0, &[0xa9, 0x05, 0x9c, 0xbb]);
+
+        // Padding is validated as in the data decoders.
+        assert!(event.topic_as_u64(1).is_err());
+        assert!(event.topic_as_i128(2).is_err());
+        assert!(event.topic_as_bytes::<2>(2).is_err());
+        assert!(event.topic_as_bytes::<4>(0).is_err());
+        assert!(event.topic_as_u64(3).is_err());
+    }
+
     #[test]
     fn test_topic_as_address() {
         let mut topic = [0u8; 32];
@@ -0,0 +1,144 @@
+//! Per-signature log counts over a stream of logs.
+//!
+//! [`TopicStats`] only looks at topic 0, so it can profile a large log set
+//! from unknown contracts cheaply and show which events are worth writing
+//! decoders for.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use super::{ZEventDecode, ZEventLog};
+
+/// Log counts keyed by topic 0.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TopicStats {
+    counts: BTreeMap<[u8; 32], u64>,
+    without_topics: u64,
+    total: u64,
+}
+
+impl TopicStats {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one log.
+    pub fn record(&mut self, log: &ZEventLog<'_>) {
+        self.total += 1;
+        match log.event_signature() {
+            Ok(topic0) => *self.counts.entry(*topic0).or_insert(0) += 1,
+            Err(_) => self.without_topics += 1,
+        }
+    }
+
+    /// Number of logs seen with this topic 0.
+    #[inline]
+    pub fn count(&self, topic0: &[u8; 32]) -> u64 {
+        self.counts.get(topic0).copied().unwrap_or(0)
+    }
+
+    /// Number of logs seen whose topic 0 is `T`'s signature hash.
+    /// Always 0 for anonymous events.
+    #[inline]
+    pub fn count_of<'a, T: ZEventDecode<'a>>(&self) -> u64 {
+        if T::ANONYMOUS {
+            0
+        } else {
+            self.count(&T::TOPIC0)
+        }
+    }
+
+    /// Number of logs seen with no topics at all.
+    #[inline]
+    pub fn without_topics(&self) -> u64 {
+        self.without_topics
+    }
+
+    /// Number of logs seen.
+    #[inline]
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Number of distinct topic 0 values seen.
+    #[inline]
+    pub fn distinct(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Iterate over `(topic0, count)` pairs in topic order.
+    pub fn iter(&self) -> impl Iterator<Item = (&[u8; 32], u64)> + '_ {
+        self.counts.iter().map(|(topic, &count)| (topic, count))
+    }
+
+    /// The `n` most frequent topic 0 values, most frequent first.
+    /// Ties are broken by topic order.
+    pub fn most_common(&self, n: usize) -> Vec<([u8; 32], u64)> {
+        let mut ranked: Vec<_> = self
+            .counts
+            .iter()
+            .map(|(topic, &count)| (*topic, count))
+            .collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranked.truncate(n);
+        ranked
+    }
+
+    /// Add the counts of `other` into `self`.
+    pub fn merge(&mut self, other: &TopicStats) {
+        for (topic, &count) in &other.counts {
+            *self.counts.entry(*topic).or_insert(0) += count;
+        }
+        self.without_topics += other.without_topics;
+        self.total += other.total;
+    }
+}
+
+impl<'a> Extend<ZEventLog<'a>> for TopicStats {
+    fn extend<I: IntoIterator<Item = ZEventLog<'a>>>(&mut self, logs: I) {
+        for log in logs {
+            self.record(&log);
+        }
+    }
+}
+
+impl<'a> FromIterator<ZEventLog<'a>> for TopicStats {
+    fn from_iter<I: IntoIterator<Item = ZEventLog<'a>>>(logs: I) -> Self {
+        let mut stats = TopicStats::new();
+        stats.extend(logs);
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topic_stats() {
+        let a = [[0xAA; 32], [0x01; 32]];
+        let b = [[0xBB; 32]];
+        let logs = [
+            ZEventLog::from_topics(&a, &[]),
+            ZEventLog::from_topics(&b, &[]),
+            ZEventLog::from_topics(&a[..1], &[1, 2, 3]),
+            ZEventLog::from_topics(&[], &[]),
+        ];
+
+        let stats: TopicStats = logs.iter().copied().collect();
+        assert_eq!(stats.total(), 4);
+        assert_eq!(stats.without_topics(), 1);
+        assert_eq!(stats.distinct(), 2);
+        assert_eq!(stats.count(&[0xAA; 32]), 2);
+        assert_eq!(stats.count(&[0x01; 32]), 0);
+        assert_eq!(stats.most_common(1), [([0xAA; 32], 2)]);
+        assert_eq!(stats.iter().map(|(_, c)| c).sum::<u64>(), 3);
+
+        let mut doubled = stats.clone();
+        doubled.merge(&stats);
+        assert_eq!(doubled.total(), 8);
+        assert_eq!(doubled.count(&[0xBB; 32]), 2);
+        assert_eq!(doubled.most_common(5), [([0xAA; 32], 4), ([0xBB; 32], 2)]);
+    }
+}
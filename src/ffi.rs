@@ -0,0 +1,336 @@
+//! C ABI for non-Rust consumers.
+//!
+//! Every function returns `ZABI_OK` (0) on success or an error code: the
+//! [`ZError::code`] of the decode failure, [`ZABI_ERR_NULL`] for a null
+//! pointer argument, or [`ZABI_ERR_PANIC`] if decoding panicked. Results are
+//! written through out-parameters, which are left untouched on error.
+//! Byte-string results point into the caller's input buffer and are only
+//! valid while it is.
+//!
+//! The `zabi-ffi` crate in this repository builds these functions as a
+//! static and a shared library, and ships the matching `include/zabi.h`:
+//!
+//! ```text
+//! cargo build --release --manifest-path zabi-ffi/Cargo.toml
+//! cc main.c -Izabi-ffi/include zabi-ffi/target/release/libzabi_ffi.a
+//! ```
+//!
+//! ```c
+//! uint8_t selector[4];
+//! uint8_t amount[32];
+//! if (zabi_read_selector(calldata, len, selector) == ZABI_OK &&
+//!     zabi_decode_u256(calldata + 4, len - 4, 32, amount) == ZABI_OK) { ... }
+//! ```
+
+use crate::decoder;
+use crate::error::ZError;
+
+/// Success.
+pub const ZABI_OK: i32 = 0;
+/// A required pointer argument was null, or `len` is larger than any buffer
+/// can be (over `isize::MAX`).
+pub const ZABI_ERR_NULL: i32 = 254;
+/// Decoding panicked. The decoders check their arithmetic, so this signals a
+/// bug; with the `std` feature it is reported here instead of unwinding
+/// into C, which would abort the process. Without `std` a panic aborts.
+pub const ZABI_ERR_PANIC: i32 = 253;
+
+/// Borrow the caller's buffer; a null pointer is only accepted with `len == 0`.
+/// No Rust slice can be longer than `isize::MAX` bytes.
+unsafe fn input<'a>(data: *const u8, len: usize) -> Option<&'a [u8]> {
+    if data.is_null() {
+        return if len == 0 { Some(&[]) } else { None };
+    }
+    if len > isize::MAX as usize {
+        return None;
+    }
+    Some(core::slice::from_raw_parts(data, len))
+}
+
+/// Run `decode` over the input and hand its result to `write`.
+unsafe fn call<'a, T>(
+    data: *const u8,
+    len: usize,
+    decode: impl FnOnce(&'a [u8]) -> Result<T, ZError>,
+    write: impl FnOnce(T),
+) -> i32 {
+    let Some(data) = input(data, len) else {
+        return ZABI_ERR_NULL;
+    };
+    #[cfg(feature = "std")]
+    let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| decode(data))) {
+        Ok(result) => result,
+        Err(_) => return ZABI_ERR_PANIC,
+    };
+    #[cfg(not(feature = "std"))]
+    let result = decode(data);
+    match result {
+        Ok(value) => {
+            write(value);
+            ZABI_OK
+        }
+        Err(e) => e.code() as i32,
+    }
+}
+
+/// Copy the 4-byte selector at the start of calldata into `out`.
+///
+/// # Safety
+/// `data` must be valid for `len` bytes and `out` valid for 4 bytes of writes.
+#[no_mangle]
+pub unsafe extern "C" fn zabi_read_selector(data: *const u8, len: usize, out: *mut u8) -> i32 {
+    if out.is_null() {
+        return ZABI_ERR_NULL;
+    }
+    call(data, len, decoder::read_selector, |s| {
+        core::ptr::copy_nonoverlapping(s.as_ptr(), out, 4)
+    })
+}
+
+/// Copy the uint256 word at `offset` into `out` (32 bytes, big-endian).
+///
+/// # Safety
+/// `data` must be valid for `len` bytes and `out` valid for 32 bytes of writes.
+#[no_mangle]
+pub unsafe extern "C" fn zabi_decode_u256(
+    data: *const u8,
+    len: usize,
+    offset: usize,
+    out: *mut u8,
+) -> i32 {
+    if out.is_null() {
+        return ZABI_ERR_NULL;
+    }
+    call(
+        data,
+        len,
+        |d| decoder::read_u256(d, offset),
+        |v| core::ptr::copy_nonoverlapping(v.0.as_ptr(), out, 32),
+    )
+}
+
+/// Copy the address at `offset` into `out` (20 bytes).
+///
+/// # Safety
+/// `data` must be valid for `len` bytes and `out` valid for 20 bytes of writes.
+#[no_mangle]
+pub unsafe extern "C" fn zabi_decode_address(
+    data: *const u8,
+    len: usize,
+    offset: usize,
+    out: *mut u8,
+) -> i32 {
+    if out.is_null() {
+        return ZABI_ERR_NULL;
+    }
+    call(
+        data,
+        len,
+        |d| decoder::read_address_from_word(d, offset),
+        |v| core::ptr::copy_nonoverlapping(v.0.as_ptr(), out, 20),
+    )
+}
+
+/// Decode a uint64 at `offset`, rejecting dirty high bytes.
+///
+/// # Safety
+/// `data` must be valid for `len` bytes and `out` valid for a `u64` write.
+#[no_mangle]
+pub unsafe extern "C" fn zabi_decode_u64(
+    data: *const u8,
+    len: usize,
+    offset: usize,
+    out: *mut u64,
+) -> i32 {
+    if out.is_null() {
+        return ZABI_ERR_NULL;
+    }
+    call(data, len, |d| decoder::read_u64(d, offset), |v| *out = v)
+}
+
+/// Decode a bool at `offset`.
+///
+/// # Safety
+/// `data` must be valid for `len` bytes and `out` valid for a `bool` write.
+#[no_mangle]
+pub unsafe extern "C" fn zabi_decode_bool(
+    data: *const u8,
+    len: usize,
+    offset: usize,
+    out: *mut bool,
+) -> i32 {
+    if out.is_null() {
+        return ZABI_ERR_NULL;
+    }
+    call(data, len, |d| decoder::read_bool(d, offset), |v| *out = v.0)
+}
+
+/// Locate the dynamic `bytes` (or `string`) whose offset word is at `offset`.
+/// `out_ptr` receives a pointer into `data`, `out_len` its length.
+///
+/// # Safety
+/// `data` must be valid for `len` bytes; `out_ptr` and `out_len` must be
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn zabi_decode_bytes(
+    data: *const u8,
+    len: usize,
+    offset: usize,
+    out_ptr: *mut *const u8,
+    out_len: *mut usize,
+) -> i32 {
+    if out_ptr.is_null() || out_len.is_null() {
+        return ZABI_ERR_NULL;
+    }
+    call(
+        data,
+        len,
+        |d| decoder::read_bytes(d, offset),
+        |v| {
+            *out_ptr = v.0.as_ptr();
+            *out_len = v.0.len();
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ffi_decode() {
+        let mut data = [0u8; 128];
+        data[31] = 7; // uint256 / uint64 / bool-invalid
+        data[63] = 64; // offset of bytes
+        data[95] = 3; // length
+        data[96..99].copy_from_slice(b"abc");
+
+        unsafe {
+            let mut word = [0u8; 32];
+            assert_eq!(
+                zabi_decode_u256(data.as_ptr(), data.len(), 0, word.as_mut_ptr()),
+                ZABI_OK
+            );
+            assert_eq!(word[31], 7);
+
+            let mut value = 0u64;
+            assert_eq!(
+                zabi_decode_u64(data.as_ptr(), data.len(), 0, &mut value),
+                ZABI_OK
+            );
+            assert_eq!(value, 7);
+
+            let mut flag = false;
+            assert_eq!(
+                zabi_decode_bool(data.as_ptr(), data.len(), 0, &mut flag),
+                ZError::InvalidBool { offset: 0 }.code() as i32
+            );
+            assert!(!flag);
+
+            let mut ptr = core::ptr::null();
+            let mut n = 0usize;
+            assert_eq!(
+                zabi_decode_bytes(data.as_ptr(), data.len(), 32, &mut ptr, &mut n),
+                ZABI_OK
+            );
+            assert_eq!(core::slice::from_raw_parts(ptr, n), b"abc");
+
+            let mut selector = [0u8; 4];
+            assert_eq!(
+                zabi_read_selector(data.as_ptr(), 2, selector.as_mut_ptr()),
+                ZError::OutOfBounds(4, 2).code() as i32
+            );
+            assert_eq!(zabi_decode_u256(data.as_ptr(), 16, 0, word.as_mut_ptr()), 2);
+            assert_eq!(
+                zabi_decode_u256(core::ptr::null(), 32, 0, word.as_mut_ptr()),
+                ZABI_ERR_NULL
+            );
+            assert_eq!(
+                zabi_decode_u256(data.as_ptr(), 32, 0, core::ptr::null_mut()),
+                ZABI_ERR_NULL
+            );
+        }
+    }
+
+    #[test]
+    fn test_ffi_hostile_input() {
+        // A bytes length word of u64::MAX and offsets near usize::MAX fail
+        // with an error code instead of panicking across the C boundary.
+        let mut data = [0u8; 64];
+        data[31] = 32;
+        data[56..].fill(0xff);
+        let out_of_bounds = ZError::OutOfBounds(0, 0).code() as i32;
+
+        unsafe {
+            let mut ptr = core::ptr::null();
+            let mut n = 0usize;
+            assert_eq!(
+                zabi_decode_bytes(data.as_ptr(), data.len(), 0, &mut ptr, &mut n),
+                out_of_bounds
+            );
+            assert!(ptr.is_null());
+
+            let mut word = [0u8; 32];
+            assert_eq!(
+                zabi_decode_u256(data.as_ptr(), data.len(), usize::MAX - 8, word.as_mut_ptr()),
+                out_of_bounds
+            );
+            let mut value = 0u64;
+            assert_eq!(
+                zabi_decode_u64(data.as_ptr(), data.len(), usize::MAX, &mut value),
+                out_of_bounds
+            );
+            assert_eq!(
+                zabi_decode_u256(data.as_ptr(), usize::MAX, 0, word.as_mut_ptr()),
+                ZABI_ERR_NULL
+            );
+        }
+    }
+
+    #[test]
+    fn test_header_lists_every_code() {
+        let header = include_str!("../zabi-ffi/include/zabi.h");
+        let defined = |code: u16| {
+            header.lines().any(|line| {
+                line.starts_with("#define ZABI_ERR_")
+                    && line.split_whitespace().nth(2) == Some(&*std::format!("{}", code))
+            })
+        };
+        #[cfg_attr(not(feature = "alloc"), allow(unused_mut))]
+        let mut errors = std::vec![
+            ZError::InvalidLength(0, 0),
+            ZError::OutOfBounds(0, 0),
+            ZError::InvalidPadding { offset: 0, ty: "" },
+            ZError::InvalidBool { offset: 0 },
+            ZError::InvalidUtf8 { offset: 0 },
+            ZError::OffsetOutOfRange {
+                at: 0,
+                target: 0,
+                len: 0
+            },
+            ZError::SelectorMismatch {
+                expected: [0; 4],
+                found: [0; 4]
+            },
+            ZError::TypeMismatch {
+                expected: [0; 32],
+                found: [0; 32]
+            },
+            ZError::UnknownSelector([0; 4]),
+            ZError::UnknownTopic([0; 32]),
+            ZError::UnknownSchema(0),
+            ZError::Overflow { ty: "" },
+            ZError::Custom(""),
+        ];
+        #[cfg(feature = "alloc")]
+        errors.extend([
+            ZError::Custom("").in_field(0),
+            ZError::Message(alloc::string::String::new()),
+            ZError::Custom("").in_named_field("x"),
+            ZError::Custom("").at_offset(0),
+        ]);
+        for error in errors {
+            assert!(defined(error.code()), "{:?} missing from zabi.h", error);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Client-side log filtering with `eth_getLogs` topic semantics.
+//!
+//! A filter holds up to four topic positions. Each position is a wildcard,
+//! a single value, or a set of accepted values. A log matches when it has at
+//! least as many topics as the filter specifies and every position matches.
+//! Matching only compares topic bytes, so logs can be dropped before any
+//! decoding.
+
+use crate::event::{ZEventDecode, ZEventLog};
+
+/// Accepted values for one topic position.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TopicMatch<'f> {
+    /// Any value (`null` in an `eth_getLogs` filter).
+    #[default]
+    Any,
+    /// Exactly this value.
+    Exact([u8; 32]),
+    /// Any one of these values.
+    OneOf(&'f [[u8; 32]]),
+}
+
+impl<'f> TopicMatch<'f> {
+    /// Returns true if `topic` is accepted.
+    #[inline]
+    pub fn matches(&self, topic: &[u8; 32]) -> bool {
+        match self {
+            TopicMatch::Any => true,
+            TopicMatch::Exact(value) => value == topic,
+            TopicMatch::OneOf(values) => values.iter().any(|v| v == topic),
+        }
+    }
+}
+
+/// Topic filter over [`ZEventLog`]s.
+///
+/// ```
+/// use zabi_rs::{TopicMatch, ZEventFilter, ZEventLog};
+///
+/// let transfer = [0xdd; 32];
+/// let mut to = [0u8; 32];
+/// to[31] = 0xAA;
+///
+/// let filter = ZEventFilter::new().topic0(transfer).topic(2, TopicMatch::Exact(to));
+/// let topics = [transfer, [0u8; 32], to];
+/// assert!(filter.matches(&ZEventLog::from_topics(&topics, &[])));
+/// assert!(!filter.matches(&ZEventLog::from_topics(&topics[..2], &[])));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ZEventFilter<'f> {
+    topics: [TopicMatch<'f>; 4],
+    /// Number of positions specified; trailing wildcards still count.
+    len: usize,
+}
+
+impl<'f> ZEventFilter<'f> {
+    /// A filter that matches every log.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A filter on topic 0 for the event `T`.
+    /// Anonymous events have no signature topic, so the filter matches every log.
+    #[inline]
+    pub fn event<'a, T: ZEventDecode<'a>>() -> Self {
+        if T::ANONYMOUS {
+            Self::new()
+        } else {
+            Self::new().topic0(T::TOPIC0)
+        }
+    }
+
+    /// Require topic 0 (the event signature hash) to equal `topic0`.
+    #[inline]
+    pub fn topic0(self, topic0: [u8; 32]) -> Self {
+        self.topic(0, TopicMatch::Exact(topic0))
+    }
+
+    /// Set the accepted values for topic `index`.
+    ///
+    /// # Panics
+    /// Panics if `index` is 4 or more; logs carry at most four topics.
+    #[inline]
+    pub fn topic(mut self, index: usize, matcher: TopicMatch<'f>) -> Self {
+        assert!(index < 4, "a log has at most 4 topics");
+        self.topics[index] = matcher;
+        self.len = self.len.max(index + 1);
+        self
+    }
+
+    /// Returns the matcher for topic `index`.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&TopicMatch<'f>> {
+        self.topics[..self.len].get(index)
+    }
+
+    /// Returns true if `log` passes the filter.
+    pub fn matches(&self, log: &ZEventLog<'_>) -> bool {
+        if log.topic_count() < self.len {
+            return false;
+        }
+        self.topics[..self.len]
+            .iter()
+            .enumerate()
+            .all(|(i, matcher)| match log.raw_topic(i) {
+                Ok(topic) => matcher.matches(topic),
+                Err(_) => false,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn topic(last: u8) -> [u8; 32] {
+        let mut t = [0u8; 32];
+        t[31] = last;
+        t
+    }
+
+    #[test]
+    fn test_empty_filter_matches_all() {
+        let filter = ZEventFilter::new();
+        assert!(filter.matches(&ZEventLog::from_topics(&[], &[])));
+        assert!(filter.matches(&ZEventLog::from_topics(&[topic(1)], &[])));
+    }
+
+    #[test]
+    fn test_filter_positions() {
+        let accepted = [topic(2), topic(3)];
+        let filter = ZEventFilter::new()
+            .topic0(topic(1))
+            .topic(2, TopicMatch::OneOf(&accepted));
+
+        assert!(filter.matches(&ZEventLog::from_topics(
+            &[topic(1), topic(9), topic(3)],
+            &[]
+        )));
+        assert!(filter.matches(&ZEventLog::from_topics(
+            &[topic(1), topic(9), topic(2), topic(4)],
+            &[]
+        )));
+        assert!(!filter.matches(&ZEventLog::from_topics(
+            &[topic(1), topic(9), topic(4)],
+            &[]
+        )));
+        assert!(!filter.matches(&ZEventLog::from_topics(
+            &[topic(5), topic(9), topic(2)],
+            &[]
+        )));
+        // Too few topics, even though the missing position would be checked.
+        assert!(!filter.matches(&ZEventLog::from_topics(&[topic(1), topic(9)], &[])));
+    }
+
+    #[test]
+    fn test_trailing_wildcard_requires_topic() {
+        let filter = ZEventFilter::new()
+            .topic0(topic(1))
+            .topic(1, TopicMatch::Any);
+        assert_eq!(filter.get(1), Some(&TopicMatch::Any));
+        assert!(filter.get(2).is_none());
+        assert!(!filter.matches(&ZEventLog::from_topics(&[topic(1)], &[])));
+        assert!(filter.matches(&ZEventLog::from_topics(&[topic(1), topic(0)], &[])));
+    }
+}
@@ -0,0 +1,283 @@
+//! Bulk import of 4byte-directory signature dumps (requires the `std` feature).
+//!
+//! Two shapes are common:
+//!
+//! - CSV or tab-separated exports, one signature per line, such as the
+//!   directory's own `id,created_at,text_signature,hex_signature,...` dump or
+//!   plain `0xa9059cbb,transfer(address,uint256)` lists.
+//! - JSON (with `serde`): an API results page,
+//!   `{"results": [{"text_signature": ..., "hex_signature": ...}]}`, or a map
+//!   from selector to one or more signatures. Deserialize it into a
+//!   [`FourByteDump`] with any serde format crate.
+//!
+//! Lines or entries that cannot be parsed are counted and skipped, since
+//! public dumps contain plenty of junk.
+
+use std::io::{self, BufRead};
+
+use crate::registry::SelectorRegistry;
+
+/// Outcome of an import.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ImportStats {
+    /// Signatures added (or already present) in the registry.
+    pub loaded: usize,
+    /// Lines or entries without a usable selector and signature.
+    pub skipped: usize,
+}
+
+impl SelectorRegistry {
+    /// Import a CSV or tab-separated dump. Each line needs a `0x`-prefixed
+    /// selector and a function signature, in any column order; header and
+    /// blank lines are skipped. Without a selector column the selector is
+    /// computed from the signature when the `keccak` feature is enabled.
+    pub fn load_4byte_csv<R: BufRead>(&mut self, reader: R) -> io::Result<ImportStats> {
+        let mut stats = ImportStats::default();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match find_signature(&line) {
+                Some(signature) if self.add(find_selector(&line), signature) => stats.loaded += 1,
+                _ => stats.skipped += 1,
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Register one dump entry; `false` if it was unusable.
+    fn add(&mut self, selector: Option<[u8; 4]>, signature: &str) -> bool {
+        match selector {
+            Some(selector) => self.insert(selector, signature).is_ok(),
+            #[cfg(feature = "keccak")]
+            None => self.register(signature).is_ok(),
+            #[cfg(not(feature = "keccak"))]
+            None => false,
+        }
+    }
+}
+
+/// The first `0x` followed by exactly 8 hex digits.
+fn find_selector(line: &str) -> Option<[u8; 4]> {
+    let bytes = line.as_bytes();
+    (0..bytes.len()).find_map(|i| {
+        let candidate = bytes.get(i..i + 10)?;
+        let boundary = |b: Option<&u8>| !b.is_some_and(u8::is_ascii_alphanumeric);
+        if &candidate[..2] != b"0x"
+            || !boundary(i.checked_sub(1).and_then(|j| bytes.get(j)))
+            || !boundary(bytes.get(i + 10))
+        {
+            return None;
+        }
+        // Work on bytes: the digits may be followed by a multibyte character.
+        let mut selector = [0u8; 4];
+        for (byte, pair) in selector.iter_mut().zip(candidate[2..].chunks_exact(2)) {
+            *byte = crate::hex::hex_digit(pair[0])? << 4 | crate::hex::hex_digit(pair[1])?;
+        }
+        Some(selector)
+    })
+}
+
+/// The identifier before the first `(` through its matching `)`.
+fn find_signature(line: &str) -> Option<&str> {
+    let open = line.find('(')?;
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+    let start = line[..open].rfind(|c| !is_ident(c)).map_or(0, |i| i + 1);
+    if start == open {
+        return None;
+    }
+    let mut depth = 0usize;
+    for (i, c) in line[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&line[start..open + i + 1]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(feature = "serde")]
+pub use self::json::FourByteDump;
+
+#[cfg(feature = "serde")]
+mod json {
+    use std::collections::BTreeMap;
+    use std::string::String;
+    use std::vec::Vec;
+
+    use serde::Deserialize;
+
+    use super::{find_selector, ImportStats};
+    use crate::registry::SelectorRegistry;
+
+    /// A JSON signature dump; see the [module docs](super).
+    #[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+    #[serde(untagged)]
+    pub enum FourByteDump {
+        /// An API results page.
+        Page { results: Vec<PageEntry> },
+        /// Selector to signature(s).
+        Map(BTreeMap<String, OneOrMany>),
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+    pub struct PageEntry {
+        pub text_signature: String,
+        #[serde(default)]
+        pub hex_signature: Option<String>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+    #[serde(untagged)]
+    pub enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    impl SelectorRegistry {
+        /// Register every signature of a JSON dump.
+        pub fn load_4byte_json(&mut self, dump: &FourByteDump) -> ImportStats {
+            let mut stats = ImportStats::default();
+            let mut count = |ok: bool| {
+                if ok {
+                    stats.loaded += 1
+                } else {
+                    stats.skipped += 1
+                }
+            };
+            match dump {
+                FourByteDump::Page { results } => {
+                    for entry in results {
+                        let selector = entry.hex_signature.as_deref().and_then(find_selector);
+                        count(self.add(selector, &entry.text_signature));
+                    }
+                }
+                FourByteDump::Map(map) => {
+                    for (selector, signatures) in map {
+                        let selector = find_selector(selector);
+                        let signatures = match signatures {
+                            OneOrMany::One(s) => core::slice::from_ref(s),
+                            OneOrMany::Many(v) => v.as_slice(),
+                        };
+                        for signature in signatures {
+                            count(selector.is_some() && self.add(selector, signature));
+                        }
+                    }
+                }
+            }
+            stats
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSFER: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
+    #[test]
+    fn test_load_csv() {
+        let dump = "id,created_at,text_signature,hex_signature,bytes_signature\n\
+                    145,2016-07-09,\"transfer(address,uint256)\",0xa9059cbb,x\n\
+                    \n\
+                    0x095ea7b3\tapprove(address,uint256)\n\
+                    0x12345678,not a signature\n\
+                    0xdeadbeef,broken(uint7)\n";
+        let mut registry = SelectorRegistry::new();
+        let stats = registry.load_4byte_csv(dump.as_bytes()).unwrap();
+        assert_eq!(
+            stats,
+            ImportStats {
+                loaded: 2,
+                skipped: 3
+            }
+        );
+        assert_eq!(
+            registry.lookup(&TRANSFER)[0].signature(),
+            "transfer(address,uint256)"
+        );
+        assert_eq!(
+            registry.lookup(&[0x09, 0x5e, 0xa7, 0xb3])[0].name,
+            "approve"
+        );
+    }
+
+    #[test]
+    fn test_load_csv_multibyte_line() {
+        let mut registry = SelectorRegistry::new();
+        let stats = registry
+            .load_4byte_csv(
+                "0x1234567é foo(uint256)
+"
+                .as_bytes(),
+            )
+            .unwrap();
+        assert_eq!(stats.loaded + stats.skipped, 1);
+        assert!(registry.lookup(&[0x12, 0x34, 0x56, 0x7e]).is_empty());
+    }
+
+    #[test]
+    fn test_find_selector_and_signature() {
+        assert_eq!(find_selector("x,0xa9059cbb"), Some(TRANSFER));
+        assert_eq!(find_selector("0xa9059cbb00"), None);
+        assert_eq!(find_selector("0x1234567é foo(uint256)"), None);
+        assert_eq!(find_selector("é0xa9059cbbé"), Some(TRANSFER));
+        assert_eq!(
+            find_signature("\"f((uint8,bool)[],bytes)\",0x01"),
+            Some("f((uint8,bool)[],bytes)")
+        );
+        assert_eq!(find_signature("(uint8)"), None);
+        assert_eq!(find_signature("f(uint8"), None);
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn test_load_csv_without_selectors() {
+        let mut registry = SelectorRegistry::new();
+        let stats = registry
+            .load_4byte_csv("transfer(address,uint256)\n".as_bytes())
+            .unwrap();
+        assert_eq!(stats.loaded, 1);
+        assert_eq!(registry.lookup(&TRANSFER).len(), 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_load_json() {
+        let page: FourByteDump = serde_json::from_str(
+            r#"{"count": 1, "next": null, "results": [
+                {"id": 145, "text_signature": "transfer(address,uint256)", "hex_signature": "0xa9059cbb"}]}"#,
+        )
+        .unwrap();
+        let map: FourByteDump = serde_json::from_str(
+            r#"{"0xa9059cbb": ["transfer(address,uint256)", "many_msg_babbage(bytes1)"],
+                "0x095ea7b3": "approve(address,uint256)", "nonsense": "f()"}"#,
+        )
+        .unwrap();
+        let mut registry = SelectorRegistry::new();
+        assert_eq!(
+            registry.load_4byte_json(&page),
+            ImportStats {
+                loaded: 1,
+                skipped: 0
+            }
+        );
+        assert_eq!(
+            registry.load_4byte_json(&map),
+            ImportStats {
+                loaded: 3,
+                skipped: 1
+            }
+        );
+        assert_eq!(registry.lookup(&TRANSFER).len(), 2);
+        assert_eq!(registry.len(), 2);
+    }
+}
@@ -0,0 +1,326 @@
+//! Fuzz input generators (requires the `arbitrary` feature).
+//!
+//! Decoders are best fuzzed with inputs close to the format: random bytes
+//! rarely get past the first offset word. [`valid_encoding`] builds the
+//! canonical encoding of a random value of a given [`DynType`], with the
+//! crate's own encoder, and [`malformed_encoding`] breaks such an encoding
+//! in one targeted way. [`AbiInput`] and [`MalformedAbiInput`] pick the type
+//! as well, for `fuzz_target!(|input: AbiInput| ...)`.
+//!
+//! Generated types stay small: at most [`MAX_DEPTH`] levels of nesting and
+//! [`MAX_LEN`] elements per array or tuple.
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::dynamic::{encode_dyn, DynType, DynValue};
+use crate::types::{ZAddress, ZBytes, ZInt256, ZString, ZU256};
+
+/// Deepest nesting of arrays and tuples in a generated [`DynType`].
+pub const MAX_DEPTH: usize = 4;
+/// Most elements in a generated array or tuple, and in a `T[N]` type.
+pub const MAX_LEN: usize = 4;
+
+/// Generates types up to [`MAX_DEPTH`] levels deep. Fixed arrays and
+/// tuples are never empty, so every type has at least one head word.
+impl<'a> Arbitrary<'a> for DynType {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        arbitrary_type(u, 0)
+    }
+}
+
+fn arbitrary_type(u: &mut Unstructured<'_>, depth: usize) -> Result<DynType> {
+    let kinds = if depth < MAX_DEPTH { 10 } else { 7 };
+    Ok(match u.choose_index(kinds)? {
+        0 => DynType::Uint(8 * u.int_in_range(1..=32)?),
+        1 => DynType::Int(8 * u.int_in_range(1..=32)?),
+        2 => DynType::Address,
+        3 => DynType::Bool,
+        4 => DynType::Bytes,
+        5 => DynType::String,
+        6 => DynType::FixedBytes(u.int_in_range(1..=32)?),
+        7 => DynType::Array(Box::new(arbitrary_type(u, depth + 1)?)),
+        8 => DynType::FixedArray(
+            Box::new(arbitrary_type(u, depth + 1)?),
+            u.int_in_range(1..=MAX_LEN)?,
+        ),
+        _ => {
+            let len = u.int_in_range(1..=MAX_LEN)?;
+            DynType::Tuple(
+                (0..len)
+                    .map(|_| arbitrary_type(u, depth + 1))
+                    .collect::<Result<_>>()?,
+            )
+        }
+    })
+}
+
+/// A type and the canonical encoding of a value of it, as [`encode_dyn`]
+/// writes it: [`crate::decode_dyn`] at offset 0 accepts `data`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiInput {
+    pub ty: DynType,
+    pub data: Vec<u8>,
+}
+
+impl<'a> Arbitrary<'a> for AbiInput {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let ty = DynType::arbitrary(u)?;
+        let data = valid_encoding(u, &ty)?;
+        Ok(AbiInput { ty, data })
+    }
+}
+
+/// A type and an encoding broken as `corruption` says.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MalformedAbiInput {
+    pub ty: DynType,
+    pub data: Vec<u8>,
+    pub corruption: Corruption,
+}
+
+impl<'a> Arbitrary<'a> for MalformedAbiInput {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let ty = DynType::arbitrary(u)?;
+        let (data, corruption) = malformed_encoding(u, &ty)?;
+        Ok(MalformedAbiInput {
+            ty,
+            data,
+            corruption,
+        })
+    }
+}
+
+/// How [`malformed_encoding`] broke an encoding. All but `BitFlip` are
+/// guaranteed to make [`crate::decode_dyn`] fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Corruption {
+    /// Cut shorter than the type's head.
+    Truncated,
+    /// A non-canonical padding byte in the first word of a static value.
+    DirtyPadding,
+    /// A high byte set in the offset word of a dynamic value.
+    OffsetOverflow,
+    /// A high byte set in the length word of a `bytes`, `string` or `T[]`.
+    LengthOverflow,
+    /// The byte at `index` XORed with `mask`, which may still decode.
+    BitFlip { index: usize, mask: u8 },
+}
+
+/// The canonical encoding of a random value of `ty`. Arrays get at most
+/// [`MAX_LEN`] elements; `bytes` and `string` values are taken from `u`.
+pub fn valid_encoding(u: &mut Unstructured<'_>, ty: &DynType) -> Result<Vec<u8>> {
+    let value = OwnedValue::arbitrary_of(u, ty)?;
+    let mut data = Vec::new();
+    // Generated values always fit their type.
+    encode_dyn(ty, &value.as_dyn(), &mut data).map_err(|_| arbitrary::Error::IncorrectFormat)?;
+    Ok(data)
+}
+
+/// A [`valid_encoding`] of `ty` broken in one way that applies to it, e.g.
+/// an oversized length word for a `bytes`, or dirty padding for a `uint8`.
+pub fn malformed_encoding(u: &mut Unstructured<'_>, ty: &DynType) -> Result<(Vec<u8>, Corruption)> {
+    let mut data = valid_encoding(u, ty)?;
+    let mut options = Vec::new();
+    if ty.head_size() > 0 && !data.is_empty() {
+        options.push(Corruption::Truncated);
+    }
+    let padding = padding_byte(ty);
+    if padding.is_some() {
+        options.push(Corruption::DirtyPadding);
+    }
+    if ty.is_dynamic() {
+        options.push(Corruption::OffsetOverflow);
+    }
+    if matches!(ty, DynType::Bytes | DynType::String | DynType::Array(_)) {
+        options.push(Corruption::LengthOverflow);
+    }
+    if !data.is_empty() {
+        options.push(Corruption::BitFlip { index: 0, mask: 0 });
+    }
+    let corruption = match *u.choose(&options)? {
+        Corruption::Truncated => {
+            let max = ty.head_size().min(data.len()) - 1;
+            data.truncate(u.int_in_range(0..=max)?);
+            Corruption::Truncated
+        }
+        Corruption::DirtyPadding => {
+            let (index, mask) = padding.expect("checked above");
+            data[index] ^= mask;
+            Corruption::DirtyPadding
+        }
+        Corruption::OffsetOverflow => {
+            data[0] = u.int_in_range(1..=0xff)?;
+            Corruption::OffsetOverflow
+        }
+        Corruption::LengthOverflow => {
+            data[32] = u.int_in_range(1..=0xff)?;
+            Corruption::LengthOverflow
+        }
+        Corruption::BitFlip { .. } => {
+            let index = u.choose_index(data.len())?;
+            let mask = u.int_in_range(1..=0xff)?;
+            data[index] ^= mask;
+            Corruption::BitFlip { index, mask }
+        }
+    };
+    Ok((data, corruption))
+}
+
+/// A byte of the first word of a static `ty` and a mask that makes its
+/// padding non-canonical, if that word has padding.
+fn padding_byte(ty: &DynType) -> Option<(usize, u8)> {
+    match ty {
+        DynType::Uint(bits) | DynType::Int(bits) if *bits < 256 => Some((0, 0x01)),
+        DynType::Bool => Some((0, 0x01)),
+        DynType::FixedBytes(n) if *n < 32 => Some((31, 0x01)),
+        DynType::FixedArray(elem, n) if *n > 0 && !elem.is_dynamic() => padding_byte(elem),
+        DynType::Tuple(members) if !ty.is_dynamic() => members.first().and_then(padding_byte),
+        _ => None,
+    }
+}
+
+/// A generated value, owning the words and strings a [`DynValue`] borrows.
+enum OwnedValue {
+    Uint([u8; 32]),
+    Int([u8; 32]),
+    Address([u8; 20]),
+    Bool(bool),
+    Bytes(Vec<u8>),
+    String(String),
+    FixedBytes(Vec<u8>),
+    Array(Vec<OwnedValue>),
+    Tuple(Vec<OwnedValue>),
+}
+
+impl OwnedValue {
+    fn arbitrary_of(u: &mut Unstructured<'_>, ty: &DynType) -> Result<Self> {
+        Ok(match ty {
+            DynType::Uint(bits) => {
+                let mut word = <[u8; 32]>::arbitrary(u)?;
+                word[..32 - bits / 8].fill(0);
+                OwnedValue::Uint(word)
+            }
+            DynType::Int(bits) => {
+                let mut word = <[u8; 32]>::arbitrary(u)?;
+                let pad = 32 - bits / 8;
+                let fill = if word[pad] & 0x80 != 0 { 0xff } else { 0x00 };
+                word[..pad].fill(fill);
+                OwnedValue::Int(word)
+            }
+            DynType::Address => OwnedValue::Address(<[u8; 20]>::arbitrary(u)?),
+            DynType::Bool => OwnedValue::Bool(bool::arbitrary(u)?),
+            DynType::Bytes => OwnedValue::Bytes(<&[u8]>::arbitrary(u)?.to_vec()),
+            DynType::String => OwnedValue::String(String::arbitrary(u)?),
+            DynType::FixedBytes(n) => OwnedValue::FixedBytes(
+                u.bytes(*n)
+                    .map_or_else(|_| alloc::vec![0; *n], <[u8]>::to_vec),
+            ),
+            DynType::Array(elem) => {
+                let len = u.int_in_range(0..=MAX_LEN)?;
+                OwnedValue::Array(
+                    (0..len)
+                        .map(|_| Self::arbitrary_of(u, elem))
+                        .collect::<Result<_>>()?,
+                )
+            }
+            DynType::FixedArray(elem, n) => OwnedValue::Array(
+                (0..*n)
+                    .map(|_| Self::arbitrary_of(u, elem))
+                    .collect::<Result<_>>()?,
+            ),
+            DynType::Tuple(members) => OwnedValue::Tuple(
+                members
+                    .iter()
+                    .map(|m| Self::arbitrary_of(u, m))
+                    .collect::<Result<_>>()?,
+            ),
+        })
+    }
+
+    fn as_dyn(&self) -> DynValue<'_> {
+        match self {
+            OwnedValue::Uint(word) => DynValue::Uint(ZU256(word)),
+            OwnedValue::Int(word) => DynValue::Int(ZInt256(word)),
+            OwnedValue::Address(addr) => DynValue::Address(ZAddress(addr)),
+            OwnedValue::Bool(v) => DynValue::Bool(*v),
+            OwnedValue::Bytes(bytes) => DynValue::Bytes(ZBytes(bytes)),
+            OwnedValue::String(s) => DynValue::String(ZString(s)),
+            OwnedValue::FixedBytes(bytes) => DynValue::FixedBytes(bytes),
+            OwnedValue::Array(items) => {
+                DynValue::Array(items.iter().map(OwnedValue::as_dyn).collect())
+            }
+            OwnedValue::Tuple(items) => {
+                DynValue::Tuple(items.iter().map(OwnedValue::as_dyn).collect())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamic::decode_dyn;
+
+    /// Deterministic pseudo-random seed bytes.
+    fn seed(n: u32) -> Vec<u8> {
+        let mut state = n.wrapping_mul(0x9e37_79b9) | 1;
+        (0..4096)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_encodings_round_trip() {
+        for n in 0..200 {
+            let bytes = seed(n);
+            let input = AbiInput::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+            let value = decode_dyn(&input.ty, &input.data, 0)
+                .unwrap_or_else(|e| panic!("{} failed to decode: {:?}", input.ty, e));
+            let mut again = Vec::new();
+            encode_dyn(&input.ty, &value, &mut again).unwrap();
+            assert_eq!(again, input.data, "{}", input.ty);
+        }
+    }
+
+    #[test]
+    fn test_malformed_encodings_fail() {
+        let mut seen = Vec::new();
+        for n in 0..400 {
+            let bytes = seed(n);
+            let input = MalformedAbiInput::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+            let result = decode_dyn(&input.ty, &input.data, 0);
+            match input.corruption {
+                Corruption::BitFlip { index, .. } => assert!(index < input.data.len()),
+                kind => {
+                    assert!(result.is_err(), "{} decoded after {:?}", input.ty, kind);
+                    if !seen.contains(&kind) {
+                        seen.push(kind);
+                    }
+                }
+            }
+        }
+        assert_eq!(seen.len(), 4, "{:?}", seen);
+    }
+
+    #[test]
+    fn test_shape_specific() {
+        let bytes = seed(7);
+        let mut u = Unstructured::new(&bytes);
+        let ty = DynType::parse("(uint8,string)[]").unwrap();
+        let data = valid_encoding(&mut u, &ty).unwrap();
+        assert!(decode_dyn(&ty, &data, 0).is_ok());
+
+        // An exhausted source still yields a valid (minimal) encoding.
+        let data = valid_encoding(&mut Unstructured::new(&[]), &ty).unwrap();
+        assert!(decode_dyn(&ty, &data, 0).is_ok());
+    }
+}
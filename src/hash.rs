@@ -0,0 +1,70 @@
+//! Keccak-256 (requires the `keccak` feature).
+//!
+//! The hash behind selectors, event topics, EIP-55 checksums and EIP-712
+//! digests, exposed so callers need no second hashing crate. This is the
+//! original Keccak padding used by Ethereum, not NIST SHA3-256.
+
+use tiny_keccak::{Hasher, Keccak};
+
+/// Keccak-256 of `bytes`.
+pub fn keccak256(bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(bytes);
+    hasher.finalize()
+}
+
+/// The 4-byte selector of a function or error signature such as
+/// `"transfer(address,uint256)"`. Whitespace is ignored, as in
+/// [`signature_hash`](crate::signature_hash).
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = crate::event::signature_hash(signature);
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Incremental Keccak-256, for hashing data that is not contiguous, such as
+/// an EIP-712 struct assembled field by field.
+#[derive(Clone)]
+pub struct Keccak256(Keccak);
+
+impl Keccak256 {
+    pub fn new() -> Self {
+        Keccak256(Keccak::v256())
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) -> &mut Self {
+        self.0.update(bytes);
+        self
+    }
+
+    pub fn finalize(self) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        self.0.finalize(&mut out);
+        out
+    }
+}
+
+impl Default for Keccak256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keccak256() {
+        let empty = keccak256(b"");
+        assert_eq!(empty[..4], [0xc5, 0xd2, 0x46, 0x01]);
+        assert_eq!(empty[28..], [0x5d, 0x85, 0xa4, 0x70]);
+
+        let mut hasher = Keccak256::new();
+        hasher.update(b"transfer(").update(b"address,uint256)");
+        assert_eq!(hasher.finalize(), keccak256(b"transfer(address,uint256)"));
+        assert_eq!(
+            selector("transfer(address, uint256)"),
+            [0xa9, 0x05, 0x9c, 0xbb]
+        );
+    }
+}
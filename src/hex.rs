@@ -0,0 +1,222 @@
+//! Hex conversion without allocation, and decoding of hex JSON-RPC results
+//! such as `eth_call` return data.
+//!
+//! ```
+//! use zabi_rs::hex;
+//!
+//! let mut bytes = [0u8; 4];
+//! let selector = hex::decode_into("0xa9059cbb", &mut bytes).unwrap();
+//! let mut digits = [0u8; 8];
+//! assert_eq!(hex::encode_into(selector, &mut digits).unwrap(), "a9059cbb");
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
+use crate::ZDecode;
+use crate::ZError;
+
+#[inline]
+pub(crate) fn hex_digit(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Decodes `0x`-prefixed (or bare) hex into `out`, which must be exactly
+/// half the digit count long.
+pub(crate) fn parse_hex_into(s: &str, out: &mut [u8]) -> Option<()> {
+    let digits = s.strip_prefix("0x").unwrap_or(s).as_bytes();
+    if digits.len() != out.len() * 2 {
+        return None;
+    }
+    for (byte, pair) in out.iter_mut().zip(digits.chunks_exact(2)) {
+        *byte = hex_digit(pair[0])? << 4 | hex_digit(pair[1])?;
+    }
+    Some(())
+}
+
+/// Decodes `0x`-prefixed (or bare) hex from `src` into the front of `dst`,
+/// returning the decoded bytes. Both digit cases are accepted.
+///
+/// Fails on an odd digit count or a non-hex character, or with
+/// [`ZError::OutOfBounds`] if `dst` is shorter than half the digit count.
+pub fn decode_into<'d>(src: &str, dst: &'d mut [u8]) -> Result<&'d [u8], ZError> {
+    let digits = src.strip_prefix("0x").unwrap_or(src);
+    if !digits.len().is_multiple_of(2) {
+        return Err(ZError::Custom("odd number of hex digits"));
+    }
+    let len = digits.len() / 2;
+    let available = dst.len();
+    let out = dst
+        .get_mut(..len)
+        .ok_or(ZError::OutOfBounds(len, available))?;
+    parse_hex_into(digits, out).ok_or(ZError::Custom("invalid hex digit"))?;
+    Ok(out)
+}
+
+/// Writes `src` as lowercase hex digits, without a `0x` prefix, into the
+/// front of `dst`, which needs two bytes per input byte.
+pub fn encode_into<'d>(src: &[u8], dst: &'d mut [u8]) -> Result<&'d str, ZError> {
+    let len = src.len() * 2;
+    let available = dst.len();
+    let out = dst
+        .get_mut(..len)
+        .ok_or(ZError::OutOfBounds(len, available))?;
+    write_digits(src, out);
+    // Only ASCII hex digits were written.
+    Ok(core::str::from_utf8(out).unwrap())
+}
+
+/// Writes `bytes` as lowercase hex digits into `out`, two per byte.
+#[inline]
+pub(crate) fn write_digits(bytes: &[u8], out: &mut [u8]) {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    for (pair, byte) in out.chunks_exact_mut(2).zip(bytes) {
+        pair[0] = DIGITS[(byte >> 4) as usize];
+        pair[1] = DIGITS[(byte & 0x0f) as usize];
+    }
+}
+
+/// Decodes `0x`-prefixed (or bare) hex of any even length.
+#[cfg(feature = "alloc")]
+pub(crate) fn parse_hex_vec(s: &str) -> Option<Vec<u8>> {
+    let digits = s.strip_prefix("0x").unwrap_or(s);
+    if !digits.len().is_multiple_of(2) {
+        return None;
+    }
+    let mut out = alloc::vec![0u8; digits.len() / 2];
+    parse_hex_into(digits, &mut out)?;
+    Some(out)
+}
+
+/// Hex-decode a JSON-RPC result such as `eth_call` return data into `buf`,
+/// replacing its contents, then decode a `T` borrowing from it. The buffer's
+/// allocation is reused, so one `buf` can serve a stream of responses.
+///
+/// ```
+/// let mut buf = Vec::new();
+/// let hex = "0x000000000000000000000000000000000000000000000000000000000000002a";
+/// let value: u64 = zabi_rs::decode_rpc_hex(hex, &mut buf).unwrap();
+/// assert_eq!(value, 42);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_rpc_hex<'b, T: ZDecode<'b>>(s: &str, buf: &'b mut Vec<u8>) -> Result<T, ZError> {
+    let digits = s.strip_prefix("0x").unwrap_or(s);
+    buf.clear();
+    if !digits.len().is_multiple_of(2) {
+        return Err(ZError::Custom("result is not a hex string"));
+    }
+    buf.resize(digits.len() / 2, 0);
+    parse_hex_into(digits, buf).ok_or(ZError::Custom("result is not a hex string"))?;
+    T::decode_params(buf)
+}
+
+/// Owned bytes of a hex JSON-RPC result, for when there is no buffer to
+/// lend: views decoded from it borrow the `HexBuf`.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct HexBuf(pub Vec<u8>);
+
+#[cfg(feature = "alloc")]
+impl HexBuf {
+    pub fn from_hex(s: &str) -> Result<Self, ZError> {
+        parse_hex_vec(s)
+            .map(HexBuf)
+            .ok_or(ZError::Custom("result is not a hex string"))
+    }
+
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Decode a complete parameter encoding, as [`ZDecode::decode_params`].
+    #[inline]
+    pub fn decode<'a, T: ZDecode<'a>>(&'a self) -> Result<T, ZError> {
+        T::decode_params(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "alloc")]
+    use crate::types::ZString;
+
+    #[test]
+    fn test_decode_into() {
+        let mut buf = [0u8; 8];
+        assert_eq!(
+            decode_into("0xA9059cbb", &mut buf).unwrap(),
+            &[0xa9, 0x05, 0x9c, 0xbb]
+        );
+        assert_eq!(decode_into("ff", &mut buf).unwrap(), &[0xff]);
+        assert!(decode_into("0x", &mut buf).unwrap().is_empty());
+        assert!(matches!(
+            decode_into("0xabc", &mut buf),
+            Err(ZError::Custom(_))
+        ));
+        assert!(matches!(
+            decode_into("0xzz", &mut buf),
+            Err(ZError::Custom(_))
+        ));
+        assert!(matches!(
+            decode_into("0x000000000000000000", &mut buf),
+            Err(ZError::OutOfBounds(9, 8))
+        ));
+    }
+
+    #[test]
+    fn test_encode_into() {
+        let mut buf = [0u8; 10];
+        assert_eq!(
+            encode_into(&[0x00, 0xab, 0x10], &mut buf).unwrap(),
+            "00ab10"
+        );
+        assert_eq!(encode_into(&[], &mut buf).unwrap(), "");
+        assert!(matches!(
+            encode_into(&[0; 6], &mut buf),
+            Err(ZError::OutOfBounds(12, 10))
+        ));
+    }
+
+    /// `abi.encode("hi")`.
+    #[cfg(feature = "alloc")]
+    const HI: &str = "0x0000000000000000000000000000000000000000000000000000000000000020\
+                      0000000000000000000000000000000000000000000000000000000000000002\
+                      6869000000000000000000000000000000000000000000000000000000000000";
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_decode_rpc_hex() {
+        let mut buf = Vec::new();
+        let s: ZString<'_> = decode_rpc_hex(HI, &mut buf).unwrap();
+        assert_eq!(s.as_str(), "hi");
+        assert_eq!(buf.len(), 96);
+        let ptr = buf.as_ptr();
+
+        let (s,): (ZString<'_>,) = decode_rpc_hex(HI, &mut buf).unwrap();
+        assert_eq!(s.as_str(), "hi");
+        // Same-sized responses land in the existing allocation.
+        assert_eq!(buf.as_ptr(), ptr);
+
+        assert!(decode_rpc_hex::<u64>("0x123", &mut buf).is_err());
+        assert!(decode_rpc_hex::<u64>("0xzz", &mut buf).is_err());
+        assert!(decode_rpc_hex::<u64>("0x", &mut buf).is_err());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_hex_buf() {
+        let ret = HexBuf::from_hex(HI).unwrap();
+        assert_eq!(ret.decode::<ZString<'_>>().unwrap().as_str(), "hi");
+        assert_eq!(ret.as_bytes()[31], 0x20);
+        assert!(HexBuf::from_hex("0x1").is_err());
+        assert!(HexBuf::from_hex("").unwrap().as_bytes().is_empty());
+    }
+}
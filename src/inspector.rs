@@ -0,0 +1,161 @@
+//! Views over revm inspector inputs (requires the `revm` feature).
+//!
+//! An EVM tracer built on revm sees calls as [`CallInputs`] and logs as
+//! [`Log`]. [`event_log`] borrows a log as a [`ZEventLog`], and [`ZCallInput`]
+//! splits a call into its selector and ABI-encoded arguments, so the typed
+//! decoders run on the tracer's buffers without copying.
+//!
+//! Call data inside a call frame usually lives in revm's shared memory, so
+//! it is resolved by the caller and passed in next to the inputs:
+//!
+//! ```ignore
+//! fn call(&mut self, context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+//!     let calldata = inputs.input.as_bytes(context);
+//!     if let Ok(call) = ZCallInput::new(inputs, &calldata) {
+//!         if call.selector() == &TRANSFER {
+//!             let (to, amount) = call.decode_args::<(ZAddress, ZU256)>().ok()?;
+//!             // ...
+//!         }
+//!     }
+//!     None
+//! }
+//! ```
+
+use revm_interpreter::primitives::Log;
+use revm_interpreter::CallInputs;
+
+use crate::decoder::read_selector;
+use crate::error::ZError;
+use crate::event::ZEventLog;
+use crate::types::ZAddress;
+use crate::ZDecode;
+
+/// Borrow a revm log as a [`ZEventLog`], with the address attached.
+#[inline]
+pub fn event_log(log: &Log) -> ZEventLog<'_> {
+    ZEventLog::from_revm(log.data.topics(), &log.data.data).with_address(&log.address.0 .0)
+}
+
+/// A call seen by an inspector: the addresses involved, the selector and
+/// the ABI-encoded arguments.
+#[derive(Clone, Copy, Debug)]
+pub struct ZCallInput<'a> {
+    /// The account whose storage the call runs against.
+    pub target: ZAddress<'a>,
+    /// The account whose code runs; differs from `target` for
+    /// `DELEGATECALL` and `CALLCODE`.
+    pub code_address: ZAddress<'a>,
+    pub caller: ZAddress<'a>,
+    selector: &'a [u8; 4],
+    args: &'a [u8],
+}
+
+impl<'a> ZCallInput<'a> {
+    /// Split `calldata`, the resolved input of `inputs`, into selector and
+    /// arguments. Fails with [`ZError::OutOfBounds`] if it is shorter than
+    /// a selector, as for plain value transfers.
+    #[inline]
+    pub fn new(inputs: &'a CallInputs, calldata: &'a [u8]) -> Result<Self, ZError> {
+        Ok(Self {
+            target: ZAddress(&inputs.target_address.0 .0),
+            code_address: ZAddress(&inputs.bytecode_address.0 .0),
+            caller: ZAddress(&inputs.caller.0 .0),
+            selector: read_selector(calldata)?,
+            args: &calldata[4..],
+        })
+    }
+
+    /// Returns the 4-byte function selector.
+    #[inline]
+    pub fn selector(&self) -> &'a [u8; 4] {
+        self.selector
+    }
+
+    /// Returns the ABI-encoded arguments after the selector.
+    #[inline]
+    pub fn args(&self) -> &'a [u8] {
+        self.args
+    }
+
+    /// Decode the arguments as `T`, e.g. a tuple or a derived struct.
+    #[inline]
+    pub fn decode_args<T: ZDecode<'a>>(&self) -> Result<T, ZError> {
+        T::decode_params(self.args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ZU256;
+    use alloc::vec;
+    use revm_interpreter::primitives::{Address, Bytes, B256};
+    use revm_interpreter::state::Bytecode;
+    use revm_interpreter::{CallInput, CallScheme, CallValue};
+
+    fn call_inputs(calldata: &[u8]) -> CallInputs {
+        CallInputs {
+            input: CallInput::Bytes(Bytes::copy_from_slice(calldata)),
+            return_memory_offset: 0..0,
+            gas_limit: 100_000,
+            reservoir: 0,
+            bytecode_address: Address::repeat_byte(0x22),
+            known_bytecode: (B256::ZERO, Bytecode::default()),
+            target_address: Address::repeat_byte(0x11),
+            caller: Address::repeat_byte(0x33),
+            value: CallValue::default(),
+            scheme: CallScheme::DelegateCall,
+            is_static: false,
+            charged_new_account_state_gas: false,
+        }
+    }
+
+    #[test]
+    fn test_call_input() {
+        let mut calldata = vec![0xa9, 0x05, 0x9c, 0xbb];
+        calldata.extend_from_slice(&[0u8; 64]);
+        calldata[4 + 31] = 0x44;
+        calldata[4 + 63] = 100;
+        let inputs = call_inputs(&calldata);
+        let CallInput::Bytes(input) = &inputs.input else {
+            unreachable!()
+        };
+
+        let call = ZCallInput::new(&inputs, input).unwrap();
+        assert_eq!(call.selector(), &[0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(call.target.as_bytes(), &[0x11; 20]);
+        assert_eq!(call.code_address.as_bytes(), &[0x22; 20]);
+        assert_eq!(call.caller.as_bytes(), &[0x33; 20]);
+        assert_eq!(call.args().len(), 64);
+        let (to, amount) = call.decode_args::<(ZAddress, ZU256)>().unwrap();
+        assert_eq!(to.as_bytes()[19], 0x44);
+        assert_eq!(amount.to_u64(), Some(100));
+
+        assert!(matches!(
+            ZCallInput::new(&inputs, &[0xa9]),
+            Err(ZError::OutOfBounds(4, 1))
+        ));
+    }
+
+    #[test]
+    fn test_event_log() {
+        let mut amount = [0u8; 32];
+        amount[31] = 7;
+        let log = Log::new_unchecked(
+            Address::repeat_byte(0x55),
+            vec![
+                B256::repeat_byte(0xdd),
+                B256::left_padding_from(&[0x66; 20]),
+            ],
+            Bytes::copy_from_slice(&amount),
+        );
+
+        let view = event_log(&log);
+        assert_eq!(view.address().unwrap().as_bytes(), &[0x55; 20]);
+        assert_eq!(view.topic_count(), 2);
+        assert_eq!(view.event_signature().unwrap(), &[0xdd; 32]);
+        assert_eq!(view.topic_as_address(1).unwrap().as_bytes(), &[0x66; 20]);
+        assert!(view.raw_topic(2).is_err());
+        assert_eq!(view.decode_data_as::<ZU256>().unwrap().to_u64(), Some(7));
+    }
+}
@@ -0,0 +1,147 @@
+//! Conversions to and from other crates' 256-bit and hash types.
+//!
+//! - `primitive-types`: `U256`, `H160` and `H256`, as used by parity-style stacks.
+//! - `ruint`: `Uint<256, 4>`, the integer behind alloy's and reth's `U256`.
+//!
+//! Views convert into the foreign types by copying; foreign values convert
+//! into this crate's owned types.
+
+#[cfg(feature = "primitive-types")]
+mod primitive {
+    use primitive_types::{H160, H256, U256 as PU256};
+
+    use crate::owned::{Address, Bytes32, U256};
+    use crate::types::{ZAddress, ZU256};
+    use crate::zbytes_fixed::ZBytesN;
+
+    impl From<ZU256<'_>> for PU256 {
+        #[inline]
+        fn from(value: ZU256<'_>) -> Self {
+            PU256::from_big_endian(value.0)
+        }
+    }
+
+    impl From<U256> for PU256 {
+        #[inline]
+        fn from(value: U256) -> Self {
+            PU256::from_big_endian(&value.0)
+        }
+    }
+
+    impl From<PU256> for U256 {
+        #[inline]
+        fn from(value: PU256) -> Self {
+            let mut bytes = [0u8; 32];
+            value.to_big_endian(&mut bytes);
+            U256(bytes)
+        }
+    }
+
+    impl From<ZAddress<'_>> for H160 {
+        #[inline]
+        fn from(value: ZAddress<'_>) -> Self {
+            H160(*value.0)
+        }
+    }
+
+    impl From<Address> for H160 {
+        #[inline]
+        fn from(value: Address) -> Self {
+            H160(value.0)
+        }
+    }
+
+    impl From<H160> for Address {
+        #[inline]
+        fn from(value: H160) -> Self {
+            Address(value.0)
+        }
+    }
+
+    impl From<ZBytesN<'_, 32>> for H256 {
+        #[inline]
+        fn from(value: ZBytesN<'_, 32>) -> Self {
+            H256(*value.0)
+        }
+    }
+
+    impl From<Bytes32> for H256 {
+        #[inline]
+        fn from(value: Bytes32) -> Self {
+            H256(value.0)
+        }
+    }
+
+    impl From<H256> for Bytes32 {
+        #[inline]
+        fn from(value: H256) -> Self {
+            Bytes32(value.0)
+        }
+    }
+}
+
+#[cfg(feature = "ruint")]
+mod ruint_impls {
+    use ruint::Uint;
+
+    use crate::owned::U256;
+    use crate::types::ZU256;
+
+    impl From<ZU256<'_>> for Uint<256, 4> {
+        #[inline]
+        fn from(value: ZU256<'_>) -> Self {
+            Uint::from_be_bytes(*value.0)
+        }
+    }
+
+    impl From<U256> for Uint<256, 4> {
+        #[inline]
+        fn from(value: U256) -> Self {
+            Uint::from_be_bytes(value.0)
+        }
+    }
+
+    impl From<Uint<256, 4>> for U256 {
+        #[inline]
+        fn from(value: Uint<256, 4>) -> Self {
+            U256(value.to_be_bytes())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use crate::owned::{Address, Bytes32, U256};
+
+    #[cfg(feature = "primitive-types")]
+    #[test]
+    fn test_primitive_types() {
+        use primitive_types::{H160, H256, U256 as PU256};
+
+        let amount = U256::from(123_456u64);
+        let foreign = PU256::from(amount.as_view());
+        assert_eq!(foreign, PU256::from(123_456u64));
+        assert_eq!(U256::from(foreign), amount);
+        assert_eq!(U256::from(PU256::MAX), U256::MAX);
+
+        let address = Address([0x42; 20]);
+        assert_eq!(H160::from(address.as_view()), H160([0x42; 20]));
+        assert_eq!(Address::from(H160::from(address)), address);
+        let hash = Bytes32([0x07; 32]);
+        assert_eq!(Bytes32::from(H256::from(hash.as_view())), hash);
+    }
+
+    #[cfg(feature = "ruint")]
+    #[test]
+    fn test_ruint() {
+        use ruint::Uint;
+
+        let amount = U256::from(u128::MAX);
+        // `Uint` has an inherent `from`, so go through `Into`.
+        let foreign: Uint<256, 4> = amount.as_view().into();
+        assert_eq!(foreign, Uint::<256, 4>::from(u128::MAX));
+        assert_eq!(U256::from(foreign), amount);
+        assert_eq!(U256::from(Uint::<256, 4>::MAX), U256::MAX);
+    }
+}
@@ -1,14 +1,58 @@
 #![no_std]
 
-#[cfg(test)]
+#[cfg(any(test, feature = "alloc"))]
 extern crate alloc;
-#[cfg(test)]
+// Lets `sol!` expansions inside this crate resolve `::zabi_rs` paths.
+#[cfg(feature = "derive")]
+extern crate self as zabi_rs;
+#[cfg(any(test, feature = "std"))]
 extern crate std;
 
+#[cfg(feature = "serde")]
+pub mod abi;
+#[cfg(feature = "derive")]
+pub mod analysis;
 pub mod decoder;
+#[cfg(feature = "defmt")]
+mod defmt_fmt;
+#[cfg(feature = "alloc")]
+pub mod dynamic;
 pub mod error;
 pub mod event;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod filter;
+#[cfg(feature = "std")]
+pub mod fourbyte;
+#[cfg(feature = "arbitrary")]
+pub mod fuzz;
+#[cfg(feature = "keccak")]
+pub mod hash;
+pub mod hex;
+#[cfg(feature = "revm")]
+pub mod inspector;
+#[cfg(any(feature = "primitive-types", feature = "ruint"))]
+mod interop;
+pub mod owned;
+#[cfg(feature = "derive")]
+pub mod presets;
+pub mod receipt;
+#[cfg(feature = "alloc")]
+pub mod registry;
+pub mod replay;
+pub mod revert;
+#[cfg(feature = "serde")]
+pub mod rpc;
+#[cfg(feature = "serde")]
+mod ser;
+pub mod signature;
+#[cfg(feature = "std")]
+pub mod service;
+pub mod storage;
+#[cfg(test)]
+pub(crate) mod test_util;
 pub mod types;
+pub mod wad;
 pub mod zbytes_fixed;
 
 pub use decoder::{
@@ -17,13 +61,33 @@ pub use decoder::{
     read_i8, read_i16, read_i32, read_i64, read_i128,
     read_selector, skip_selector
 };
-pub use error::ZError;
-pub use types::{ZAddress, ZU256, ZInt256, ZBytes, ZBool, ZString, ZArray};
+#[cfg(feature = "alloc")]
+pub use dynamic::{DynType, DynValue, decode_dyn, decode_dyn_params, decode_by_signature, encode_dyn, encode_dyn_params};
+pub use error::{ZError, ZErrorContext, ZResultExt};
+#[cfg(feature = "alloc")]
+pub use hex::{decode_rpc_hex, HexBuf};
+#[cfg(feature = "alloc")]
+pub use registry::{EventRegistry, SelectorRegistry};
+pub use owned::{Address, Bytes32, I256, IntoOwned, U256};
+pub use types::{ZAddress, ZU256, ZInt256, ZBytes, ZBool, ZString, ZArray, ZFixedArray, MAX_DECIMAL_LEN};
 pub use zbytes_fixed::{ZBytesN, read_bytes_n, read_bytes1, read_bytes2, read_bytes3, read_bytes4, read_bytes8, read_bytes16, read_bytes20, read_bytes32};
-pub use event::{ZEventLog, read_topic_u256, read_topic_int256, read_topic_address, read_topic_bool};
+pub use event::{ZEventLog, ZEventDecode, ZIndexedHash, read_topic_u256, read_topic_int256, read_topic_address, read_topic_bool, read_topic_hash, decode_logs, bloom_contains_hash};
+#[cfg(feature = "keccak")]
+pub use event::{bloom_contains, signature_hash};
+#[cfg(feature = "keccak")]
+pub use hash::keccak256;
+pub use filter::{ZEventFilter, TopicMatch};
+pub use receipt::ZReceiptLogs;
+pub use wad::ZWad;
+pub use revert::{decode_revert, PanicCode, RevertError};
+pub use replay::{LogPosition, Positioned, ZPositionedLog, compare_logs, merge_logs};
+#[cfg(feature = "alloc")]
+pub use replay::sort_logs;
+#[cfg(feature = "serde")]
+pub use rpc::{LogBuf, PositionedLogBuf};
 
 #[cfg(feature = "derive")]
-pub use zabi_derive::ZDecode;
+pub use zabi_derive::{ZDecode, ZView, sol, contract, abigen, dispatch, selector, event_topic};
 
 /// Decode a tuple of types from ABI-encoded data.
 /// 
@@ -49,8 +113,8 @@ macro_rules! decode_tuple {
         (|| -> Result<($($T,)+), $crate::ZError> {
             Ok((
                 $({
-                    let val = <$T as $crate::ZDecode>::decode(data, offset)?;
-                    offset += 32;
+                    let val = <$T as $crate::ZDecode>::decode_member(data, offset)?;
+                    offset += <$T as $crate::ZDecode>::HEAD_SIZE;
                     val
                 },)+
             ))
@@ -58,15 +122,54 @@ macro_rules! decode_tuple {
     }};
 }
 
-/// The main trait for zero-copy decoding.
 /// The main trait for zero-copy decoding.
 pub trait ZDecode<'a>: Sized {
     const HEAD_SIZE: usize = 32; // Default for words and offsets
+    /// Canonical Solidity type name, e.g. `"uint256"` or `"(address,bool)"`.
+    ///
+    /// Empty by default, so impls written before this constant existed still
+    /// compile on their own. A derived struct with such a field fails to
+    /// compile, since its own `SOL_TYPE` and `SIGNATURE` would be wrong.
+    const SOL_TYPE: &'static str = "";
+    /// Whether the encoding has a tail (`bytes`, `string`, `T[]`, or a tuple
+    /// containing one). Dynamic values occupy a single offset word in the head.
+    const IS_DYNAMIC: bool = false;
+    /// Head size of the value laid out inline, as [`ZDecode::decode`] reads
+    /// it: the sum of the member heads for a tuple, even a dynamic one.
+    const INLINE_HEAD_SIZE: usize = Self::HEAD_SIZE;
     fn decode(data: &'a [u8], offset: usize) -> Result<Self, ZError>;
+
+    /// Padding-tolerant decode, used by `#[zabi(lenient)]`.
+    /// Types with no padding to check fall back to [`ZDecode::decode`].
+    fn decode_lenient(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
+        Self::decode(data, offset)
+    }
+
+    /// Decode a complete parameter encoding: calldata after the selector,
+    /// return data or event data. Same as [`ZDecode::decode`] at offset 0.
+    fn decode_params(data: &'a [u8]) -> Result<Self, ZError> {
+        Self::decode(data, 0)
+    }
+
+    /// Decode a member whose head slot is at `offset` inside an enclosing
+    /// tuple, struct or array.
+    ///
+    /// Only differs from [`ZDecode::decode`] for tuples: `decode` reads a
+    /// tuple laid out inline at `offset`, like a parameter list, while a
+    /// dynamic tuple member sits behind an offset word.
+    fn decode_member(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
+        Self::decode(data, offset)
+    }
+
+    /// Padding-tolerant [`ZDecode::decode_member`].
+    fn decode_member_lenient(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
+        Self::decode_lenient(data, offset)
+    }
 }
 
 impl<'a> ZDecode<'a> for ZU256<'a> {
     const HEAD_SIZE: usize = 32;
+    const SOL_TYPE: &'static str = "uint256";
     fn decode(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
         decoder::read_u256(data, offset)
     }
@@ -74,6 +177,7 @@ impl<'a> ZDecode<'a> for ZU256<'a> {
 
 impl<'a> ZDecode<'a> for ZAddress<'a> {
     const HEAD_SIZE: usize = 32;
+    const SOL_TYPE: &'static str = "address";
     fn decode(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
         decoder::read_address_from_word(data, offset)
     }
@@ -81,69 +185,139 @@ impl<'a> ZDecode<'a> for ZAddress<'a> {
 
 impl<'a> ZDecode<'a> for ZBool {
     const HEAD_SIZE: usize = 32;
+    const SOL_TYPE: &'static str = "bool";
     fn decode(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
         decoder::read_bool(data, offset)
     }
+    fn decode_lenient(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
+        decoder::read_bool_lenient(data, offset)
+    }
 }
 
 impl<'a> ZDecode<'a> for ZInt256<'a> {
     const HEAD_SIZE: usize = 32;
+    const SOL_TYPE: &'static str = "int256";
     fn decode(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
         decoder::read_int256(data, offset)
     }
 }
 
 macro_rules! impl_zdecode_primitive {
-    ($t:ty, $func:path) => {
+    ($t:ty, $func:path, $lenient:path, $sol:literal) => {
         impl<'a> ZDecode<'a> for $t {
             const HEAD_SIZE: usize = 32;
+            const SOL_TYPE: &'static str = $sol;
             fn decode(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
                 $func(data, offset)
             }
+            fn decode_lenient(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
+                $lenient(data, offset)
+            }
         }
     };
 }
 
-impl_zdecode_primitive!(u8, decoder::read_u8);
-impl_zdecode_primitive!(u16, decoder::read_u16);
-impl_zdecode_primitive!(u32, decoder::read_u32);
-impl_zdecode_primitive!(u64, decoder::read_u64);
-impl_zdecode_primitive!(u128, decoder::read_u128);
+impl_zdecode_primitive!(u8, decoder::read_u8, decoder::read_u8_lenient, "uint8");
+impl_zdecode_primitive!(u16, decoder::read_u16, decoder::read_u16_lenient, "uint16");
+impl_zdecode_primitive!(u32, decoder::read_u32, decoder::read_u32_lenient, "uint32");
+impl_zdecode_primitive!(u64, decoder::read_u64, decoder::read_u64_lenient, "uint64");
+impl_zdecode_primitive!(u128, decoder::read_u128, decoder::read_u128_lenient, "uint128");
 
-impl_zdecode_primitive!(i8, decoder::read_i8);
-impl_zdecode_primitive!(i16, decoder::read_i16);
-impl_zdecode_primitive!(i32, decoder::read_i32);
-impl_zdecode_primitive!(i64, decoder::read_i64);
-impl_zdecode_primitive!(i128, decoder::read_i128);
+impl_zdecode_primitive!(i8, decoder::read_i8, decoder::read_i8_lenient, "int8");
+impl_zdecode_primitive!(i16, decoder::read_i16, decoder::read_i16_lenient, "int16");
+impl_zdecode_primitive!(i32, decoder::read_i32, decoder::read_i32_lenient, "int32");
+impl_zdecode_primitive!(i64, decoder::read_i64, decoder::read_i64_lenient, "int64");
+impl_zdecode_primitive!(i128, decoder::read_i128, decoder::read_i128_lenient, "int128");
 
 impl<'a, T: ZDecode<'a>> ZDecode<'a> for ZArray<'a, T> {
     const HEAD_SIZE: usize = 32;
+    const IS_DYNAMIC: bool = true;
+    const SOL_TYPE: &'static str = signature::SolTypeBuf::new()
+        .push_str(T::SOL_TYPE)
+        .push_str("[]")
+        .as_str();
     fn decode(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
         decoder::read_array_dyn(data, offset)
     }
 }
 
+// `T[N]` is laid out like a tuple of `N` members: inline when `T` is static,
+// behind an offset word when it is dynamic.
+impl<'a, T: ZDecode<'a>, const N: usize> ZDecode<'a> for ZFixedArray<'a, T, N> {
+    const HEAD_SIZE: usize = if Self::IS_DYNAMIC { 32 } else { Self::INLINE_HEAD_SIZE };
+    const INLINE_HEAD_SIZE: usize = N * T::HEAD_SIZE;
+    const IS_DYNAMIC: bool = T::IS_DYNAMIC;
+    const SOL_TYPE: &'static str = signature::SolTypeBuf::new()
+        .push_str(T::SOL_TYPE)
+        .push_str("[")
+        .push_usize(N)
+        .push_str("]")
+        .as_str();
+    fn decode(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
+        let end = offset.saturating_add(Self::INLINE_HEAD_SIZE);
+        if end > data.len() {
+            return Err(ZError::OutOfBounds(end, data.len()));
+        }
+        Ok(ZFixedArray(ZArray::new(data, offset, N)))
+    }
+    fn decode_member(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
+        let body = decoder::read_tuple_body(data, offset, Self::IS_DYNAMIC)?;
+        Self::decode(body, 0)
+    }
+}
+
 impl<'a, const N: usize> ZDecode<'a> for ZBytesN<'a, N> {
     const HEAD_SIZE: usize = 32;
+    const SOL_TYPE: &'static str = signature::SolTypeBuf::new()
+        .push_str("bytes")
+        .push_usize(N)
+        .as_str();
     fn decode(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
         zbytes_fixed::read_bytes_n(data, offset)
     }
+    fn decode_lenient(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
+        zbytes_fixed::read_bytes_n_lenient(data, offset)
+    }
 }
 
+// `decode` reads a tuple laid out inline at `offset`, as a parameter list is;
+// `decode_member` reads it from its head slot, following the offset word of
+// a dynamic tuple. Member tails resolve relative to the tuple's encoding.
 macro_rules! impl_zdecode_tuple {
     ($($T:ident),+) => {
         impl<'a, $($T: ZDecode<'a>),+> ZDecode<'a> for ($($T,)+) {
-            const HEAD_SIZE: usize = 0 $(+ <$T as ZDecode>::HEAD_SIZE)*;
+            const HEAD_SIZE: usize = if Self::IS_DYNAMIC { 32 } else { Self::INLINE_HEAD_SIZE };
+            const INLINE_HEAD_SIZE: usize = 0 $(+ <$T as ZDecode>::HEAD_SIZE)*;
+            const SOL_TYPE: &'static str = signature::SolTypeBuf::new()
+                .push_tuple(&[$(<$T as ZDecode>::SOL_TYPE),+])
+                .as_str();
+            const IS_DYNAMIC: bool = false $(|| <$T as ZDecode>::IS_DYNAMIC)*;
             fn decode(data: &'a [u8], mut offset: usize) -> Result<Self, ZError> {
                 #[allow(unused_assignments)]
                 Ok((
                     $({
-                        let val = <$T as ZDecode>::decode(data, offset)?;
+                        let val = <$T as ZDecode>::decode_member(data, offset)?;
+                        offset += <$T as ZDecode>::HEAD_SIZE;
+                        val
+                    },)+
+                ))
+            }
+            fn decode_lenient(data: &'a [u8], mut offset: usize) -> Result<Self, ZError> {
+                #[allow(unused_assignments)]
+                Ok((
+                    $({
+                        let val = <$T as ZDecode>::decode_member_lenient(data, offset)?;
                         offset += <$T as ZDecode>::HEAD_SIZE;
                         val
                     },)+
                 ))
             }
+            fn decode_member(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
+                Self::decode(decoder::read_tuple_body(data, offset, Self::IS_DYNAMIC)?, 0)
+            }
+            fn decode_member_lenient(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
+                Self::decode_lenient(decoder::read_tuple_body(data, offset, Self::IS_DYNAMIC)?, 0)
+            }
         }
     };
 }
@@ -160,15 +334,32 @@ impl_zdecode_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9);
 impl_zdecode_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
 impl_zdecode_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
 impl_zdecode_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12);
+impl_zdecode_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13);
+impl_zdecode_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14);
+impl_zdecode_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15);
+impl_zdecode_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16);
+
+impl<'a> ZDecode<'a> for ZBytes<'a> {
+    const HEAD_SIZE: usize = 32;
+    const SOL_TYPE: &'static str = "bytes";
+    const IS_DYNAMIC: bool = true;
+    fn decode(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
+        decoder::read_bytes(data, offset)
+    }
+}
 
 impl<'a> ZDecode<'a> for ZString<'a> {
     const HEAD_SIZE: usize = 32;
+    const SOL_TYPE: &'static str = "string";
+    const IS_DYNAMIC: bool = true;
     fn decode(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
         decoder::read_string(data, offset)
     }
 }
 
 #[cfg(test)]
+// These unit tests predate the lint and keep their original form.
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
     use alloc::vec::Vec;
@@ -283,6 +474,16 @@ mod tests {
         assert_eq!(arr_fixed.get(0).unwrap().0[31], 1);
         assert_eq!(arr_fixed.get(1).unwrap().0[31], 2);
 
+        // The same through ZDecode: uint256[2] is static and inline.
+        type Pair<'a> = ZFixedArray<'a, ZU256<'a>, 2>;
+        assert_eq!((Pair::HEAD_SIZE, Pair::IS_DYNAMIC, Pair::SOL_TYPE), (64, false, "uint256[2]"));
+        let pair = Pair::decode_member(&data, 0).expect("fixed array");
+        assert_eq!(pair.get(1).unwrap().0[31], 2);
+        assert!(matches!(Pair::decode(&data, 32), Err(ZError::OutOfBounds(96, 64))));
+        type Names<'a> = ZFixedArray<'a, ZString<'a>, 3>;
+        assert_eq!((Names::HEAD_SIZE, Names::INLINE_HEAD_SIZE, Names::IS_DYNAMIC), (32, 96, true));
+        assert_eq!(Names::SOL_TYPE, "string[3]");
+
         // 2. Dynamic Array: uint256[] = [3, 4]
         // Encoded as: Offset (head) -> Length -> Elements
         let mut dyn_data = Vec::new();
@@ -314,6 +515,63 @@ mod tests {
         assert_eq!(arr_dyn.get(1).unwrap().0[31], 4);
     }
 
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn test_heapless() {
+        use crate::test_util::word;
+
+        let data = [word(32), word(2), word(3), word(4)].concat();
+        let array = ZArray::<u64>::decode(&data, 0).unwrap();
+        let values: heapless::Vec<u64, 4> = array.collect_into().unwrap();
+        assert_eq!(values.as_slice(), &[3, 4]);
+        assert!(matches!(array.collect_into::<1>(), Err(ZError::InvalidLength(1, 2))));
+
+        let name = ZString("zabi");
+        assert_eq!(name.to_heapless::<8>().unwrap().as_str(), "zabi");
+        assert!(matches!(name.to_heapless::<3>(), Err(ZError::InvalidLength(3, 4))));
+    }
+
+    #[test]
+    fn test_sol_type_default() {
+        // A hand-written impl that predates SOL_TYPE still compiles.
+        struct Legacy;
+        impl<'a> ZDecode<'a> for Legacy {
+            fn decode(_data: &'a [u8], _offset: usize) -> Result<Self, ZError> {
+                Ok(Legacy)
+            }
+        }
+        assert_eq!(<Legacy as ZDecode>::SOL_TYPE, "");
+        assert!(Legacy::decode(&[], 0).is_ok());
+    }
+
+    #[test]
+    fn test_oversized_lengths() {
+        // Length words of u64::MAX must fail on bounds, not overflow.
+        let mut data = [0u8; 64];
+        data[31] = 32;
+        data[56..].fill(0xff);
+        assert!(matches!(ZBytes::decode(&data, 0), Err(ZError::OutOfBounds(..))));
+        assert!(matches!(ZString::decode(&data, 0), Err(ZError::OutOfBounds(..))));
+        assert!(matches!(ZArray::<u8>::decode(&data, 0), Err(ZError::OutOfBounds(..))));
+        assert!(matches!(decoder::read_array_fixed::<u8>(&data, 32, usize::MAX), Err(ZError::OutOfBounds(..))));
+        assert!(matches!(u8::decode(&data, usize::MAX - 16), Err(ZError::OutOfBounds(usize::MAX, 64))));
+
+        // A length of 2^192 + 2 must not be read as 2, nor an offset of 2^192 + 32 as 32.
+        let mut data = [0u8; 96];
+        data[31] = 32;
+        data[39] = 1;
+        data[63] = 2;
+        data[64..66].copy_from_slice(b"hi");
+        assert!(matches!(ZBytes::decode(&data, 0), Err(ZError::OutOfBounds(usize::MAX, 96))));
+        assert!(matches!(ZString::decode(&data, 0), Err(ZError::OutOfBounds(usize::MAX, 96))));
+        assert!(matches!(ZArray::<u8>::decode(&data, 0), Err(ZError::OutOfBounds(usize::MAX, 96))));
+        data[39] = 0;
+        assert_eq!(ZString::decode(&data, 0).unwrap().0, "hi");
+        data[7] = 1;
+        assert!(matches!(ZBytes::decode(&data, 0), Err(ZError::OffsetOutOfRange { at: 0, target: usize::MAX, len: 96 })));
+        assert!(matches!(ZArray::<u8>::decode(&data, 0), Err(ZError::OffsetOutOfRange { at: 0, target: usize::MAX, len: 96 })));
+    }
+
     #[test]
     fn test_integers() {
         use crate::decoder::*;
@@ -358,5 +616,375 @@ mod tests {
 
         // Test Invalid
         assert!(read_u8(&data, 128).is_err());
+
+        // Lenient readers ignore the padding
+        assert_eq!(read_u8_lenient(&data, 128).unwrap(), 1);
+        assert_eq!(read_u16_lenient(&data, 128).unwrap(), 0x0101);
+        assert_eq!(read_i8_lenient(&data, 96).unwrap(), 1);
+        assert!(read_bool_lenient(&data, 128).unwrap().0);
+        assert!(!read_bool_lenient(&[0u8; 32], 0).unwrap().0);
+    }
+
+    #[test]
+    fn test_ordering() {
+        use alloc::collections::BTreeSet;
+
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let mut big = [0u8; 32];
+        big[0] = 1;
+        assert!(ZU256(&one) < ZU256(&big));
+        assert_eq!(ZU256(&one).cmp(&ZU256(&one)), core::cmp::Ordering::Equal);
+
+        // Signed: -1 < 0 < 1, and -2 < -1.
+        let minus_one = [0xff; 32];
+        let mut minus_two = [0xff; 32];
+        minus_two[31] = 0xfe;
+        let zero = [0u8; 32];
+        let mut min = [0u8; 32];
+        min[0] = 0x80;
+        assert!(ZInt256(&minus_one) < ZInt256(&zero));
+        assert!(ZInt256(&zero) < ZInt256(&one));
+        assert!(ZInt256(&minus_two) < ZInt256(&minus_one));
+        assert!(ZInt256(&min) < ZInt256(&minus_two));
+
+        let a = [0x01; 20];
+        let b = [0x02; 20];
+        let set: BTreeSet<ZAddress> = [ZAddress(&b), ZAddress(&a), ZAddress(&b)].into_iter().collect();
+        assert_eq!(set.into_iter().collect::<Vec<_>>(), [ZAddress(&a), ZAddress(&b)]);
+
+        let mut seen = std::collections::HashSet::new();
+        assert!(seen.insert(ZBytes(&a[..])));
+        assert!(!seen.insert(ZBytes(&a[..])));
+        assert!(ZBytesN(&[0x01u8; 4]) < ZBytesN(&[0x02u8; 4]));
+    }
+
+    #[test]
+    fn test_signed_arithmetic() {
+        fn word(v: i128) -> [u8; 32] {
+            let mut w = if v < 0 { [0xff; 32] } else { [0u8; 32] };
+            w[16..].copy_from_slice(&v.to_be_bytes());
+            w
+        }
+        let five = word(5);
+        let minus_three = word(-3);
+        let zero = word(0);
+        let mut max = [0xff; 32];
+        max[0] = 0x7f;
+        let mut min = [0u8; 32];
+        min[0] = 0x80;
+
+        assert_eq!(ZInt256(&five).sign(), 1);
+        assert_eq!(ZInt256(&minus_three).sign(), -1);
+        assert_eq!(ZInt256(&zero).sign(), 0);
+
+        assert_eq!(ZInt256(&five).neg().as_view().to_i64(), Some(-5));
+        assert_eq!(ZInt256(&zero).neg().0, zero);
+        assert_eq!(ZInt256(&min).neg().0, min);
+        assert_eq!(ZInt256(&minus_three).abs().to_u64(), Some(3));
+        let mut two_pow_255 = [0u8; 32];
+        two_pow_255[0] = 0x80;
+        assert_eq!(ZInt256(&min).abs().0, two_pow_255);
+
+        let sum = ZInt256(&five).checked_add(&ZInt256(&minus_three)).unwrap();
+        assert_eq!(sum.as_view().to_i64(), Some(2));
+        let diff = ZInt256(&minus_three).checked_sub(&ZInt256(&five)).unwrap();
+        assert_eq!(diff.as_view().to_i64(), Some(-8));
+        assert!(ZInt256(&max).checked_add(&ZInt256(&five)).is_none());
+        assert!(ZInt256(&min).checked_add(&ZInt256(&minus_three)).is_none());
+        assert!(ZInt256(&min).checked_sub(&ZInt256(&five)).is_none());
+        assert!(ZInt256(&max).checked_sub(&ZInt256(&minus_three)).is_none());
+        assert_eq!(ZInt256(&min).checked_sub(&ZInt256(&minus_three)).unwrap().as_view().sign(), -1);
+        assert!(diff < sum);
+    }
+
+    #[test]
+    fn test_bit_access() {
+        let mut w = [0u8; 32];
+        w[31] = 0b1010_0001;
+        w[16] = 0x80;
+        let v = ZU256(&w);
+        assert!(v.bit(0) && !v.bit(1) && v.bit(5) && v.bit(7));
+        assert!(v.bit(127) && !v.bit(128) && !v.bit(300));
+        assert_eq!(v.byte(31), 0b1010_0001);
+        assert_eq!(v.byte(16), 0x80);
+        assert_eq!(v.byte(32), 0);
+        assert_eq!(v.leading_zeros(), 128);
+        assert_eq!(v.bit_len(), 128);
+        assert_eq!(ZU256(&[0u8; 32]).bit_len(), 0);
+        assert_eq!(ZU256(&[0xff; 32]).leading_zeros(), 0);
+
+        let x: u128 = 0x0123_4567_89ab_cdef_0011_2233_4455_6677;
+        let mut xw = [0u8; 32];
+        xw[16..].copy_from_slice(&x.to_be_bytes());
+        let xv = ZU256(&xw);
+        for n in [0, 1, 7, 8, 13, 64, 100] {
+            assert_eq!(xv.shr(n).to_u128(), Some(x >> n));
+        }
+        assert_eq!(xv.shl(4).to_u128(), Some(x << 4));
+        assert_eq!(xv.shl(131).as_view().shr(131).to_u128(), Some(x));
+        assert!(xv.shl(256).is_zero() && xv.shr(256).is_zero());
+        assert_eq!(xv.bit_len(), 121);
+        assert_eq!(xv.shl(135).as_view().bit_len(), 256);
+        assert_eq!(xv.shl(136).as_view().bit_len(), 254);
+    }
+
+    #[test]
+    fn test_decimal_format() {
+        use std::format;
+
+        let mut buf = [0u8; MAX_DECIMAL_LEN];
+        assert_eq!(ZU256(&[0u8; 32]).to_decimal(&mut buf), "0");
+        assert_eq!(
+            ZU256(&[0xff; 32]).to_decimal(&mut buf),
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+        );
+        let mut w = [0u8; 32];
+        w[16..].copy_from_slice(&u128::MAX.to_be_bytes());
+        assert_eq!(format!("{}", ZU256(&w)), format!("{}", u128::MAX));
+        assert_eq!(format!("{:>5}", ZU256(&[0u8; 32])), "    0");
+
+        let mut min = [0u8; 32];
+        min[0] = 0x80;
+        assert_eq!(
+            ZInt256(&min).to_decimal(&mut buf),
+            "-57896044618658097711785492504343953926634992332820282019728792003956564819968"
+        );
+        assert_eq!(format!("{}", ZInt256(&[0xff; 32])), "-1");
+        assert_eq!(format!("{:+}", ZInt256(&w)), format!("+{}", u128::MAX));
+        assert_eq!(format!("{}", ZInt256(&w).to_owned()), format!("{}", u128::MAX));
+
+        let mut small = [0u8; 3];
+        assert_eq!(ZU256(&w).shr(120).as_view().to_decimal(&mut small), "255");
+    }
+
+    #[test]
+    fn test_try_from_ints() {
+        let mut w = [0u8; 32];
+        w[30] = 0x01;
+        w[31] = 0x2c; // 300
+        assert_eq!(u16::try_from(ZU256(&w)).unwrap(), 300);
+        assert_eq!(usize::try_from(ZU256(&w)).unwrap(), 300);
+        assert_eq!(u128::try_from(ZU256(&w)).unwrap(), 300);
+        assert!(matches!(u8::try_from(ZU256(&w)), Err(ZError::Overflow { ty: "u8" })));
+        assert!(u128::try_from(ZU256(&[0xff; 32])).is_err());
+
+        let minus_one = [0xff; 32];
+        assert_eq!(i8::try_from(ZInt256(&minus_one)).unwrap(), -1);
+        assert_eq!(isize::try_from(ZInt256(&minus_one)).unwrap(), -1);
+        assert_eq!(i64::try_from(ZInt256(&w)).unwrap(), 300);
+        assert!(matches!(i8::try_from(ZInt256(&w)), Err(ZError::Overflow { ty: "i8" })));
+        let mut big = [0u8; 32];
+        big[0] = 0x01;
+        assert!(i128::try_from(ZInt256(&big)).is_err());
+    }
+
+    #[test]
+    fn test_eq_plain_values() {
+        const ROUTER: [u8; 20] = [0x7a; 20];
+        let word = [0x7a; 20];
+        let addr = ZAddress(&word);
+        assert!(addr == ROUTER);
+        assert!(addr == &word[..]);
+        assert!(addr != [0u8; 20]);
+        assert!(addr == Address(ROUTER) && Address(ROUTER) == addr);
+
+        let mut w = [0u8; 32];
+        w[31] = 9;
+        assert!(ZU256(&w) == 9u64 && ZU256(&w) == 9u128 && ZU256(&w) == w);
+        assert!(ZU256(&[0xff; 32]) != u128::MAX);
+        assert!(ZInt256(&[0xff; 32]) == -1i64 && ZInt256(&[0xff; 32]) == -1i128);
+        assert!(ZInt256(&w) != -9i64);
+
+        let raw: &[u8] = b"abc";
+        assert!(ZBytes(b"abc") == raw && ZBytes(b"abc") == *raw);
+        assert!(ZBytesN(&[1u8, 2, 3, 4]) == [1, 2, 3, 4]);
+        assert!(ZBytesN(&[1u8, 2]) == &[1u8, 2][..]);
+        assert!(ZString("hi") == "hi");
+    }
+
+    #[test]
+    fn test_hex_format() {
+        use std::format;
+
+        let word = [0xabu8; 20];
+        let addr = ZAddress(&word);
+        assert_eq!(format!("{:x}", addr), "ab".repeat(20));
+        assert_eq!(format!("{:#X}", addr), format!("0x{}", "AB".repeat(20)));
+        assert_eq!(format!("{:x}", ZBytes(&[0x01, 0x02])), "0102");
+        assert_eq!(format!("{:#x}", ZBytesN(&[0xde, 0xad])), "0xdead");
+        assert_eq!(format!("{:>8x}|", ZBytes(&[0x0f])), "      0f|");
+        assert_eq!(format!("{:*<8x}|", ZBytes(&[0x0f])), "0f******|");
+        assert_eq!(format!("{:^#8x}|", ZBytes(&[0x0f])), "  0x0f  |");
+        assert_eq!(format!("{:#08x}", ZBytes(&[0x0f])), "0x00000f");
+
+        let mut w = [0u8; 32];
+        w[31] = 0xff;
+        assert_eq!(format!("{:#x}", ZU256(&w)), format!("0x{}ff", "00".repeat(31)));
+        assert_eq!(format!("{:X}", ZInt256(&[0xff; 32])), "FF".repeat(32));
+        assert_eq!(format!("{:x}", U256::ONE), format!("{:x}", ZU256(&U256::ONE.0)));
+        assert_eq!(format!("{:#x}", Address::ZERO), format!("0x{}", "00".repeat(20)));
+    }
+
+    #[test]
+    fn test_bytes_helpers() {
+        let data = [0xa9, 0x05, 0x9c, 0xbb, 0x01, 0x02, 0x03];
+        let bytes = ZBytes(&data);
+        assert_eq!(bytes.len(), 7);
+        assert!(!bytes.is_empty() && ZBytes(&[]).is_empty());
+        assert!(bytes.starts_with(&[0xa9, 0x05, 0x9c, 0xbb]));
+        assert!(!bytes.starts_with(&[0x00]));
+        assert_eq!(bytes.get(4..), Some(&[0x01, 0x02, 0x03][..]));
+        assert_eq!(bytes.get(..2), Some(&[0xa9, 0x05][..]));
+        assert_eq!(bytes.get(5..9), None);
+        let chunks: Vec<&[u8]> = bytes.chunks(4).collect();
+        assert_eq!(chunks, [&data[..4], &data[4..]]);
+
+        assert_eq!(ZBytes(b"hello").as_str().unwrap(), "hello");
+        assert!(matches!(ZBytes(b"he\xffllo").as_str(), Err(ZError::InvalidUtf8 { offset: 2 })));
+    }
+
+    #[test]
+    fn test_string_ergonomics() {
+        fn takes_str(s: &str) -> usize {
+            s.len()
+        }
+        fn takes_as_ref<S: AsRef<str>>(s: S) -> bool {
+            s.as_ref().starts_with("gm")
+        }
+
+        let owner = std::string::String::from("gm frens");
+        let s = ZString(&owner);
+        assert_eq!(takes_str(&s), 8);
+        assert!(takes_as_ref(s));
+        assert!(s.contains("frens") && s.to_uppercase() == "GM FRENS");
+        assert_eq!(AsRef::<[u8]>::as_ref(&s), b"gm frens");
+        let inner: &str = s.into();
+        assert_eq!(inner, s.as_str());
+    }
+
+    #[test]
+    fn test_bool_ops() {
+        let (t, f) = (ZBool(true), ZBool(false));
+        assert!(t.as_bool() && bool::from(t));
+        assert_eq!(ZBool::from(false), f);
+        assert!((!f).as_bool());
+        assert!(!(t & f).as_bool());
+        assert_eq!(t | f, t);
+        let both: bool = (t & !f).into();
+        assert!(both);
+    }
+
+    #[test]
+    #[should_panic(expected = "decimal buffer too small")]
+    fn test_decimal_buffer_too_small() {
+        let mut buf = [0u8; 2];
+        ZInt256(&[0xff; 32]).to_decimal(&mut buf[..1]);
+    }
+
+    #[test]
+    fn test_structured_errors() {
+        let mut data = [0u8; 128];
+        data[32 + 30] = 1; // uint8 with a dirty byte
+        data[64 + 31] = 2; // bool = 2
+        assert!(matches!(read_u8(&data, 32), Err(ZError::InvalidPadding { offset: 32, ty: "uint8" })));
+        assert!(matches!(read_bool(&data, 64), Err(ZError::InvalidBool { offset: 64 })));
+        assert!(matches!(read_bytes4(&data, 32), Err(ZError::InvalidPadding { offset: 32, ty: "bytes4" })));
+
+        // Head at 0 points past the end of the buffer.
+        data[31] = 0xA0;
+        assert!(matches!(read_bytes(&data, 0), Err(ZError::OffsetOutOfRange { at: 0, target: 0xA0, len: 128 })));
+
+        // "a\xFFb" at offset 64: the invalid byte sits at 64 + 32 + 1.
+        data[31] = 64;
+        data[64 + 31] = 3;
+        data[96..99].copy_from_slice(b"a\xFFb");
+        assert!(matches!(read_string(&data, 0), Err(ZError::InvalidUtf8 { offset: 97 })));
+    }
+
+    #[test]
+    fn test_error_trait() {
+        fn decode(data: &[u8]) -> Result<u8, std::boxed::Box<dyn core::error::Error>> {
+            Ok(read_u8(data, 0)?)
+        }
+        let err = decode(&[0xff; 32]).unwrap_err();
+        assert!(err.downcast_ref::<ZError>().is_some());
+        #[cfg(not(feature = "tiny-errors"))]
+        assert_eq!(std::string::ToString::to_string(&err), "Invalid uint8 padding in word at offset 0");
+    }
+
+    #[test]
+    fn test_error_context() {
+        let mut data = [0u8; 64];
+        data[63] = 7;
+        let err = read_bool(&data, 32).unwrap_err();
+        assert_eq!(err.word_offset(), Some(32));
+        #[cfg(not(feature = "tiny-errors"))]
+        {
+            let shown = std::format!("{}", err.with_context(&data));
+            assert!(shown.starts_with("Invalid bool (not 0 or 1) in word at offset 32 (word at 32: 0x00"));
+            assert!(shown.ends_with("07)"));
+        }
+        // A buffer that does not hold the word falls back to the plain message.
+        assert_eq!(std::format!("{}", err.with_context(&data[..32])), std::format!("{}", err));
+        assert_eq!(ZError::OutOfBounds(1, 0).word_offset(), None);
+    }
+
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(ZError::InvalidLength(0, 0).code(), 1);
+        assert_eq!(read_u8(&[0xff; 32], 0).unwrap_err().code(), 3);
+        assert_eq!(read_bool(&[0xff; 32], 0).unwrap_err().code(), 4);
+        assert_eq!(ZError::SelectorMismatch { expected: [0; 4], found: [1; 4] }.code(), 7);
+        assert_eq!(u8::try_from(ZU256(&[0xff; 32])).unwrap_err().code(), 16);
+        assert_eq!(ZError::Custom("x").code(), 255);
+    }
+
+    #[cfg(feature = "tiny-errors")]
+    #[test]
+    fn test_tiny_errors_format() {
+        let err = read_bool(&[0xff; 32], 0).unwrap_err();
+        assert_eq!(std::format!("{}", err), "zabi error 4");
+        assert_eq!(std::format!("{:?}", ZError::Custom("x")), "ZError(255)");
+        assert_eq!(std::format!("{}", err.with_context(&[0xff; 32])), "zabi error 4");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_error_message() {
+        let field = "amount";
+        let err = ZError::message(alloc::format!("{} is zero", field)).in_field(2);
+        #[cfg(not(feature = "tiny-errors"))]
+        assert_eq!(std::format!("{}", err), "In field 2: Error: amount is zero");
+        assert!(matches!(err.root_cause(), ZError::Message(msg) if msg == "amount is zero"));
+        assert_eq!(err.root_cause().code(), 10);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_result_context() {
+        let mut data = [0u8; 128];
+        data[96] = 1;
+        let err = read_u64(&data, 96).context_offset(96).context_field("amount").unwrap_err();
+        #[cfg(not(feature = "tiny-errors"))]
+        assert_eq!(
+            std::format!("{}", err),
+            "In field `amount`: At offset 96: Invalid uint64 padding in word at offset 96"
+        );
+        assert_eq!(err.code(), 11);
+        assert_eq!(err.word_offset(), Some(96));
+        assert!(matches!(err.root_cause(), ZError::InvalidPadding { offset: 96, ty: "uint64" }));
+
+        let err = Err::<(), _>(ZError::Custom("bad")).context_offset(64).unwrap_err();
+        assert_eq!(err.word_offset(), Some(64));
+        assert!(read_u64(&data, 0).context_field("nonce").is_ok());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_io_error_conversion() {
+        let err: std::io::Error = ZError::InvalidBool { offset: 0 }.into();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(err.get_ref().unwrap().downcast_ref::<ZError>().is_some());
     }
 }
@@ -0,0 +1,708 @@
+//! Owned counterparts of the zero-copy views.
+//!
+//! Views such as [`ZAddress`] and [`ZU256`] borrow the input buffer. When a
+//! decoded value has to outlive that buffer (a map key, a queued job, state
+//! carried across blocks) copy it into one of these types with `to_owned()`
+//! and get a view back with `as_view()`.
+//!
+//! [`IntoOwned`] does the same for any view, and for whole structs derived
+//! with `#[zabi(owned)]`, which get an owned mirror struct. With the `borsh`
+//! or `rkyv` feature the owned types implement those crates' traits, so a
+//! mirror deriving them can be persisted as is.
+
+use core::fmt;
+
+use crate::error::ZError;
+use crate::types::{ZAddress, ZBool, ZInt256, ZU256};
+use crate::zbytes_fixed::ZBytesN;
+
+/// An owned 20-byte Ethereum address.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize))]
+pub struct Address(pub [u8; 20]);
+
+/// An owned 32-byte EVM word interpreted as uint256.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize))]
+pub struct U256(pub [u8; 32]);
+
+/// An owned 32-byte EVM word interpreted as two's-complement int256.
+/// Ordering is signed, as for [`ZInt256`].
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize))]
+pub struct I256(pub [u8; 32]);
+
+/// An owned `bytes32` value, such as a hash or storage key.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize))]
+pub struct Bytes32(pub [u8; 32]);
+
+macro_rules! impl_owned {
+    ($owned:ident, $view:ty, $ctor:ident, $n:literal, $name:literal) => {
+        impl $owned {
+            /// Borrow as the zero-copy view.
+            #[inline]
+            pub fn as_view(&self) -> $view {
+                $ctor(&self.0)
+            }
+
+            /// Returns the inner byte array reference.
+            #[inline]
+            pub fn as_bytes(&self) -> &[u8; $n] {
+                &self.0
+            }
+
+            #[doc = concat!("Parse hex encoding exactly ", $n, " bytes, with or without a `0x` prefix.")]
+            /// Case is not checked; use a checksum-aware parser where one exists.
+            pub fn from_hex(s: &str) -> Result<Self, ZError> {
+                let mut bytes = [0u8; $n];
+                crate::hex::parse_hex_into(s, &mut bytes)
+                    .ok_or(ZError::Custom(concat!($name, " must be ", $n, " hex-encoded bytes")))?;
+                Ok($owned(bytes))
+            }
+        }
+
+        impl From<[u8; $n]> for $owned {
+            #[inline]
+            fn from(bytes: [u8; $n]) -> Self {
+                $owned(bytes)
+            }
+        }
+
+        impl From<$owned> for [u8; $n] {
+            #[inline]
+            fn from(value: $owned) -> Self {
+                value.0
+            }
+        }
+
+        impl PartialEq<$owned> for $view {
+            #[inline]
+            fn eq(&self, other: &$owned) -> bool {
+                *self.0 == other.0
+            }
+        }
+
+        impl PartialEq<$view> for $owned {
+            #[inline]
+            fn eq(&self, other: &$view) -> bool {
+                self.0 == *other.0
+            }
+        }
+
+        impl From<$view> for $owned {
+            #[inline]
+            fn from(view: $view) -> Self {
+                $owned(*view.0)
+            }
+        }
+
+        impl fmt::Debug for $owned {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, concat!($name, "(0x"))?;
+                for byte in &self.0 {
+                    write!(f, "{:02x}", byte)?;
+                }
+                write!(f, ")")
+            }
+        }
+
+    };
+}
+
+macro_rules! impl_display_hex {
+    ($owned:ident) => {
+        impl fmt::Display for $owned {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "0x")?;
+                for byte in &self.0 {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+macro_rules! impl_display_decimal {
+    ($owned:ident) => {
+        /// Formats the value in base 10, like its view.
+        impl fmt::Display for $owned {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.as_view(), f)
+            }
+        }
+    };
+}
+
+impl_owned!(Address, ZAddress<'_>, ZAddress, 20, "Address");
+impl_owned!(U256, ZU256<'_>, ZU256, 32, "U256");
+impl_owned!(I256, ZInt256<'_>, ZInt256, 32, "I256");
+impl_owned!(Bytes32, ZBytesN<'_, 32>, ZBytesN, 32, "Bytes32");
+
+crate::types::impl_hex_fmt!(Address, U256, I256, Bytes32);
+
+// The owned types are `Copy`, so they cannot scrub themselves on drop;
+// callers holding secrets call `zeroize()` once done with a value.
+#[cfg(feature = "zeroize")]
+macro_rules! impl_zeroize {
+    ($($owned:ident),+) => {
+        $(
+            impl zeroize::Zeroize for $owned {
+                fn zeroize(&mut self) {
+                    self.0.zeroize();
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "zeroize")]
+impl_zeroize!(Address, U256, I256, Bytes32);
+
+#[cfg(feature = "subtle")]
+macro_rules! impl_ct_eq {
+    ($($owned:ident),+) => {
+        $(
+            impl subtle::ConstantTimeEq for $owned {
+                #[inline]
+                fn ct_eq(&self, other: &Self) -> subtle::Choice {
+                    self.0[..].ct_eq(&other.0[..])
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "subtle")]
+impl_ct_eq!(Address, U256, Bytes32);
+
+impl_display_hex!(Address);
+impl_display_hex!(Bytes32);
+impl_display_decimal!(U256);
+impl_display_decimal!(I256);
+
+impl PartialOrd for I256 {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for I256 {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.as_view().cmp(&other.as_view())
+    }
+}
+
+impl Address {
+    /// The zero address, used as the mint source and burn sink.
+    pub const ZERO: Address = Address([0u8; 20]);
+
+    /// Check if this is the zero address.
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        *self == Address::ZERO
+    }
+
+    /// Parse a `0x`-prefixed address whose mixed-case EIP-55 checksum must
+    /// match exactly. All-lowercase or all-uppercase input carries no
+    /// checksum and is rejected too.
+    #[cfg(feature = "keccak")]
+    pub fn from_checksummed(s: &str) -> Result<Address, ZError> {
+        let digits = s
+            .strip_prefix("0x")
+            .ok_or(ZError::Custom("address must start with 0x"))?;
+        let mut bytes = [0u8; 20];
+        crate::hex::parse_hex_into(digits, &mut bytes)
+            .ok_or(ZError::Custom("address is not 40 hex digits"))?;
+        let address = Address(bytes);
+        let mut buf = [0u8; 42];
+        if address.as_view().to_checksum(&mut buf) != s {
+            return Err(ZError::Custom("invalid address checksum"));
+        }
+        Ok(address)
+    }
+}
+
+impl Bytes32 {
+    /// All-zero bytes32.
+    pub const ZERO: Bytes32 = Bytes32([0u8; 32]);
+}
+
+impl U256 {
+    /// The value zero.
+    pub const ZERO: U256 = U256([0u8; 32]);
+    /// The value one.
+    pub const ONE: U256 = {
+        let mut bytes = [0u8; 32];
+        bytes[31] = 1;
+        U256(bytes)
+    };
+    /// `type(uint256).max`, the usual "unlimited" approval amount.
+    pub const MAX: U256 = U256([0xff; 32]);
+
+    /// Check if the value is `type(uint256).max`.
+    #[inline]
+    pub fn is_max(&self) -> bool {
+        *self == U256::MAX
+    }
+
+    /// Check if the value is zero.
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.as_view().is_zero()
+    }
+
+    /// Convert to u64 if the value fits.
+    #[inline]
+    pub fn to_u64(&self) -> Option<u64> {
+        self.as_view().to_u64()
+    }
+
+    /// Convert to u128 if the value fits.
+    #[inline]
+    pub fn to_u128(&self) -> Option<u128> {
+        self.as_view().to_u128()
+    }
+
+    /// Parse a decimal amount such as `"1.5"` into base units, scaling by
+    /// `10^decimals`; the inverse of [`ZU256::format_units`].
+    ///
+    /// Fails on anything but digits with at most one `.`, on more than
+    /// `decimals` fractional digits, and on values that overflow uint256.
+    pub fn parse_units(s: &str, decimals: u8) -> Result<U256, ZError> {
+        let (int_part, frac_part) = match s.split_once('.') {
+            Some((int_part, frac_part)) => (int_part, frac_part),
+            None => (s, ""),
+        };
+        if int_part.is_empty() && frac_part.is_empty() {
+            return Err(ZError::Custom("empty amount"));
+        }
+        if frac_part.len() > decimals as usize {
+            return Err(ZError::Custom("too many fractional digits"));
+        }
+
+        let mut limbs = [0u64; 4];
+        let padding = decimals as usize - frac_part.len();
+        let digits = int_part
+            .bytes()
+            .chain(frac_part.bytes())
+            .chain(core::iter::repeat_n(b'0', padding));
+        for d in digits {
+            if !d.is_ascii_digit() {
+                return Err(ZError::Custom("invalid digit in amount"));
+            }
+            // limbs = limbs * 10 + d, least significant limb last.
+            let mut carry = (d - b'0') as u128;
+            for limb in limbs.iter_mut().rev() {
+                let cur = *limb as u128 * 10 + carry;
+                *limb = cur as u64;
+                carry = cur >> 64;
+            }
+            if carry != 0 {
+                return Err(ZError::Custom("amount overflows uint256"));
+            }
+        }
+
+        let mut bytes = [0u8; 32];
+        for (chunk, limb) in bytes.chunks_exact_mut(8).zip(limbs) {
+            chunk.copy_from_slice(&limb.to_be_bytes());
+        }
+        Ok(U256(bytes))
+    }
+}
+
+impl From<u64> for U256 {
+    #[inline]
+    fn from(value: u64) -> Self {
+        let mut bytes = [0u8; 32];
+        bytes[24..].copy_from_slice(&value.to_be_bytes());
+        U256(bytes)
+    }
+}
+
+impl From<u128> for U256 {
+    #[inline]
+    fn from(value: u128) -> Self {
+        let mut bytes = [0u8; 32];
+        bytes[16..].copy_from_slice(&value.to_be_bytes());
+        U256(bytes)
+    }
+}
+
+impl ZAddress<'_> {
+    /// Copy into an owned [`Address`].
+    #[inline]
+    pub fn to_owned(&self) -> Address {
+        Address(*self.0)
+    }
+}
+
+impl ZU256<'_> {
+    /// Copy into an owned [`U256`].
+    #[inline]
+    pub fn to_owned(&self) -> U256 {
+        U256(*self.0)
+    }
+}
+
+impl ZInt256<'_> {
+    /// Copy into an owned [`I256`].
+    #[inline]
+    pub fn to_owned(&self) -> I256 {
+        I256(*self.0)
+    }
+}
+
+impl ZBytesN<'_, 32> {
+    /// Copy into an owned [`Bytes32`].
+    #[inline]
+    pub fn to_owned(&self) -> Bytes32 {
+        Bytes32(*self.0)
+    }
+}
+
+/// Copies a decoded value into one that no longer borrows the input.
+///
+/// Words become [`U256`], [`I256`] and [`Address`], `bytesN` a `[u8; N]`,
+/// and `bool` and native integers stay as they are. With `alloc`, `bytes`
+/// and `string` become `Vec<u8>` and `String`, and arrays a `Vec` or
+/// `[_; N]` of owned elements. `#[zabi(owned)]` implements it for a derived
+/// struct, into a generated `<Name>Owned` mirror.
+pub trait IntoOwned {
+    type Owned;
+
+    /// Fails only if an array element, decoded here, is malformed.
+    fn into_owned(self) -> Result<Self::Owned, ZError>;
+}
+
+macro_rules! impl_into_owned {
+    ($($view:ty => $owned:ty, $convert:expr;)+) => {
+        $(
+            impl IntoOwned for $view {
+                type Owned = $owned;
+
+                #[inline]
+                fn into_owned(self) -> Result<$owned, ZError> {
+                    #[allow(clippy::redundant_closure_call)]
+                    Ok(($convert)(self))
+                }
+            }
+        )+
+    };
+}
+
+impl_into_owned! {
+    ZU256<'_> => U256, |v: ZU256<'_>| U256(*v.0);
+    ZInt256<'_> => I256, |v: ZInt256<'_>| I256(*v.0);
+    ZAddress<'_> => Address, |v: ZAddress<'_>| Address(*v.0);
+    ZBool => bool, |v: ZBool| v.0;
+    bool => bool, |v| v;
+    u8 => u8, |v| v;
+    u16 => u16, |v| v;
+    u32 => u32, |v| v;
+    u64 => u64, |v| v;
+    u128 => u128, |v| v;
+    i8 => i8, |v| v;
+    i16 => i16, |v| v;
+    i32 => i32, |v| v;
+    i64 => i64, |v| v;
+    i128 => i128, |v| v;
+}
+
+impl<const N: usize> IntoOwned for ZBytesN<'_, N> {
+    type Owned = [u8; N];
+
+    #[inline]
+    fn into_owned(self) -> Result<[u8; N], ZError> {
+        Ok(*self.0)
+    }
+}
+
+/// For fields read with `#[zabi(as = "bytesN")]` or `as = "address"`.
+impl<const N: usize> IntoOwned for &[u8; N] {
+    type Owned = [u8; N];
+
+    #[inline]
+    fn into_owned(self) -> Result<[u8; N], ZError> {
+        Ok(*self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod alloc_impls {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use super::IntoOwned;
+    use crate::error::ZError;
+    use crate::types::{ZArray, ZBytes, ZFixedArray, ZString};
+    use crate::ZDecode;
+
+    impl IntoOwned for ZBytes<'_> {
+        type Owned = Vec<u8>;
+
+        fn into_owned(self) -> Result<Vec<u8>, ZError> {
+            Ok(self.0.to_vec())
+        }
+    }
+
+    impl IntoOwned for ZString<'_> {
+        type Owned = String;
+
+        fn into_owned(self) -> Result<String, ZError> {
+            Ok(String::from(self.0))
+        }
+    }
+
+    /// For fields read with `#[zabi(as = "bytes")]`.
+    impl IntoOwned for &[u8] {
+        type Owned = Vec<u8>;
+
+        fn into_owned(self) -> Result<Vec<u8>, ZError> {
+            Ok(self.to_vec())
+        }
+    }
+
+    /// For fields read with `#[zabi(as = "string")]`.
+    impl IntoOwned for &str {
+        type Owned = String;
+
+        fn into_owned(self) -> Result<String, ZError> {
+            Ok(String::from(self))
+        }
+    }
+
+    impl<'a, T: ZDecode<'a> + IntoOwned> IntoOwned for ZArray<'a, T> {
+        type Owned = Vec<T::Owned>;
+
+        fn into_owned(self) -> Result<Vec<T::Owned>, ZError> {
+            (0..self.len()).map(|i| self.get(i)?.into_owned().map_err(|e| e.in_field(i))).collect()
+        }
+    }
+
+    impl<'a, T: ZDecode<'a> + IntoOwned, const N: usize> IntoOwned for ZFixedArray<'a, T, N> {
+        type Owned = [T::Owned; N];
+
+        fn into_owned(self) -> Result<[T::Owned; N], ZError> {
+            let items = self.0.into_owned()?;
+            // The view always holds `N` elements.
+            Ok(items.try_into().unwrap_or_else(|_| unreachable!()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::BTreeMap;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_owned_outlives_buffer() {
+        let mut balances: BTreeMap<Address, U256> = BTreeMap::new();
+        {
+            let mut data = Vec::new();
+            data.extend_from_slice(&[0u8; 12]);
+            data.extend_from_slice(&[0xab; 20]);
+            let mut amount = [0u8; 32];
+            amount[31] = 7;
+            data.extend_from_slice(&amount);
+
+            let addr = crate::decoder::read_address_from_word(&data, 0).unwrap();
+            let value = crate::decoder::read_u256(&data, 32).unwrap();
+            balances.insert(addr.to_owned(), value.to_owned());
+        }
+        let value = balances[&Address([0xab; 20])];
+        assert_eq!(value, U256::from(7u64));
+        assert_eq!(value.to_u64(), Some(7));
+        assert_eq!(value.as_view().to_u8(), Some(7));
+    }
+
+    #[test]
+    fn test_owned_round_trip() {
+        let word = [0x11u8; 32];
+        let hash = ZBytesN::<32>(&word).to_owned();
+        assert_eq!(hash, Bytes32(word));
+        assert_eq!(hash.as_view(), ZBytesN(&word));
+        assert_eq!(Bytes32::from(ZBytesN(&word)), hash);
+
+        let addr = Address::from([0x01; 20]);
+        assert_eq!(addr.as_view().to_owned(), addr);
+        assert_eq!(<[u8; 20]>::from(addr), [0x01; 20]);
+        assert!(U256::ZERO.is_zero());
+        assert_eq!(U256::from(u128::MAX).to_u128(), Some(u128::MAX));
+        assert_eq!(U256::from(u128::MAX).to_u64(), None);
+
+        assert_eq!(std::format!("{}", addr), std::format!("{}", addr.as_view()));
+        assert!(std::format!("{:?}", hash).starts_with("Bytes32(0x1111"));
+    }
+
+    #[test]
+    fn test_units_round_trip() {
+        let mut buf = [0u8; 100];
+        let amount = U256::parse_units("1.5", 18).unwrap();
+        assert_eq!(amount.to_u64(), Some(1_500_000_000_000_000_000));
+        assert_eq!(amount.as_view().format_units(18, &mut buf), "1.5");
+
+        assert_eq!(
+            U256::from(1_000_000u64).as_view().format_units(6, &mut buf),
+            "1"
+        );
+        assert_eq!(
+            U256::from(1u64).as_view().format_units(18, &mut buf),
+            "0.000000000000000001"
+        );
+        assert_eq!(
+            U256::from(1_234_500u64).as_view().format_units(2, &mut buf),
+            "12345"
+        );
+        assert_eq!(U256::ZERO.as_view().format_units(18, &mut buf), "0");
+        assert_eq!(U256::from(42u64).as_view().format_units(0, &mut buf), "42");
+
+        assert_eq!(U256::parse_units("0.000001", 6).unwrap(), U256::from(1u64));
+        assert_eq!(U256::parse_units(".5", 1).unwrap(), U256::from(5u64));
+        assert_eq!(U256::parse_units("7", 0).unwrap(), U256::from(7u64));
+        assert!(U256::parse_units("1.0000001", 6).is_err());
+        assert!(U256::parse_units("1,5", 6).is_err());
+        assert!(U256::parse_units("", 6).is_err());
+        assert!(U256::parse_units("1.2.3", 6).is_err());
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(U256::parse_units(max, 0).unwrap(), U256([0xff; 32]));
+        assert!(U256::parse_units(max, 1).is_err());
+    }
+
+    #[test]
+    fn test_into_owned() {
+        let word = [0x22u8; 32];
+        assert_eq!(ZU256(&word).into_owned().unwrap(), U256(word));
+        assert_eq!(ZInt256(&word).into_owned().unwrap(), I256(word));
+        assert_eq!(ZAddress(&[0x33; 20]).into_owned().unwrap(), Address([0x33; 20]));
+        assert_eq!(ZBytesN::<4>(&[1, 2, 3, 4]).into_owned().unwrap(), [1, 2, 3, 4]);
+        assert!(ZBool(true).into_owned().unwrap());
+        assert_eq!((-7i64).into_owned().unwrap(), -7);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_into_owned_dynamic() {
+        let mut data = [0u8; 128];
+        data[31] = 32; // offset
+        data[63] = 2; // length
+        data[95] = 5;
+        data[127] = 6;
+        let items = crate::decoder::read_array_dyn::<u64>(&data, 0).unwrap();
+        assert_eq!(items.into_owned().unwrap(), [5, 6]);
+        let pair = crate::types::ZFixedArray::<u64, 2>(items);
+        assert_eq!(pair.into_owned().unwrap(), [5, 6]);
+
+        data[127 - 8] = 1; // second element overflows u64
+        let err = crate::decoder::read_array_dyn::<u64>(&data, 0).unwrap().into_owned().unwrap_err();
+        assert!(matches!(err, ZError::Field { index: 1, .. }));
+        assert_eq!(crate::types::ZString("hi").into_owned().unwrap(), "hi");
+        assert_eq!(crate::types::ZBytes(&[9]).into_owned().unwrap(), [9]);
+    }
+
+    #[cfg(feature = "borsh")]
+    #[test]
+    fn test_borsh_round_trip() {
+        let value = (Address([0x01; 20]), U256::MAX, I256([0x80; 32]), Bytes32([0x02; 32]));
+        let bytes = borsh::to_vec(&value).unwrap();
+        assert_eq!(bytes.len(), 20 + 32 * 3);
+        assert_eq!(borsh::from_slice::<(Address, U256, I256, Bytes32)>(&bytes).unwrap(), value);
+    }
+
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn test_rkyv_round_trip() {
+        let value = (Address([0x01; 20]), U256::MAX, I256([0x80; 32]), Bytes32([0x02; 32]));
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&value).unwrap();
+        let back = rkyv::from_bytes::<(Address, U256, I256, Bytes32), rkyv::rancor::Error>(&bytes).unwrap();
+        assert_eq!(back, value);
+    }
+
+    #[test]
+    fn test_constants() {
+        assert!(Address::ZERO.is_zero() && Address::ZERO.as_view().is_zero());
+        assert!(!Address([0x01; 20]).is_zero());
+        assert!(U256::MAX.is_max() && U256::MAX.as_view().is_max());
+        assert!(!U256::ONE.is_max() && !U256::ZERO.as_view().is_max());
+        assert_eq!(U256::ONE.to_u64(), Some(1));
+        assert_eq!(U256::ONE, U256::from(1u64));
+        assert!(U256::ZERO < U256::ONE && U256::ONE < U256::MAX);
+        assert_eq!(Bytes32::ZERO, Bytes32::default());
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn test_checksum() {
+        // Test vectors from EIP-55.
+        for expected in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            let address = Address::from_checksummed(expected).unwrap();
+            let mut buf = [0u8; 42];
+            assert_eq!(address.as_view().to_checksum(&mut buf), expected);
+        }
+
+        let lower = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+        assert!(Address::from_checksummed(lower).is_err());
+        assert!(Address::from_checksummed("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD").is_err());
+        assert!(Address::from_checksummed("5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_err());
+        assert!(Address::from_checksummed("0x5aAeb6").is_err());
+    }
+
+    #[test]
+    fn test_from_hex() {
+        let router = Address::from_hex("0x7a250d5630b4cf539739df2c5dacb4c659f2488d").unwrap();
+        assert_eq!(router.0[0], 0x7a);
+        assert_eq!(
+            Address::from_hex("7A250D5630B4CF539739DF2C5DACB4C659F2488D").unwrap(),
+            router
+        );
+        assert!(Address::from_hex("0x7a250d").is_err());
+        assert!(Address::from_hex("0x7a250d5630b4cf539739df2c5dacb4c659f2488d00").is_err());
+        assert!(Address::from_hex("0xzz250d5630b4cf539739df2c5dacb4c659f2488d").is_err());
+
+        let one = "0x0000000000000000000000000000000000000000000000000000000000000001";
+        assert_eq!(U256::from_hex(one).unwrap(), U256::ONE);
+        assert_eq!(Bytes32::from_hex(&one[2..]).unwrap().0[31], 1);
+        assert!(U256::from_hex("0x1").is_err());
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize() {
+        use zeroize::Zeroize;
+
+        let mut key = Bytes32([0x42; 32]);
+        key.zeroize();
+        assert_eq!(key, Bytes32::ZERO);
+        let mut amount = U256::MAX;
+        amount.zeroize();
+        assert!(amount.is_zero());
+    }
+
+    #[cfg(feature = "subtle")]
+    #[test]
+    fn test_ct_eq() {
+        use subtle::ConstantTimeEq;
+
+        let a = [0x11u8; 32];
+        let mut b = a;
+        assert!(bool::from(ZU256(&a).ct_eq(&ZU256(&b))));
+        b[31] = 0;
+        assert!(!bool::from(ZU256(&a).ct_eq(&ZU256(&b))));
+        assert!(!bool::from(ZBytesN(&a).ct_eq(&ZBytesN(&b))));
+        assert!(bool::from(ZAddress(&[1; 20]).ct_eq(&ZAddress(&[1; 20]))));
+        assert!(bool::from(Bytes32(a).ct_eq(&Bytes32(a))));
+        assert!(!bool::from(U256(a).ct_eq(&U256(b))));
+    }
+}
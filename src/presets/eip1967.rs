@@ -0,0 +1,102 @@
+//! EIP-1967 proxy storage slots.
+//!
+//! Read a slot with `eth_getStorageAt(proxy, SLOT)` and pass the 32-byte
+//! word to [`slot_address`] to get the implementation, admin or beacon. A
+//! beacon proxy's implementation is then `implementation()` on the beacon.
+//!
+//! ```
+//! use zabi_rs::presets::eip1967::slot_address;
+//!
+//! let mut word = [0u8; 32];
+//! word[12..].copy_from_slice(&[0x11; 20]);
+//! let implementation = slot_address(&word).unwrap().unwrap();
+//! assert_eq!(implementation.as_bytes(), &[0x11; 20]);
+//! assert!(slot_address(&[0u8; 32]).unwrap().is_none());
+//! ```
+
+use crate::sol;
+use crate::{ZAddress, ZError};
+
+sol! {
+    event Upgraded(address indexed implementation);
+    event AdminChanged(address previousAdmin, address newAdmin);
+    event BeaconUpgraded(address indexed beacon);
+
+    function implementation() external returns (address);
+}
+
+/// `keccak256("eip1967.proxy.implementation") - 1`.
+pub const IMPLEMENTATION_SLOT: [u8; 32] = [
+    0x36, 0x08, 0x94, 0xa1, 0x3b, 0xa1, 0xa3, 0x21, 0x06, 0x67, 0xc8, 0x28, 0x49, 0x2d, 0xb9, 0x8d,
+    0xca, 0x3e, 0x20, 0x76, 0xcc, 0x37, 0x35, 0xa9, 0x20, 0xa3, 0xca, 0x50, 0x5d, 0x38, 0x2b, 0xbc,
+];
+
+/// `keccak256("eip1967.proxy.admin") - 1`.
+pub const ADMIN_SLOT: [u8; 32] = [
+    0xb5, 0x31, 0x27, 0x68, 0x4a, 0x56, 0x8b, 0x31, 0x73, 0xae, 0x13, 0xb9, 0xf8, 0xa6, 0x01, 0x6e,
+    0x24, 0x3e, 0x63, 0xb6, 0xe8, 0xee, 0x11, 0x78, 0xd6, 0xa7, 0x17, 0x85, 0x0b, 0x5d, 0x61, 0x03,
+];
+
+/// `keccak256("eip1967.proxy.beacon") - 1`.
+pub const BEACON_SLOT: [u8; 32] = [
+    0xa3, 0xf0, 0xad, 0x74, 0xe5, 0x42, 0x3a, 0xeb, 0xfd, 0x80, 0xd3, 0xef, 0x43, 0x46, 0x57, 0x83,
+    0x35, 0xa9, 0xa7, 0x2a, 0xea, 0xee, 0x59, 0xff, 0x6c, 0xb3, 0x58, 0x2b, 0x35, 0x13, 0x3d, 0x50,
+];
+
+/// The address stored in a slot word, or `None` for an empty slot.
+///
+/// Fails with [`ZError::InvalidPadding`] if the upper 12 bytes are not zero,
+/// i.e. the slot holds something other than an address.
+pub fn slot_address(word: &[u8; 32]) -> Result<Option<ZAddress<'_>>, ZError> {
+    if word[..12].iter().any(|&b| b != 0) {
+        return Err(ZError::InvalidPadding {
+            offset: 0,
+            ty: "address",
+        });
+    }
+    let address = ZAddress(word[12..].try_into().unwrap());
+    Ok(if address.is_zero() {
+        None
+    } else {
+        Some(address)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_address() {
+        let mut word = [0u8; 32];
+        word[31] = 1;
+        assert_eq!(slot_address(&word).unwrap().unwrap().as_bytes()[19], 1);
+        word[11] = 1;
+        assert!(matches!(
+            slot_address(&word),
+            Err(ZError::InvalidPadding {
+                offset: 0,
+                ty: "address"
+            })
+        ));
+        assert_eq!(implementationCall::SELECTOR, [0x5c, 0x60, 0xda, 0x1b]);
+        assert_eq!(Upgraded::TOPIC0[..4], [0xbc, 0x7c, 0xd7, 0x5a]);
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn test_slot_constants() {
+        use crate::hash::keccak256;
+
+        for (slot, name) in [
+            (IMPLEMENTATION_SLOT, "eip1967.proxy.implementation"),
+            (ADMIN_SLOT, "eip1967.proxy.admin"),
+            (BEACON_SLOT, "eip1967.proxy.beacon"),
+        ] {
+            let mut expected = keccak256(name.as_bytes());
+            // None of the hashes end in a zero byte, so no borrow.
+            expected[31] -= 1;
+            assert_eq!(slot, expected, "{}", name);
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! EIP-2612 `permit`: gasless ERC-20 approvals signed as EIP-712 messages.
+//!
+//! The `sol!` part decodes `permit` calldata. [`Permit`] is the signed
+//! message itself; with the `keccak` feature it computes the EIP-712 struct
+//! hash and the digest a wallet signs or a verifier recovers from.
+//!
+//! ```
+//! # #[cfg(feature = "keccak")] {
+//! use zabi_rs::presets::eip2612::{domain_separator, Permit};
+//! use zabi_rs::{Address, U256};
+//!
+//! let token = Address([0x11; 20]);
+//! let domain = domain_separator("Token", "1", 1, &token);
+//! let permit = Permit {
+//!     owner: Address([0xaa; 20]),
+//!     spender: Address([0xbb; 20]),
+//!     value: U256::from(1_000u64),
+//!     nonce: U256::ZERO,
+//!     deadline: U256::MAX,
+//! };
+//! let digest: [u8; 32] = permit.digest(&domain);
+//! # }
+//! ```
+
+use crate::owned::{Address, U256};
+use crate::sol;
+
+sol! {
+    function permit(address owner, address spender, uint256 value, uint256 deadline, uint8 v, bytes32 r, bytes32 s) external;
+    function nonces(address owner) external returns (uint256);
+    function DOMAIN_SEPARATOR() external returns (bytes32);
+}
+
+/// `keccak256("Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)")`.
+pub const PERMIT_TYPEHASH: [u8; 32] = [
+    0x6e, 0x71, 0xed, 0xae, 0x12, 0xb1, 0xb9, 0x7f, 0x4d, 0x1f, 0x60, 0x37, 0x0f, 0xef, 0x10, 0x10,
+    0x5f, 0xa2, 0xfa, 0xae, 0x01, 0x26, 0x11, 0x4a, 0x16, 0x9c, 0x64, 0x84, 0x5d, 0x61, 0x26, 0xc9,
+];
+
+/// `keccak256("EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)")`.
+pub const EIP712_DOMAIN_TYPEHASH: [u8; 32] = [
+    0x8b, 0x73, 0xc3, 0xc6, 0x9b, 0xb8, 0xfe, 0x3d, 0x51, 0x2e, 0xcc, 0x4c, 0xf7, 0x59, 0xcc, 0x79,
+    0x23, 0x9f, 0x7b, 0x17, 0x9b, 0x0f, 0xfa, 0xca, 0xa9, 0xa7, 0x5d, 0x52, 0x2b, 0x39, 0x40, 0x0f,
+];
+
+/// The EIP-712 message signed for a permit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Permit {
+    pub owner: Address,
+    pub spender: Address,
+    pub value: U256,
+    /// The owner's current `nonces(owner)`; not part of the calldata.
+    pub nonce: U256,
+    pub deadline: U256,
+}
+
+impl Permit {
+    /// The message a `permit` call was signed over, given the owner's nonce
+    /// at the time of signing.
+    pub fn from_call(call: &permitCall<'_>, nonce: U256) -> Self {
+        Permit {
+            owner: call.owner.to_owned(),
+            spender: call.spender.to_owned(),
+            value: call.value.to_owned(),
+            nonce,
+            deadline: call.deadline.to_owned(),
+        }
+    }
+
+    /// `hashStruct(permit)`: the type hash and the five fields, one word each.
+    #[cfg(feature = "keccak")]
+    pub fn struct_hash(&self) -> [u8; 32] {
+        let mut buf = [0u8; 192];
+        buf[..32].copy_from_slice(&PERMIT_TYPEHASH);
+        buf[44..64].copy_from_slice(self.owner.as_bytes());
+        buf[76..96].copy_from_slice(self.spender.as_bytes());
+        buf[96..128].copy_from_slice(self.value.as_bytes());
+        buf[128..160].copy_from_slice(self.nonce.as_bytes());
+        buf[160..].copy_from_slice(self.deadline.as_bytes());
+        crate::hash::keccak256(&buf)
+    }
+
+    /// The EIP-712 digest, `keccak256(0x1901 ‖ domainSeparator ‖ hashStruct)`,
+    /// for the token's `DOMAIN_SEPARATOR()`.
+    #[cfg(feature = "keccak")]
+    pub fn digest(&self, domain_separator: &[u8; 32]) -> [u8; 32] {
+        let mut buf = [0u8; 66];
+        buf[..2].copy_from_slice(&[0x19, 0x01]);
+        buf[2..34].copy_from_slice(domain_separator);
+        buf[34..].copy_from_slice(&self.struct_hash());
+        crate::hash::keccak256(&buf)
+    }
+}
+
+/// The domain separator of a token using the common
+/// `EIP712Domain(name, version, chainId, verifyingContract)` domain.
+/// Tokens may use other domain fields; prefer reading `DOMAIN_SEPARATOR()`.
+#[cfg(feature = "keccak")]
+pub fn domain_separator(
+    name: &str,
+    version: &str,
+    chain_id: u64,
+    verifying_contract: &Address,
+) -> [u8; 32] {
+    let mut buf = [0u8; 160];
+    buf[..32].copy_from_slice(&EIP712_DOMAIN_TYPEHASH);
+    buf[32..64].copy_from_slice(&crate::hash::keccak256(name.as_bytes()));
+    buf[64..96].copy_from_slice(&crate::hash::keccak256(version.as_bytes()));
+    buf[120..128].copy_from_slice(&chain_id.to_be_bytes());
+    buf[140..].copy_from_slice(verifying_contract.as_bytes());
+    crate::hash::keccak256(&buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permit_call() {
+        assert_eq!(permitCall::SELECTOR, [0xd5, 0x05, 0xac, 0xcf]);
+        assert_eq!(noncesCall::SELECTOR, [0x7e, 0xce, 0xbe, 0x00]);
+        assert_eq!(DOMAIN_SEPARATORCall::SELECTOR, [0x36, 0x44, 0xe5, 0x15]);
+
+        let mut calldata = permitCall::SELECTOR.to_vec();
+        for (i, last) in [0xaa, 0xbb, 0x64, 0xff, 27, 1, 2].into_iter().enumerate() {
+            let mut word = [0u8; 32];
+            word[31] = last;
+            if i < 2 {
+                word[12] = last;
+            }
+            calldata.extend_from_slice(&word);
+        }
+        let call = permitCall::decode_calldata(&calldata).unwrap();
+        assert_eq!(call.v, 27);
+        let permit = Permit::from_call(&call, U256::from(3u64));
+        assert_eq!(permit.owner.as_bytes()[0], 0xaa);
+        assert_eq!(permit.value, U256::from(100u64));
+        assert_eq!(permit.nonce, U256::from(3u64));
+        assert_eq!(permit.deadline, U256::from(255u64));
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn test_permit_hashes() {
+        use crate::hash::keccak256;
+        use crate::owned::Bytes32;
+
+        assert_eq!(
+            PERMIT_TYPEHASH,
+            keccak256(b"Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)")
+        );
+        assert_eq!(
+            EIP712_DOMAIN_TYPEHASH,
+            keccak256(b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)")
+        );
+
+        // USDC on mainnet.
+        let usdc = Address::from_hex("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+        let domain = domain_separator("USD Coin", "2", 1, &usdc);
+        let expected =
+            Bytes32::from_hex("0x06c37168a7db5138defc7866392bb87a741f9b3d104deb5094588ce041cae335")
+                .unwrap();
+        assert_eq!(Bytes32(domain), expected);
+
+        let permit = Permit {
+            owner: Address([0xaa; 20]),
+            spender: Address([0xbb; 20]),
+            value: U256::from(1u64),
+            nonce: U256::ZERO,
+            deadline: U256::MAX,
+        };
+        let digest = permit.digest(&domain);
+        assert_ne!(digest, permit.digest(&[0u8; 32]));
+        assert_ne!(
+            permit.struct_hash(),
+            Permit {
+                nonce: U256::ONE,
+                ..permit
+            }
+            .struct_hash()
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! ENS name resolution: [`namehash`] and the common registry and resolver
+//! calls.
+//!
+//! ```
+//! # #[cfg(feature = "keccak")] {
+//! use zabi_rs::presets::ens::{addrCall, namehash};
+//!
+//! let node = namehash("vitalik.eth");
+//! let mut calldata = addrCall::SELECTOR.to_vec();
+//! calldata.extend_from_slice(&node);
+//! let call = addrCall::decode_calldata(&calldata).unwrap();
+//! assert_eq!(call.node.as_bytes(), &node);
+//! # }
+//! ```
+
+use crate::sol;
+
+sol! {
+    function resolver(bytes32 node) external returns (address);
+    function addr(bytes32 node) external returns (address);
+    function text(bytes32 node, string key) external returns (string);
+    function name(bytes32 node) external returns (string);
+    function contenthash(bytes32 node) external returns (bytes);
+}
+
+/// `keccak256` of one label, e.g. `"vitalik"`.
+#[cfg(feature = "keccak")]
+pub fn labelhash(label: &str) -> [u8; 32] {
+    crate::hash::keccak256(label.as_bytes())
+}
+
+/// The EIP-137 namehash of a dotted name; the empty name hashes to zero.
+///
+/// The name is hashed as given: normalize it (ENSIP-15) first, as resolvers
+/// only know normalized names.
+#[cfg(feature = "keccak")]
+pub fn namehash(name: &str) -> [u8; 32] {
+    let mut node = [0u8; 32];
+    if name.is_empty() {
+        return node;
+    }
+    let mut buf = [0u8; 64];
+    for label in name.rsplit('.') {
+        buf[..32].copy_from_slice(&node);
+        buf[32..].copy_from_slice(&labelhash(label));
+        node = crate::hash::keccak256(&buf);
+    }
+    node
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolver_selectors() {
+        assert_eq!(resolverCall::SELECTOR, [0x01, 0x78, 0xb8, 0xbf]);
+        assert_eq!(addrCall::SELECTOR, [0x3b, 0x3b, 0x57, 0xde]);
+        assert_eq!(textCall::SELECTOR, [0x59, 0xd1, 0xd4, 0x3c]);
+        assert_eq!(nameCall::SELECTOR, [0x69, 0x1f, 0x34, 0x31]);
+        assert_eq!(contenthashCall::SELECTOR, [0xbc, 0x1c, 0x58, 0xd1]);
+    }
+
+    #[test]
+    fn test_text_call() {
+        let mut calldata = [0u8; 4 + 4 * 32];
+        calldata[..4].copy_from_slice(&textCall::SELECTOR);
+        calldata[4..36].fill(0x11);
+        calldata[67] = 0x40;
+        calldata[99] = 3;
+        calldata[100..103].copy_from_slice(b"url");
+        let call = textCall::decode_calldata(&calldata).unwrap();
+        assert_eq!(call.node.as_bytes(), &[0x11; 32]);
+        assert_eq!(call.key.as_str(), "url");
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn test_namehash() {
+        use crate::owned::Bytes32;
+
+        // EIP-137 test vectors.
+        assert_eq!(namehash(""), [0u8; 32]);
+        let eth =
+            Bytes32::from_hex("0x93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae")
+                .unwrap();
+        assert_eq!(Bytes32(namehash("eth")), eth);
+        let foo =
+            Bytes32::from_hex("0xde9b09fd7c5f901e23a3f19fecc54828e9c848539801e86591bd9801b019f84f")
+                .unwrap();
+        assert_eq!(Bytes32(namehash("foo.eth")), foo);
+    }
+}
@@ -0,0 +1,57 @@
+//! ERC-1155 multi-token events.
+
+use crate::sol;
+
+sol! {
+    event TransferSingle(address indexed operator, address indexed from, address indexed to, uint256 id, uint256 value);
+    event TransferBatch(address indexed operator, address indexed from, address indexed to, uint256[] ids, uint256[] values);
+    event ApprovalForAll(address indexed account, address indexed operator, bool approved);
+    event URI(string value, uint256 indexed id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::word;
+    use crate::ZEventLog;
+
+    #[test]
+    fn test_erc1155_topics() {
+        assert_eq!(TransferSingle::TOPIC0[..4], [0xc3, 0xd5, 0x81, 0x68]);
+        assert_eq!(TransferBatch::TOPIC0[..4], [0x4a, 0x39, 0xdc, 0x06]);
+        assert_eq!(URI::SIGNATURE, "URI(string,uint256)");
+    }
+
+    #[test]
+    fn test_erc1155_transfer_single() {
+        let topics = [TransferSingle::TOPIC0, word(9), word(1), word(2)];
+        let data = [word(5), word(40)].concat();
+        let event = TransferSingle::decode_log(&ZEventLog::from_topics(&topics, &data)).unwrap();
+        assert_eq!(event.operator.as_bytes()[19], 9);
+        assert_eq!(event.id.to_u64(), Some(5));
+        assert_eq!(event.value.to_u64(), Some(40));
+    }
+
+    #[test]
+    fn test_erc1155_transfer_batch() {
+        let topics = [TransferBatch::TOPIC0, word(9), word(1), word(2)];
+        // ids = [3, 4], values = [30, 40]
+        let data = [
+            word(64),
+            word(160),
+            word(2),
+            word(3),
+            word(4),
+            word(2),
+            word(30),
+            word(40),
+        ]
+        .concat();
+        let event = TransferBatch::decode_log(&ZEventLog::from_topics(&topics, &data)).unwrap();
+        assert_eq!(event.ids.len(), 2);
+        assert_eq!(event.ids.get(1).unwrap().to_u64(), Some(4));
+        assert_eq!(event.values.get(0).unwrap().to_u64(), Some(30));
+        assert_eq!(event.values.get(1).unwrap().to_u64(), Some(40));
+        assert!(event.values.get(2).is_err());
+    }
+}
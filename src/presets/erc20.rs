@@ -0,0 +1,132 @@
+//! ERC-20 token events and state-changing calls.
+//!
+//! [`Erc20Call`] classifies transaction calldata as one of the calls, e.g.
+//! to display a pending transaction.
+//!
+//! ```
+//! use zabi_rs::presets::erc20::Transfer;
+//! use zabi_rs::ZEventLog;
+//!
+//! let mut from = [0u8; 32];
+//! from[31] = 1;
+//! let mut to = [0u8; 32];
+//! to[31] = 2;
+//! let mut value = [0u8; 32];
+//! value[31] = 100;
+//!
+//! let topics = [Transfer::TOPIC0, from, to];
+//! let log = ZEventLog::from_topics(&topics, &value);
+//! let transfer: Transfer = log.try_decode().unwrap();
+//! assert_eq!(transfer.value.to_u64(), Some(100));
+//! ```
+
+use crate::sol;
+use crate::ZError;
+
+sol! {
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Approval(address indexed owner, address indexed spender, uint256 value);
+
+    function transfer(address to, uint256 value) external returns (bool);
+    function transferFrom(address from, address to, uint256 value) external returns (bool);
+    function approve(address spender, uint256 value) external returns (bool);
+}
+
+/// A state-changing ERC-20 call.
+#[derive(Debug, Clone, Copy)]
+pub enum Erc20Call<'a> {
+    Transfer(transferCall<'a>),
+    TransferFrom(transferFromCall<'a>),
+    Approve(approveCall<'a>),
+}
+
+impl<'a> Erc20Call<'a> {
+    /// Decode calldata by dispatching on its selector.
+    pub fn decode(calldata: &'a [u8]) -> Result<Self, ZError> {
+        match *crate::decoder::read_selector(calldata)? {
+            transferCall::SELECTOR => {
+                transferCall::decode_calldata(calldata).map(Erc20Call::Transfer)
+            }
+            transferFromCall::SELECTOR => {
+                transferFromCall::decode_calldata(calldata).map(Erc20Call::TransferFrom)
+            }
+            approveCall::SELECTOR => approveCall::decode_calldata(calldata).map(Erc20Call::Approve),
+            selector => Err(ZError::UnknownSelector(selector)),
+        }
+    }
+
+    pub fn selector(&self) -> [u8; 4] {
+        match self {
+            Erc20Call::Transfer(_) => transferCall::SELECTOR,
+            Erc20Call::TransferFrom(_) => transferFromCall::SELECTOR,
+            Erc20Call::Approve(_) => approveCall::SELECTOR,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ZEventLog;
+
+    #[test]
+    fn test_erc20_topics() {
+        assert_eq!(Transfer::SIGNATURE, "Transfer(address,address,uint256)");
+        assert_eq!(Transfer::TOPIC0[..4], [0xdd, 0xf2, 0x52, 0xad]);
+        assert_eq!(Approval::SIGNATURE, "Approval(address,address,uint256)");
+        assert_eq!(Approval::TOPIC0[..4], [0x8c, 0x5b, 0xe1, 0xe5]);
+    }
+
+    #[test]
+    fn test_erc20_selectors() {
+        assert_eq!(transferCall::SELECTOR, [0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(transferFromCall::SELECTOR, [0x23, 0xb8, 0x72, 0xdd]);
+        assert_eq!(approveCall::SELECTOR, [0x09, 0x5e, 0xa7, 0xb3]);
+    }
+
+    #[test]
+    fn test_erc20_call() {
+        let mut calldata = [0u8; 100];
+        calldata[..4].copy_from_slice(&transferFromCall::SELECTOR);
+        calldata[35] = 0xAA;
+        calldata[67] = 0xBB;
+        calldata[99] = 7;
+        let Erc20Call::TransferFrom(call) = Erc20Call::decode(&calldata).unwrap() else {
+            panic!("expected transferFrom")
+        };
+        assert_eq!(call.from.as_bytes()[19], 0xAA);
+        assert_eq!(call.to.as_bytes()[19], 0xBB);
+        assert_eq!(call.value.to_u64(), Some(7));
+
+        calldata[..4].copy_from_slice(&approveCall::SELECTOR);
+        let call = Erc20Call::decode(&calldata[..68]).unwrap();
+        assert!(matches!(call, Erc20Call::Approve(ref a) if a.spender.as_bytes()[19] == 0xAA));
+        assert_eq!(call.selector(), approveCall::SELECTOR);
+
+        calldata[..4].copy_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+        assert!(matches!(
+            Erc20Call::decode(&calldata),
+            Err(ZError::UnknownSelector(_))
+        ));
+        assert!(Erc20Call::decode(&calldata[..2]).is_err());
+    }
+
+    #[test]
+    fn test_erc20_approval() {
+        let mut owner = [0u8; 32];
+        owner[31] = 0xAA;
+        let mut spender = [0u8; 32];
+        spender[31] = 0xBB;
+        let mut value = [0u8; 32];
+        value[30] = 1;
+
+        let topics = [Approval::TOPIC0, owner, spender];
+        let approval = Approval::decode_log(&ZEventLog::from_topics(&topics, &value)).unwrap();
+        assert_eq!(approval.owner.as_bytes()[19], 0xAA);
+        assert_eq!(approval.spender.as_bytes()[19], 0xBB);
+        assert_eq!(approval.value.to_u64(), Some(256));
+
+        let transfer_topics = [Transfer::TOPIC0, owner, spender];
+        assert!(Approval::decode_log(&ZEventLog::from_topics(&transfer_topics, &value)).is_err());
+    }
+}
@@ -0,0 +1,314 @@
+//! ERC-4337 account abstraction: `EntryPoint.handleOps` bundles.
+//!
+//! [`v06`] is EntryPoint v0.6 with the flat `UserOperation`; [`v07`] is
+//! EntryPoint v0.7 with `PackedUserOperation`, whose gas fields are packed
+//! in pairs into `bytes32` words. Either way each operation's `callData` is
+//! what the account executes; [`AccountCall`] decodes the common
+//! `execute`/`executeBatch` entry points, and the inner calls they carry can
+//! be decoded again, with [`AccountCall::decode`] or any other preset.
+
+use crate::sol;
+use crate::{ZBytes, ZError};
+
+sol! {
+    function execute(address dest, uint256 value, bytes func) external;
+}
+
+/// EntryPoint v0.6.
+pub mod v06 {
+    use crate::sol;
+
+    sol! {
+        struct UserOperation {
+            address sender;
+            uint256 nonce;
+            bytes initCode;
+            bytes callData;
+            uint256 callGasLimit;
+            uint256 verificationGasLimit;
+            uint256 preVerificationGas;
+            uint256 maxFeePerGas;
+            uint256 maxPriorityFeePerGas;
+            bytes paymasterAndData;
+            bytes signature;
+        }
+
+        function handleOps(UserOperation[] ops, address beneficiary) external;
+
+        function executeBatch(address[] dest, bytes[] func) external;
+    }
+}
+
+/// EntryPoint v0.7.
+pub mod v07 {
+    use crate::sol;
+
+    sol! {
+        struct PackedUserOperation {
+            address sender;
+            uint256 nonce;
+            bytes initCode;
+            bytes callData;
+            bytes32 accountGasLimits;
+            uint256 preVerificationGas;
+            bytes32 gasFees;
+            bytes paymasterAndData;
+            bytes signature;
+        }
+
+        function handleOps(PackedUserOperation[] ops, address beneficiary) external;
+
+        function executeBatch(address[] dest, uint256[] value, bytes[] func) external;
+    }
+
+    /// The high and low 128 bits of a packed word.
+    fn split(word: &[u8; 32]) -> (u128, u128) {
+        let high = u128::from_be_bytes(word[..16].try_into().unwrap());
+        let low = u128::from_be_bytes(word[16..].try_into().unwrap());
+        (high, low)
+    }
+
+    impl PackedUserOperation<'_> {
+        /// The high half of `accountGasLimits`.
+        pub fn verification_gas_limit(&self) -> u128 {
+            split(self.accountGasLimits.as_bytes()).0
+        }
+
+        /// The low half of `accountGasLimits`.
+        pub fn call_gas_limit(&self) -> u128 {
+            split(self.accountGasLimits.as_bytes()).1
+        }
+
+        /// The high half of `gasFees`.
+        pub fn max_priority_fee_per_gas(&self) -> u128 {
+            split(self.gasFees.as_bytes()).0
+        }
+
+        /// The low half of `gasFees`.
+        pub fn max_fee_per_gas(&self) -> u128 {
+            split(self.gasFees.as_bytes()).1
+        }
+    }
+}
+
+/// What a user operation's `callData` asks the account to do.
+#[derive(Debug, Clone, Copy)]
+pub enum AccountCall<'a> {
+    Execute(executeCall<'a>),
+    /// The v0.6 `SimpleAccount` batch.
+    ExecuteBatch(v06::executeBatchCall<'a>),
+    /// The v0.7 `SimpleAccount` batch, with a value per call.
+    ExecuteBatchWithValue(v07::executeBatchCall<'a>),
+    /// Any other selector, or empty calldata; the raw calldata.
+    Other(&'a [u8]),
+}
+
+impl<'a> AccountCall<'a> {
+    /// Classify account calldata by its selector. Unrecognized calldata is
+    /// returned as [`AccountCall::Other`]; a known selector with malformed
+    /// arguments is an error.
+    pub fn decode(calldata: &'a [u8]) -> Result<Self, ZError> {
+        let selector = match calldata.get(..4) {
+            Some(selector) => selector,
+            None => return Ok(AccountCall::Other(calldata)),
+        };
+        if selector == executeCall::SELECTOR {
+            executeCall::decode_calldata(calldata).map(AccountCall::Execute)
+        } else if selector == v06::executeBatchCall::SELECTOR {
+            v06::executeBatchCall::decode_calldata(calldata).map(AccountCall::ExecuteBatch)
+        } else if selector == v07::executeBatchCall::SELECTOR {
+            v07::executeBatchCall::decode_calldata(calldata).map(AccountCall::ExecuteBatchWithValue)
+        } else {
+            Ok(AccountCall::Other(calldata))
+        }
+    }
+}
+
+impl<'a> v06::UserOperation<'a> {
+    /// Decode `callData` as an account call.
+    pub fn account_call(&self) -> Result<AccountCall<'a>, ZError> {
+        AccountCall::decode(self.callData.0)
+    }
+}
+
+impl<'a> v07::PackedUserOperation<'a> {
+    /// Decode `callData` as an account call.
+    pub fn account_call(&self) -> Result<AccountCall<'a>, ZError> {
+        AccountCall::decode(self.callData.0)
+    }
+}
+
+impl<'a> executeCall<'a> {
+    /// Decode the inner call, which may itself be an account call.
+    pub fn inner(&self) -> Result<AccountCall<'a>, ZError> {
+        AccountCall::decode(self.func.0)
+    }
+}
+
+/// The inner calldata of a batch, in order.
+fn batch_calls<'a>(
+    func: crate::ZArray<'a, ZBytes<'a>>,
+) -> impl Iterator<Item = Result<ZBytes<'a>, ZError>> + 'a {
+    (0..func.len()).map(move |i| func.get(i))
+}
+
+impl<'a> v06::executeBatchCall<'a> {
+    /// The calldata of each call in the batch.
+    pub fn calls(&self) -> impl Iterator<Item = Result<ZBytes<'a>, ZError>> + 'a {
+        batch_calls(self.func)
+    }
+}
+
+impl<'a> v07::executeBatchCall<'a> {
+    /// The calldata of each call in the batch.
+    pub fn calls(&self) -> impl Iterator<Item = Result<ZBytes<'a>, ZError>> + 'a {
+        batch_calls(self.func)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::dynamic::{encode_dyn_params, DynType, DynValue};
+    use crate::presets::erc20::transferCall;
+    use crate::test_util::word;
+    use crate::{ZAddress, ZU256};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn calldata(selector: [u8; 4], types: &str, values: Vec<DynValue<'_>>) -> Vec<u8> {
+        let ty = DynType::parse(types).unwrap();
+        let mut out = selector.to_vec();
+        encode_dyn_params(&ty, &DynValue::Tuple(values), &mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_handle_ops_v06() {
+        assert_eq!(v06::handleOpsCall::SELECTOR, [0x1f, 0xad, 0x94, 0x8c]);
+        assert_eq!(executeCall::SELECTOR, [0xb6, 0x1d, 0x27, 0xf6]);
+        assert_eq!(v06::executeBatchCall::SELECTOR, [0x18, 0xdf, 0xb3, 0xc7]);
+
+        let (token, to, amount, gas) = ([0x22; 20], [0x33; 20], word(100), word(7));
+        let transfer = calldata(
+            transferCall::SELECTOR,
+            "(address,uint256)",
+            vec![
+                DynValue::Address(ZAddress(&to)),
+                DynValue::Uint(ZU256(&amount)),
+            ],
+        );
+        let zero = [0u8; 32];
+        let execute = calldata(
+            executeCall::SELECTOR,
+            "(address,uint256,bytes)",
+            vec![
+                DynValue::Address(ZAddress(&token)),
+                DynValue::Uint(ZU256(&zero)),
+                DynValue::Bytes(ZBytes(&transfer)),
+            ],
+        );
+        let sender = [0x11; 20];
+        let op = DynValue::Tuple(vec![
+            DynValue::Address(ZAddress(&sender)),
+            DynValue::Uint(ZU256(&zero)),
+            DynValue::Bytes(ZBytes(&[])),
+            DynValue::Bytes(ZBytes(&execute)),
+            DynValue::Uint(ZU256(&gas)),
+            DynValue::Uint(ZU256(&gas)),
+            DynValue::Uint(ZU256(&gas)),
+            DynValue::Uint(ZU256(&gas)),
+            DynValue::Uint(ZU256(&gas)),
+            DynValue::Bytes(ZBytes(&[])),
+            DynValue::Bytes(ZBytes(&[0xee; 65])),
+        ]);
+        let beneficiary = [0x44; 20];
+        let bundle = calldata(
+            v06::handleOpsCall::SELECTOR,
+            "((address,uint256,bytes,bytes,uint256,uint256,uint256,uint256,uint256,bytes,bytes)[],address)",
+            vec![DynValue::Array(vec![op.clone(), op]), DynValue::Address(ZAddress(&beneficiary))],
+        );
+
+        let call = v06::handleOpsCall::decode_calldata(&bundle).unwrap();
+        assert_eq!(call.ops.len(), 2);
+        assert_eq!(call.beneficiary.as_bytes(), &beneficiary);
+        let op = call.ops.get(1).unwrap();
+        assert_eq!(op.sender.as_bytes(), &sender);
+        assert_eq!(op.signature.len(), 65);
+
+        let AccountCall::Execute(exec) = op.account_call().unwrap() else {
+            panic!("expected execute")
+        };
+        assert_eq!(exec.dest.as_bytes(), &token);
+        let AccountCall::Other(inner) = exec.inner().unwrap() else {
+            panic!("expected a token call")
+        };
+        let transfer = transferCall::decode_calldata(inner).unwrap();
+        assert_eq!(transfer.to.as_bytes(), &to);
+        assert_eq!(transfer.value.to_u64(), Some(100));
+    }
+
+    #[test]
+    fn test_packed_user_operation() {
+        assert_eq!(v07::handleOpsCall::SELECTOR, [0x76, 0x5e, 0x82, 0x7f]);
+        assert_eq!(v07::executeBatchCall::SELECTOR, [0x47, 0xe1, 0xda, 0x2a]);
+
+        let dest = [0x22; 20];
+        let one = word(1);
+        let batch = calldata(
+            v07::executeBatchCall::SELECTOR,
+            "(address[],uint256[],bytes[])",
+            vec![
+                DynValue::Array(vec![DynValue::Address(ZAddress(&dest)); 2]),
+                DynValue::Array(vec![DynValue::Uint(ZU256(&one)); 2]),
+                DynValue::Array(vec![
+                    DynValue::Bytes(ZBytes(&[0xab])),
+                    DynValue::Bytes(ZBytes(&[0xcd, 0xef])),
+                ]),
+            ],
+        );
+        let mut gas_limits = [0u8; 32];
+        gas_limits[15] = 5;
+        gas_limits[31] = 9;
+        let mut fees = [0u8; 32];
+        fees[15] = 2;
+        fees[31] = 3;
+        let sender = [0x11; 20];
+        let op = DynValue::Tuple(vec![
+            DynValue::Address(ZAddress(&sender)),
+            DynValue::Uint(ZU256(&one)),
+            DynValue::Bytes(ZBytes(&[])),
+            DynValue::Bytes(ZBytes(&batch)),
+            DynValue::FixedBytes(&gas_limits),
+            DynValue::Uint(ZU256(&one)),
+            DynValue::FixedBytes(&fees),
+            DynValue::Bytes(ZBytes(&[])),
+            DynValue::Bytes(ZBytes(&[])),
+        ]);
+        let bundle = calldata(
+            v07::handleOpsCall::SELECTOR,
+            "((address,uint256,bytes,bytes,bytes32,uint256,bytes32,bytes,bytes)[],address)",
+            vec![
+                DynValue::Array(vec![op]),
+                DynValue::Address(ZAddress(&sender)),
+            ],
+        );
+
+        let call = v07::handleOpsCall::decode_calldata(&bundle).unwrap();
+        let op = call.ops.get(0).unwrap();
+        assert_eq!(op.verification_gas_limit(), 5);
+        assert_eq!(op.call_gas_limit(), 9);
+        assert_eq!(op.max_priority_fee_per_gas(), 2);
+        assert_eq!(op.max_fee_per_gas(), 3);
+
+        let AccountCall::ExecuteBatchWithValue(batch) = op.account_call().unwrap() else {
+            panic!("expected a batch")
+        };
+        let calls: Vec<_> = batch.calls().map(|c| c.unwrap().0).collect();
+        assert_eq!(calls, [&[0xab][..], &[0xcd, 0xef][..]]);
+        assert!(matches!(
+            AccountCall::decode(&[]),
+            Ok(AccountCall::Other(&[]))
+        ));
+    }
+}
@@ -0,0 +1,48 @@
+//! ERC-721 non-fungible token events and transfer call.
+//!
+//! `Transfer` and `Approval` share their signatures (and `TOPIC0`) with
+//! ERC-20, but index the token id, so the logs carry four topics and no data.
+
+use crate::sol;
+
+sol! {
+    event Transfer(address indexed from, address indexed to, uint256 indexed tokenId);
+    event Approval(address indexed owner, address indexed approved, uint256 indexed tokenId);
+    event ApprovalForAll(address indexed owner, address indexed operator, bool approved);
+
+    function transferFrom(address from, address to, uint256 tokenId) external payable;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::word;
+    use crate::ZEventLog;
+
+    #[test]
+    fn test_erc721_transfer() {
+        assert_eq!(Transfer::TOPIC0, crate::presets::erc20::Transfer::TOPIC0);
+        let topics = [Transfer::TOPIC0, word(1), word(2), word(7)];
+        let transfer = Transfer::decode_log(&ZEventLog::from_topics(&topics, &[])).unwrap();
+        assert_eq!(transfer.from.as_bytes()[19], 1);
+        assert_eq!(transfer.to.as_bytes()[19], 2);
+        assert_eq!(transfer.tokenId.to_u64(), Some(7));
+
+        // An ERC-20 transfer has no topic for the token id.
+        assert!(Transfer::decode_log(&ZEventLog::from_topics(&topics[..3], &[])).is_err());
+    }
+
+    #[test]
+    fn test_erc721_approval_for_all() {
+        assert_eq!(
+            ApprovalForAll::SIGNATURE,
+            "ApprovalForAll(address,address,bool)"
+        );
+        assert_eq!(ApprovalForAll::TOPIC0[..4], [0x17, 0x30, 0x7e, 0xab]);
+        let topics = [ApprovalForAll::TOPIC0, word(1), word(2)];
+        let data = word(1);
+        let event = ApprovalForAll::decode_log(&ZEventLog::from_topics(&topics, &data)).unwrap();
+        assert_eq!(event.operator.as_bytes()[19], 2);
+        assert!(event.approved.0);
+    }
+}
@@ -0,0 +1,17 @@
+//! Ready-made decoders for widely deployed standard contracts.
+//!
+//! Each module is a `sol!` expansion, so the types carry the same
+//! `SIGNATURE`/`TOPIC0` constants and [`ZEventDecode`](crate::ZEventDecode)
+//! impls as user declarations.
+
+pub mod eip1967;
+pub mod eip2612;
+pub mod ens;
+pub mod erc1155;
+pub mod erc20;
+pub mod erc4337;
+pub mod erc721;
+pub mod multicall3;
+pub mod permit2;
+pub mod safe;
+pub mod weth;
@@ -0,0 +1,172 @@
+//! Multicall3 `aggregate3` and `aggregate3Value` batches.
+//!
+//! Each call's `callData` is a [`ZBytes`](crate::ZBytes) view into the
+//! outer calldata. Decode it with the preset for the target contract, or
+//! with a [`SelectorRegistry`](crate::SelectorRegistry) via `decode_with`,
+//! which also works for calls that are themselves multicalls.
+//!
+//! Multicall3's `Result` struct is named [`CallResult`] here so it does not
+//! shadow `core::result::Result`; the ABI is the same.
+
+use crate::sol;
+
+sol! {
+    struct Call3 {
+        address target;
+        bool allowFailure;
+        bytes callData;
+    }
+
+    struct Call3Value {
+        address target;
+        bool allowFailure;
+        uint256 value;
+        bytes callData;
+    }
+
+    struct CallResult {
+        bool success;
+        bytes returnData;
+    }
+
+    function aggregate3(Call3[] calls) external payable returns (CallResult[] returnData);
+    function aggregate3Value(Call3Value[] calls) external payable returns (CallResult[] returnData);
+}
+
+#[cfg(feature = "alloc")]
+mod nested {
+    use super::{Call3, Call3Value};
+    use crate::registry::{DecodedCall, SelectorRegistry};
+    use crate::ZError;
+
+    impl<'a> Call3<'a> {
+        /// Decode `callData` with the functions known to `registry`.
+        pub fn decode_with<'r>(
+            &self,
+            registry: &'r SelectorRegistry,
+        ) -> Result<DecodedCall<'r, 'a>, ZError> {
+            registry.decode(self.callData.0)
+        }
+    }
+
+    impl<'a> Call3Value<'a> {
+        /// Decode `callData` with the functions known to `registry`.
+        pub fn decode_with<'r>(
+            &self,
+            registry: &'r SelectorRegistry,
+        ) -> Result<DecodedCall<'r, 'a>, ZError> {
+            registry.decode(self.callData.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presets::erc20::{approveCall, transferCall};
+    use crate::test_util::{uint_word, word};
+    use alloc::vec::Vec;
+
+    /// The `bytes` encoding of `transfer(0x..02, 5)`: a length word and
+    /// 68 bytes padded to 96.
+    fn nested_transfer() -> [u8; 128] {
+        let mut out = [0u8; 128];
+        out[..32].copy_from_slice(&uint_word(68));
+        out[32..36].copy_from_slice(&transferCall::SELECTOR);
+        out[67] = 2;
+        out[99] = 5;
+        out
+    }
+
+    #[test]
+    fn test_aggregate3() {
+        assert_eq!(aggregate3Call::SELECTOR, [0x82, 0xad, 0x56, 0xcb]);
+        assert_eq!(aggregate3ValueCall::SELECTOR, [0x17, 0x4d, 0xea, 0x71]);
+        assert_eq!(
+            aggregate3Call::SIGNATURE,
+            "aggregate3((address,bool,bytes)[])"
+        );
+
+        // One Call3 { target: 0x..aa, allowFailure: true, callData: transfer(...) }.
+        let mut calldata = aggregate3Call::SELECTOR.to_vec();
+        for w in [
+            uint_word(32),
+            word(1),
+            uint_word(32),
+            word(0xaa),
+            word(1),
+            uint_word(96),
+        ] {
+            calldata.extend_from_slice(&w);
+        }
+        calldata.extend_from_slice(&nested_transfer());
+
+        let call = aggregate3Call::decode_calldata(&calldata).unwrap();
+        assert_eq!(call.calls.len(), 1);
+        let inner = call.calls.get(0).unwrap();
+        assert_eq!(inner.target.as_bytes()[19], 0xaa);
+        assert!(inner.allowFailure.as_bool());
+        let transfer = transferCall::decode_calldata(inner.callData.0).unwrap();
+        assert_eq!(transfer.to.as_bytes()[19], 2);
+        assert_eq!(transfer.value.to_u64(), Some(5));
+        assert!(approveCall::decode_calldata(inner.callData.0).is_err());
+    }
+
+    #[test]
+    fn test_aggregate3_returns() {
+        // [CallResult { success: false, returnData: 0x01 }]
+        let mut data = Vec::new();
+        for w in [
+            uint_word(32),
+            word(1),
+            uint_word(32),
+            word(0),
+            uint_word(64),
+            word(1),
+            [0u8; 32],
+        ] {
+            data.extend_from_slice(&w);
+        }
+        data[32 * 6] = 1;
+        let ret = aggregate3Return::decode_returns(&data).unwrap();
+        let result = ret.returnData.get(0).unwrap();
+        assert!(!result.success.as_bool());
+        assert_eq!(result.returnData.0, &[1]);
+    }
+
+    #[cfg(all(feature = "alloc", feature = "keccak"))]
+    #[test]
+    fn test_decode_with_registry() {
+        use crate::registry::SelectorRegistry;
+
+        let mut calldata = aggregate3ValueCall::SELECTOR.to_vec();
+        for w in [
+            uint_word(32),
+            word(1),
+            uint_word(32),
+            word(0xaa),
+            word(0),
+            word(7),
+            uint_word(128),
+        ] {
+            calldata.extend_from_slice(&w);
+        }
+        calldata.extend_from_slice(&nested_transfer());
+        let call = aggregate3ValueCall::decode_calldata(&calldata).unwrap();
+        let inner = call.calls.get(0).unwrap();
+        assert_eq!(inner.value.to_u64(), Some(7));
+
+        let mut registry = SelectorRegistry::new();
+        registry.register("transfer(address,uint256)").unwrap();
+        let decoded = inner.decode_with(&registry).unwrap();
+        assert_eq!(decoded.function.name, "transfer");
+        assert_eq!(
+            decoded
+                .args
+                .get(1)
+                .and_then(|v| v.as_uint())
+                .and_then(|v| v.to_u64()),
+            Some(5)
+        );
+    }
+}
@@ -0,0 +1,320 @@
+//! Uniswap Permit2 signature transfers: `permitTransferFrom` for one token
+//! and its batch overload, `permitTransferFrom_1`.
+//!
+//! The permit structs are views into the calldata. With the `keccak`
+//! feature they compute the EIP-712 struct hash and digest the owner
+//! signed. The `spender` in the signed message is not in the calldata: it is
+//! whoever calls Permit2, so it is passed in.
+
+#[cfg(feature = "keccak")]
+use crate::hash::{keccak256, Keccak256};
+use crate::owned::Address;
+use crate::sol;
+#[cfg(feature = "keccak")]
+use crate::ZError;
+
+sol! {
+    struct TokenPermissions {
+        address token;
+        uint256 amount;
+    }
+
+    struct PermitTransferFrom {
+        TokenPermissions permitted;
+        uint256 nonce;
+        uint256 deadline;
+    }
+
+    struct PermitBatchTransferFrom {
+        TokenPermissions[] permitted;
+        uint256 nonce;
+        uint256 deadline;
+    }
+
+    struct SignatureTransferDetails {
+        address to;
+        uint256 requestedAmount;
+    }
+
+    function permitTransferFrom(PermitTransferFrom permit, SignatureTransferDetails transferDetails, address owner, bytes signature) external;
+    function permitTransferFrom(PermitBatchTransferFrom permit, SignatureTransferDetails[] transferDetails, address owner, bytes signature) external;
+}
+
+/// The canonical Permit2 deployment, at the same address on every chain.
+pub const PERMIT2_ADDRESS: Address = Address([
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x22, 0xd4, 0x73, 0x03, 0x0f, 0x11, 0x6d, 0xde, 0xe9, 0xf6, 0xb4,
+    0x3a, 0xc7, 0x8b, 0xa3,
+]);
+
+/// `keccak256("TokenPermissions(address token,uint256 amount)")`.
+pub const TOKEN_PERMISSIONS_TYPEHASH: [u8; 32] = [
+    0x61, 0x83, 0x58, 0xac, 0x3d, 0xb8, 0xdc, 0x27, 0x4f, 0x0c, 0xd8, 0x82, 0x9d, 0xa7, 0xe2, 0x34,
+    0xbd, 0x48, 0xcd, 0x73, 0xc4, 0xa7, 0x40, 0xae, 0xde, 0x1a, 0xde, 0xc9, 0x84, 0x6d, 0x06, 0xa1,
+];
+
+/// `keccak256("PermitTransferFrom(TokenPermissions permitted,address spender,uint256 nonce,uint256 deadline)TokenPermissions(address token,uint256 amount)")`.
+pub const PERMIT_TRANSFER_FROM_TYPEHASH: [u8; 32] = [
+    0x93, 0x9c, 0x21, 0xa4, 0x8a, 0x8d, 0xbe, 0x3a, 0x9a, 0x24, 0x04, 0xa1, 0xd4, 0x66, 0x91, 0xe4,
+    0xd3, 0x9f, 0x65, 0x83, 0xd6, 0xec, 0x6b, 0x35, 0x71, 0x46, 0x04, 0xc9, 0x86, 0xd8, 0x01, 0x06,
+];
+
+/// `keccak256("PermitBatchTransferFrom(TokenPermissions[] permitted,address spender,uint256 nonce,uint256 deadline)TokenPermissions(address token,uint256 amount)")`.
+pub const PERMIT_BATCH_TRANSFER_FROM_TYPEHASH: [u8; 32] = [
+    0xfc, 0xf3, 0x5f, 0x5a, 0xc6, 0xa2, 0xc2, 0x88, 0x68, 0xdc, 0x44, 0xc3, 0x02, 0x16, 0x64, 0x70,
+    0x26, 0x62, 0x39, 0x19, 0x5f, 0x02, 0xb0, 0xee, 0x40, 0x83, 0x34, 0x82, 0x93, 0x33, 0xb7, 0x66,
+];
+
+/// `keccak256("EIP712Domain(string name,uint256 chainId,address verifyingContract)")`.
+pub const PERMIT2_DOMAIN_TYPEHASH: [u8; 32] = [
+    0x8c, 0xad, 0x95, 0x68, 0x7b, 0xa8, 0x2c, 0x2c, 0xe5, 0x0e, 0x74, 0xf7, 0xb7, 0x54, 0x64, 0x5e,
+    0x51, 0x17, 0xc3, 0xa5, 0xbe, 0xc8, 0x15, 0x1c, 0x07, 0x26, 0xd5, 0x85, 0x79, 0x80, 0xa8, 0x66,
+];
+
+/// Permit2's domain separator on `chain_id`, for the canonical deployment
+/// at [`PERMIT2_ADDRESS`]. Permit2's domain has no version field.
+#[cfg(feature = "keccak")]
+pub fn domain_separator(chain_id: u64) -> [u8; 32] {
+    let mut buf = [0u8; 128];
+    buf[..32].copy_from_slice(&PERMIT2_DOMAIN_TYPEHASH);
+    buf[32..64].copy_from_slice(&keccak256(b"Permit2"));
+    buf[88..96].copy_from_slice(&chain_id.to_be_bytes());
+    buf[108..].copy_from_slice(PERMIT2_ADDRESS.as_bytes());
+    keccak256(&buf)
+}
+
+/// `keccak256(0x1901 ‖ domainSeparator ‖ structHash)`.
+#[cfg(feature = "keccak")]
+fn typed_digest(domain_separator: &[u8; 32], struct_hash: &[u8; 32]) -> [u8; 32] {
+    let mut buf = [0u8; 66];
+    buf[..2].copy_from_slice(&[0x19, 0x01]);
+    buf[2..34].copy_from_slice(domain_separator);
+    buf[34..].copy_from_slice(struct_hash);
+    keccak256(&buf)
+}
+
+/// Hashes the typehash, the hash of the permitted tokens, then the spender,
+/// nonce and deadline.
+#[cfg(feature = "keccak")]
+fn permit_hash(
+    typehash: &[u8; 32],
+    permitted: &[u8; 32],
+    spender: &[u8; 20],
+    nonce: &[u8; 32],
+    deadline: &[u8; 32],
+) -> [u8; 32] {
+    let mut buf = [0u8; 160];
+    buf[..32].copy_from_slice(typehash);
+    buf[32..64].copy_from_slice(permitted);
+    buf[76..96].copy_from_slice(spender);
+    buf[96..128].copy_from_slice(nonce);
+    buf[128..].copy_from_slice(deadline);
+    keccak256(&buf)
+}
+
+#[cfg(feature = "keccak")]
+impl TokenPermissions<'_> {
+    /// `hashStruct(permissions)`.
+    pub fn struct_hash(&self) -> [u8; 32] {
+        let mut buf = [0u8; 96];
+        buf[..32].copy_from_slice(&TOKEN_PERMISSIONS_TYPEHASH);
+        buf[44..64].copy_from_slice(self.token.as_bytes());
+        buf[64..].copy_from_slice(self.amount.0);
+        keccak256(&buf)
+    }
+}
+
+#[cfg(feature = "keccak")]
+impl PermitTransferFrom<'_> {
+    /// `hashStruct(permit)` as signed for `spender`.
+    pub fn struct_hash(&self, spender: &Address) -> [u8; 32] {
+        let permitted = self.permitted.struct_hash();
+        permit_hash(
+            &PERMIT_TRANSFER_FROM_TYPEHASH,
+            &permitted,
+            spender.as_bytes(),
+            self.nonce.0,
+            self.deadline.0,
+        )
+    }
+
+    /// The EIP-712 digest the owner signed.
+    pub fn digest(&self, spender: &Address, domain_separator: &[u8; 32]) -> [u8; 32] {
+        typed_digest(domain_separator, &self.struct_hash(spender))
+    }
+}
+
+#[cfg(feature = "keccak")]
+impl PermitBatchTransferFrom<'_> {
+    /// `hashStruct(permit)` as signed for `spender`. The permitted tokens
+    /// hash as the concatenation of their struct hashes.
+    pub fn struct_hash(&self, spender: &Address) -> Result<[u8; 32], ZError> {
+        let mut hasher = Keccak256::new();
+        for i in 0..self.permitted.len() {
+            hasher.update(&self.permitted.get(i)?.struct_hash());
+        }
+        let permitted = hasher.finalize();
+        Ok(permit_hash(
+            &PERMIT_BATCH_TRANSFER_FROM_TYPEHASH,
+            &permitted,
+            spender.as_bytes(),
+            self.nonce.0,
+            self.deadline.0,
+        ))
+    }
+
+    /// The EIP-712 digest the owner signed.
+    pub fn digest(
+        &self,
+        spender: &Address,
+        domain_separator: &[u8; 32],
+    ) -> Result<[u8; 32], ZError> {
+        Ok(typed_digest(domain_separator, &self.struct_hash(spender)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{calldata, uint_word, word};
+
+    #[test]
+    fn test_permit_transfer_from() {
+        assert_eq!(permitTransferFromCall::SELECTOR, [0x30, 0xf2, 0x8b, 0x7a]);
+        assert_eq!(permitTransferFrom_1Call::SELECTOR, [0xed, 0xd9, 0x44, 0x4b]);
+
+        // permit: ((0x..aa, 100), 1, 255), transferDetails: (0x..bb, 50),
+        // owner: 0x..cc, signature: 0x01.
+        let data = calldata(
+            permitTransferFromCall::SELECTOR,
+            &[
+                word(0xaa),
+                word(100),
+                word(1),
+                word(255),
+                word(0xbb),
+                word(50),
+                word(0xcc),
+                uint_word(0x100),
+                word(1),
+                [1; 32],
+            ],
+        );
+        let call = permitTransferFromCall::decode_calldata(&data).unwrap();
+        assert_eq!(call.permit.permitted.token.as_bytes()[19], 0xaa);
+        assert_eq!(call.permit.deadline.to_u64(), Some(255));
+        assert_eq!(call.transferDetails.requestedAmount.to_u64(), Some(50));
+        assert_eq!(call.owner.as_bytes()[19], 0xcc);
+        assert_eq!(call.signature.0, &[1]);
+    }
+
+    #[test]
+    fn test_permit_batch_transfer_from() {
+        // permit: ([(0x..aa, 100), (0x..ab, 200)], 1, 255),
+        // transferDetails: [(0x..bb, 50)], owner: 0x..cc, signature: empty.
+        let data = calldata(
+            permitTransferFrom_1Call::SELECTOR,
+            &[
+                uint_word(0x80),
+                uint_word(0x180),
+                word(0xcc),
+                uint_word(0x1e0),
+                word(0x60),
+                word(1),
+                word(255),
+                word(2),
+                word(0xaa),
+                word(100),
+                word(0xab),
+                word(200),
+                word(1),
+                word(0xbb),
+                word(50),
+                word(0),
+            ],
+        );
+        let call = permitTransferFrom_1Call::decode_calldata(&data).unwrap();
+        assert_eq!(call.permit.permitted.len(), 2);
+        let second = call.permit.permitted.get(1).unwrap();
+        assert_eq!(second.token.as_bytes()[19], 0xab);
+        assert_eq!(second.amount.to_u64(), Some(200));
+        assert_eq!(call.permit.nonce.to_u64(), Some(1));
+        assert_eq!(call.transferDetails.get(0).unwrap().to.as_bytes()[19], 0xbb);
+        assert_eq!(call.owner.as_bytes()[19], 0xcc);
+        assert!(call.signature.is_empty());
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn test_permit2_hashes() {
+        use crate::ZDecode;
+
+        assert_eq!(
+            TOKEN_PERMISSIONS_TYPEHASH,
+            keccak256(b"TokenPermissions(address token,uint256 amount)")
+        );
+        assert_eq!(
+            PERMIT_TRANSFER_FROM_TYPEHASH,
+            keccak256(b"PermitTransferFrom(TokenPermissions permitted,address spender,uint256 nonce,uint256 deadline)TokenPermissions(address token,uint256 amount)")
+        );
+        assert_eq!(
+            PERMIT_BATCH_TRANSFER_FROM_TYPEHASH,
+            keccak256(b"PermitBatchTransferFrom(TokenPermissions[] permitted,address spender,uint256 nonce,uint256 deadline)TokenPermissions(address token,uint256 amount)")
+        );
+        assert_eq!(
+            PERMIT2_DOMAIN_TYPEHASH,
+            keccak256(b"EIP712Domain(string name,uint256 chainId,address verifyingContract)")
+        );
+        assert_eq!(
+            PERMIT2_ADDRESS,
+            Address::from_hex("0x000000000022D473030F116dDEE9F6B43aC78BA3").unwrap()
+        );
+
+        // A batch of one hashes its tokens as keccak256(hashStruct(token)),
+        // unlike the single permit, which embeds hashStruct(token) directly.
+        let single = [word(0xaa), word(100), word(1), word(255)].concat();
+        let batch = [
+            word(0x60),
+            word(1),
+            word(255),
+            word(1),
+            word(0xaa),
+            word(100),
+        ]
+        .concat();
+        let single = PermitTransferFrom::decode(&single, 0).unwrap();
+        let batch = PermitBatchTransferFrom::decode_params(&batch).unwrap();
+        let spender = Address([0x11; 20]);
+        let token_hash = single.permitted.struct_hash();
+        assert_eq!(
+            single.struct_hash(&spender),
+            permit_hash(
+                &PERMIT_TRANSFER_FROM_TYPEHASH,
+                &token_hash,
+                &[0x11; 20],
+                &word(1),
+                &word(255)
+            )
+        );
+        assert_eq!(
+            batch.struct_hash(&spender).unwrap(),
+            permit_hash(
+                &PERMIT_BATCH_TRANSFER_FROM_TYPEHASH,
+                &keccak256(&token_hash),
+                &[0x11; 20],
+                &word(1),
+                &word(255)
+            )
+        );
+
+        let domain = domain_separator(1);
+        assert_ne!(domain, domain_separator(10));
+        assert_ne!(
+            single.digest(&spender, &domain),
+            single.digest(&Address([0x12; 20]), &domain)
+        );
+        assert_eq!(
+            batch.digest(&spender, &domain).unwrap(),
+            typed_digest(&domain, &batch.struct_hash(&spender).unwrap())
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! Safe (formerly Gnosis Safe) multisig `execTransaction`.
+//!
+//! The `signatures` argument is a packed blob rather than an ABI array: one
+//! 65-byte `{r, s, v}` entry per owner, sorted by owner, followed by the
+//! payloads of any contract (EIP-1271) signatures. [`SafeSignatures`] walks it
+//! and classifies each entry the way the Safe contract does.
+//!
+//! ```
+//! use zabi_rs::presets::safe::{SafeSignature, SafeSignatures};
+//!
+//! let mut blob = [0u8; 65];
+//! blob[31] = 0xaa; // r: the approving owner
+//! blob[64] = 1; // v = 1: pre-approved hash
+//! let mut signatures = SafeSignatures::new(&blob);
+//! let Some(Ok(SafeSignature::ApprovedHash { owner })) = signatures.next() else { panic!() };
+//! assert_eq!(owner.as_bytes()[19], 0xaa);
+//! assert!(signatures.next().is_none());
+//! ```
+
+use crate::sol;
+use crate::{ZAddress, ZError};
+
+sol! {
+    function execTransaction(address to, uint256 value, bytes data, uint8 operation, uint256 safeTxGas, uint256 baseGas, uint256 gasPrice, address gasToken, address refundReceiver, bytes signatures) external returns (bool);
+    function nonce() external returns (uint256);
+    function getThreshold() external returns (uint256);
+    function getOwners() external returns (address[]);
+}
+
+/// Length of one packed signature entry.
+pub const SIGNATURE_LEN: usize = 65;
+
+/// How the Safe executes the inner call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operation {
+    Call,
+    DelegateCall,
+}
+
+impl Operation {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Operation::Call),
+            1 => Some(Operation::DelegateCall),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> execTransactionCall<'a> {
+    /// The `operation` argument, or `None` for a value the Safe rejects.
+    pub fn operation_kind(&self) -> Option<Operation> {
+        Operation::from_u8(self.operation)
+    }
+
+    /// The entries of the `signatures` blob.
+    pub fn signature_entries(&self) -> SafeSignatures<'a> {
+        SafeSignatures::new(self.signatures.0)
+    }
+}
+
+/// One entry of a Safe signature blob, classified by its `v` byte.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SafeSignature<'a> {
+    /// `v == 0`: an EIP-1271 signature checked by the owner contract, with
+    /// the payload from the dynamic part of the blob.
+    Contract { owner: ZAddress<'a>, data: &'a [u8] },
+    /// `v == 1`: the owner approved the hash on chain with `approveHash`, or
+    /// is the transaction sender.
+    ApprovedHash { owner: ZAddress<'a> },
+    /// `v > 30`: ECDSA over the `eth_sign`-prefixed hash. `v` has already
+    /// been reduced by 4 to the usual 27/28.
+    EthSign {
+        r: &'a [u8; 32],
+        s: &'a [u8; 32],
+        v: u8,
+    },
+    /// Any other `v`: plain ECDSA over the Safe transaction hash.
+    Ecdsa {
+        r: &'a [u8; 32],
+        s: &'a [u8; 32],
+        v: u8,
+    },
+}
+
+/// Iterator over the entries of a packed Safe signature blob.
+///
+/// The blob does not record how many entries it holds, so iteration stops
+/// at the end of the blob or at the first contract signature payload,
+/// whichever comes first. Pass the Safe's threshold to `take` when the
+/// trailing bytes may be padding.
+#[derive(Clone, Debug)]
+pub struct SafeSignatures<'a> {
+    blob: &'a [u8],
+    pos: usize,
+    /// Start of the dynamic part, as far as seen so far.
+    end: usize,
+}
+
+impl<'a> SafeSignatures<'a> {
+    pub fn new(blob: &'a [u8]) -> Self {
+        SafeSignatures {
+            blob,
+            pos: 0,
+            end: blob.len(),
+        }
+    }
+
+    /// The start and bytes of a contract signature payload: a length word at
+    /// `offset` followed by the bytes.
+    fn contract_data(&self, at: usize, offset: &[u8; 32]) -> Result<(usize, &'a [u8]), ZError> {
+        let len = self.blob.len();
+        let out_of_range = |target| ZError::OffsetOutOfRange { at, target, len };
+        if offset[..24].iter().any(|&b| b != 0) {
+            return Err(out_of_range(usize::MAX));
+        }
+        let start = u64::from_be_bytes(offset[24..].try_into().unwrap()) as usize;
+        // The payload may not overlap the static entries.
+        if start < self.pos + SIGNATURE_LEN {
+            return Err(out_of_range(start));
+        }
+        let data_len =
+            crate::decoder::read_u64(self.blob, start).map_err(|_| out_of_range(start))? as usize;
+        let data = start
+            .checked_add(32)
+            .and_then(|from| self.blob.get(from..from.checked_add(data_len)?))
+            .ok_or(ZError::OutOfBounds(
+                start.saturating_add(32).saturating_add(data_len),
+                len,
+            ))?;
+        Ok((start, data))
+    }
+}
+
+impl<'a> Iterator for SafeSignatures<'a> {
+    type Item = Result<SafeSignature<'a>, ZError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let at = self.pos;
+        let entry = self
+            .blob
+            .get(at..at + SIGNATURE_LEN)
+            .filter(|_| at + SIGNATURE_LEN <= self.end)?;
+        let r: &'a [u8; 32] = entry[..32].try_into().unwrap();
+        let s: &'a [u8; 32] = entry[32..64].try_into().unwrap();
+        let v = entry[64];
+        // The Safe reads the owner from the low 20 bytes of `r`.
+        let owner = ZAddress(r[12..].try_into().unwrap());
+        let signature = match v {
+            0 => match self.contract_data(at, s) {
+                Ok((start, data)) => {
+                    // Payloads follow every static entry.
+                    self.end = self.end.min(start);
+                    SafeSignature::Contract { owner, data }
+                }
+                Err(e) => {
+                    self.pos = self.blob.len();
+                    return Some(Err(e));
+                }
+            },
+            1 => SafeSignature::ApprovedHash { owner },
+            31..=u8::MAX => SafeSignature::EthSign { r, s, v: v - 4 },
+            _ => SafeSignature::Ecdsa { r, s, v },
+        };
+        self.pos += SIGNATURE_LEN;
+        Some(Ok(signature))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::uint_word;
+
+    fn entry(r_last: u8, s: [u8; 32], v: u8) -> [u8; SIGNATURE_LEN] {
+        let mut out = [0u8; SIGNATURE_LEN];
+        out[31] = r_last;
+        out[32..64].copy_from_slice(&s);
+        out[64] = v;
+        out
+    }
+
+    #[test]
+    fn test_exec_transaction_selector() {
+        assert_eq!(execTransactionCall::SELECTOR, [0x6a, 0x76, 0x12, 0x02]);
+        assert_eq!(nonceCall::SELECTOR, [0xaf, 0xfe, 0xd0, 0xe0]);
+        assert_eq!(getThresholdCall::SELECTOR, [0xe7, 0x52, 0x35, 0xb8]);
+        assert_eq!(getOwnersCall::SELECTOR, [0xa0, 0xe6, 0x7e, 0x2b]);
+        assert_eq!(Operation::from_u8(1), Some(Operation::DelegateCall));
+        assert_eq!(Operation::from_u8(2), None);
+    }
+
+    #[test]
+    fn test_signature_blob() {
+        // ECDSA, eth_sign, approved hash, then a contract signature whose
+        // payload starts right after the four static entries.
+        let mut blob = [0u8; 4 * SIGNATURE_LEN + 32 + 3];
+        blob[..65].copy_from_slice(&entry(7, uint_word(8), 27));
+        blob[65..130].copy_from_slice(&entry(7, uint_word(8), 32));
+        blob[130..195].copy_from_slice(&entry(0xaa, [0u8; 32], 1));
+        blob[195..260].copy_from_slice(&entry(0xbb, uint_word(260), 0));
+        blob[260..292].copy_from_slice(&uint_word(3));
+        blob[292..].copy_from_slice(&[1, 2, 3]);
+
+        let mut entries = [None; 4];
+        let mut iter = SafeSignatures::new(&blob);
+        for slot in entries.iter_mut() {
+            *slot = iter.next().map(Result::unwrap);
+        }
+        assert!(iter.next().is_none());
+        let entries = entries.map(Option::unwrap);
+        assert!(matches!(entries[0], SafeSignature::Ecdsa { v: 27, .. }));
+        assert!(matches!(entries[1], SafeSignature::EthSign { v: 28, .. }));
+        assert!(
+            matches!(entries[2], SafeSignature::ApprovedHash { owner } if owner.as_bytes()[19] == 0xaa)
+        );
+        let SafeSignature::Contract { owner, data } = entries[3] else {
+            panic!("expected a contract signature")
+        };
+        assert_eq!(owner.as_bytes()[19], 0xbb);
+        assert_eq!(data, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_signature_blob_errors() {
+        // The payload offset points into the entry itself.
+        let blob = entry(0xbb, uint_word(0), 0);
+        let mut entries = SafeSignatures::new(&blob);
+        assert!(matches!(
+            entries.next(),
+            Some(Err(ZError::OffsetOutOfRange {
+                at: 0,
+                target: 0,
+                ..
+            }))
+        ));
+        assert!(entries.next().is_none());
+
+        // Trailing bytes shorter than an entry are ignored.
+        let mut blob = [0u8; SIGNATURE_LEN + 10];
+        blob[64] = 27;
+        assert_eq!(SafeSignatures::new(&blob).count(), 1);
+    }
+}
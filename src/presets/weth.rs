@@ -0,0 +1,44 @@
+//! WETH9 (wrapped native token) events and calls.
+
+use crate::sol;
+
+sol! {
+    event Deposit(address indexed dst, uint256 wad);
+    event Withdrawal(address indexed src, uint256 wad);
+
+    function deposit() external payable;
+    function withdraw(uint256 wad) external;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::word;
+    use crate::ZEventLog;
+
+    #[test]
+    fn test_weth_events() {
+        assert_eq!(Deposit::TOPIC0[..4], [0xe1, 0xff, 0xfc, 0xc4]);
+        assert_eq!(Withdrawal::TOPIC0[..4], [0x7f, 0xcf, 0x53, 0x2c]);
+
+        let topics = [Deposit::TOPIC0, word(0xAA)];
+        let data = word(5);
+        let deposit = Deposit::decode_log(&ZEventLog::from_topics(&topics, &data)).unwrap();
+        assert_eq!(deposit.dst.as_bytes()[19], 0xAA);
+        assert_eq!(deposit.wad.to_u64(), Some(5));
+        assert!(Withdrawal::decode_log(&ZEventLog::from_topics(&topics, &data)).is_err());
+    }
+
+    #[test]
+    fn test_weth_calls() {
+        assert_eq!(depositCall::SELECTOR, [0xd0, 0xe3, 0x0d, 0xb0]);
+        assert_eq!(withdrawCall::SELECTOR, [0x2e, 0x1a, 0x7d, 0x4d]);
+
+        assert!(depositCall::decode_calldata(&depositCall::SELECTOR).is_ok());
+        let mut calldata = withdrawCall::SELECTOR.to_vec();
+        calldata.extend_from_slice(&word(9));
+        let call = withdrawCall::decode_calldata(&calldata).unwrap();
+        assert_eq!(call.wad.to_u64(), Some(9));
+        assert!(withdrawCall::decode_calldata(&depositCall::SELECTOR).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+//! Lazy access to the logs of a transaction receipt.
+//!
+//! Light clients and indexers often hold a receipt's logs as one byte
+//! payload. [`ZReceiptLogs`] walks that payload in place and yields a
+//! [`ZEventLog`] per entry, with the emitting address attached.
+//!
+//! Each log is laid out back to back as:
+//!
+//! | bytes            | content                        |
+//! |------------------|--------------------------------|
+//! | 20               | emitting contract address      |
+//! | 1                | topic count `n` (at most 4)    |
+//! | `32 * n`         | topics                         |
+//! | 4                | data length `len`, big-endian  |
+//! | `len`            | non-indexed data               |
+//!
+//! This framing is specific to zabi-rs, not the RLP receipt encoding from
+//! the execution layer: re-encode logs into it first, e.g. from
+//! `eth_getTransactionReceipt` results or decoded RLP receipts.
+
+use core::convert::TryInto;
+
+use crate::error::ZError;
+use crate::event::ZEventLog;
+
+/// The concatenated logs of one receipt.
+#[derive(Clone, Copy, Debug)]
+pub struct ZReceiptLogs<'a> {
+    raw: &'a [u8],
+}
+
+impl<'a> ZReceiptLogs<'a> {
+    /// Wrap a raw logs payload. Nothing is validated until the logs are read.
+    #[inline]
+    pub fn new(raw: &'a [u8]) -> Self {
+        Self { raw }
+    }
+
+    /// Returns the raw payload.
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.raw
+    }
+
+    /// Iterate over the logs in order.
+    #[inline]
+    pub fn iter(&self) -> ReceiptLogsIter<'a> {
+        ReceiptLogsIter {
+            raw: self.raw,
+            offset: 0,
+        }
+    }
+}
+
+impl<'a> IntoIterator for ZReceiptLogs<'a> {
+    type Item = Result<ZEventLog<'a>, ZError>;
+    type IntoIter = ReceiptLogsIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &ZReceiptLogs<'a> {
+    type Item = Result<ZEventLog<'a>, ZError>;
+    type IntoIter = ReceiptLogsIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the logs of a [`ZReceiptLogs`].
+/// Stops after the first malformed entry.
+#[derive(Clone, Debug)]
+pub struct ReceiptLogsIter<'a> {
+    raw: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> ReceiptLogsIter<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ZError> {
+        let end = match self.offset.checked_add(len) {
+            Some(end) if end <= self.raw.len() => end,
+            _ => {
+                return Err(ZError::OutOfBounds(
+                    self.offset.saturating_add(len),
+                    self.raw.len(),
+                ))
+            }
+        };
+        let bytes = &self.raw[self.offset..end];
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn read_log(&mut self) -> Result<ZEventLog<'a>, ZError> {
+        let address: &'a [u8; 20] = self.take(20)?.try_into().unwrap();
+        let topic_count = self.take(1)?[0] as usize;
+        if topic_count > 4 {
+            // Expected at most 4 topics.
+            return Err(ZError::InvalidLength(4, topic_count));
+        }
+        let topics = self.take(topic_count * 32)?;
+        let data_len = u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as usize;
+        let data = self.take(data_len)?;
+        Ok(ZEventLog::from_raw(topics, data)?.with_address(address))
+    }
+}
+
+impl<'a> Iterator for ReceiptLogsIter<'a> {
+    type Item = Result<ZEventLog<'a>, ZError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.raw.len() {
+            return None;
+        }
+        let log = self.read_log();
+        if log.is_err() {
+            self.offset = self.raw.len();
+        }
+        Some(log)
+    }
+}
+
+impl core::iter::FusedIterator for ReceiptLogsIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn push_log(out: &mut Vec<u8>, address: u8, topics: &[[u8; 32]], data: &[u8]) {
+        out.extend_from_slice(&[address; 20]);
+        out.push(topics.len() as u8);
+        for topic in topics {
+            out.extend_from_slice(topic);
+        }
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        out.extend_from_slice(data);
+    }
+
+    #[test]
+    fn test_receipt_logs() {
+        let mut raw = Vec::new();
+        push_log(&mut raw, 0x11, &[[0xAB; 32], [0x01; 32]], &[0u8; 32]);
+        push_log(&mut raw, 0x22, &[], &[1, 2, 3]);
+
+        let receipt = ZReceiptLogs::new(&raw);
+        let logs: Vec<_> = receipt.iter().collect::<Result<_, _>>().unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].address().unwrap().as_bytes(), &[0x11; 20]);
+        assert_eq!(logs[0].topic_count(), 2);
+        assert_eq!(logs[0].raw_topic(1).unwrap(), &[0x01; 32]);
+        assert_eq!(logs[0].data().len(), 32);
+        assert_eq!(logs[1].address().unwrap().as_bytes(), &[0x22; 20]);
+        assert_eq!(logs[1].topic_count(), 0);
+        assert_eq!(logs[1].data(), &[1, 2, 3]);
+
+        assert_eq!(ZReceiptLogs::new(&[]).iter().count(), 0);
+    }
+
+    #[test]
+    fn test_receipt_logs_malformed() {
+        let mut raw = Vec::new();
+        push_log(&mut raw, 0x11, &[[0xAB; 32]], &[]);
+        push_log(&mut raw, 0x22, &[[0xCD; 32]], &[0u8; 8]);
+        raw.truncate(raw.len() - 1);
+
+        let mut iter = ZReceiptLogs::new(&raw).iter();
+        assert!(iter.next().unwrap().is_ok());
+        assert!(matches!(iter.next(), Some(Err(ZError::OutOfBounds(_, _)))));
+        assert!(iter.next().is_none());
+
+        let mut bad = Vec::new();
+        push_log(&mut bad, 0x11, &[[0u8; 32]; 5], &[]);
+        assert!(matches!(
+            ZReceiptLogs::new(&bad).iter().next(),
+            Some(Err(ZError::InvalidLength(4, 5)))
+        ));
+
+        // A data length of u32::MAX is rejected without overflowing.
+        let mut huge = Vec::new();
+        push_log(&mut huge, 0x11, &[], &[]);
+        let len = huge.len();
+        huge[len - 4..].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(matches!(
+            ZReceiptLogs::new(&huge).iter().next(),
+            Some(Err(ZError::OutOfBounds(..)))
+        ));
+    }
+}
@@ -0,0 +1,745 @@
+//! Runtime registries of known functions and events.
+//!
+//! [`SelectorRegistry`] maps selectors to function signatures collected at
+//! runtime, e.g. from a 4byte-directory dump or a set of ABI files, and
+//! decodes arbitrary calldata into a named call. [`EventRegistry`] does the
+//! same for logs, keyed by topic 0.
+//!
+//! Selectors are only 4 bytes and public dumps contain collisions, so several
+//! signatures may share one. Decoding tries them in registration order and
+//! returns the first whose parameters accept the calldata. Events can share
+//! a topic 0 too: ERC-20 and ERC-721 `Transfer` differ only in which
+//! parameters are indexed, and are told apart by the log's topic count.
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::decoder;
+use crate::dynamic::{
+    canonical_len, decode_dyn, decode_dyn_params, parse_signature, write_pretty, DynType, DynValue,
+};
+use crate::error::ZError;
+use crate::event::ZEventLog;
+
+/// A registered function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FunctionEntry {
+    pub name: String,
+    /// The parameter list, always a [`DynType::Tuple`].
+    pub params: DynType,
+}
+
+impl FunctionEntry {
+    /// Canonical signature, e.g. `"transfer(address,uint256)"`.
+    pub fn signature(&self) -> String {
+        format!("{}{}", self.name, self.params)
+    }
+}
+
+/// Calldata decoded against a [`SelectorRegistry`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedCall<'r, 'a> {
+    pub function: &'r FunctionEntry,
+    /// The arguments, as a [`DynValue::Tuple`].
+    pub args: DynValue<'a>,
+}
+
+/// Selector to function lookup for decoding calldata of unknown contracts.
+#[derive(Clone, Debug, Default)]
+pub struct SelectorRegistry {
+    functions: BTreeMap<[u8; 4], Vec<FunctionEntry>>,
+}
+
+impl SelectorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `signature` under a known `selector`, without hashing.
+    /// Registering the same signature twice has no effect.
+    pub fn insert(&mut self, selector: [u8; 4], signature: &str) -> Result<(), ZError> {
+        let (name, params) = parse_signature(signature)?;
+        self.push(
+            selector,
+            FunctionEntry {
+                name: String::from(name),
+                params,
+            },
+        );
+        Ok(())
+    }
+
+    /// Register `signature` under the selector computed from it.
+    #[cfg(feature = "keccak")]
+    pub fn register(&mut self, signature: &str) -> Result<[u8; 4], ZError> {
+        let (name, params) = parse_signature(signature)?;
+        let hash = crate::hash::keccak256(params.canonical_signature(name)?.as_bytes());
+        let selector = [hash[0], hash[1], hash[2], hash[3]];
+        self.insert(selector, signature)?;
+        Ok(selector)
+    }
+
+    /// Register every function of a contract ABI.
+    #[cfg(all(feature = "serde", feature = "keccak"))]
+    pub fn register_abi(&mut self, abi: &crate::abi::JsonAbi) {
+        for function in &abi.functions {
+            self.push(
+                function.selector(),
+                FunctionEntry {
+                    name: function.name.clone(),
+                    params: function.input_type(),
+                },
+            );
+        }
+    }
+
+    fn push(&mut self, selector: [u8; 4], entry: FunctionEntry) {
+        let entries = self.functions.entry(selector).or_default();
+        if !entries.contains(&entry) {
+            entries.push(entry);
+        }
+    }
+
+    /// Every function registered under `selector`, in registration order.
+    pub fn lookup(&self, selector: &[u8; 4]) -> &[FunctionEntry] {
+        self.functions.get(selector).map_or(&[], Vec::as_slice)
+    }
+
+    /// Number of registered selectors.
+    pub fn len(&self) -> usize {
+        self.functions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+
+    /// Decode calldata, selector included, against the registered functions.
+    ///
+    /// Fails with [`ZError::UnknownSelector`] if nothing is registered under
+    /// the selector, or with the last candidate's error if none decodes.
+    pub fn decode<'r, 'a>(&'r self, calldata: &'a [u8]) -> Result<DecodedCall<'r, 'a>, ZError> {
+        let selector = decoder::read_selector(calldata)?;
+        let mut last_error = ZError::UnknownSelector(*selector);
+        for function in self.lookup(selector) {
+            match decode_dyn_params(&function.params, &calldata[4..]) {
+                Ok(args) => return Ok(DecodedCall { function, args }),
+                Err(e) => last_error = e,
+            }
+        }
+        Err(last_error)
+    }
+}
+
+/// Something about calldata that decodes but would not come out of a
+/// standard encoder, or that makes the decoding uncertain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Warning<'r> {
+    /// Bytes left over after the canonical encoding of the arguments.
+    TrailingBytes(usize),
+    /// The argument section is not a whole number of words.
+    Misaligned,
+    /// Another function registered under the same selector also accepts
+    /// the calldata.
+    Ambiguous(&'r FunctionEntry),
+}
+
+impl fmt::Display for Warning<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::TrailingBytes(n) => {
+                write!(f, "{} trailing bytes after the encoded arguments", n)
+            }
+            Warning::Misaligned => f.write_str("argument data is not a multiple of 32 bytes"),
+            Warning::Ambiguous(other) => write!(f, "also decodes as {}", other.signature()),
+        }
+    }
+}
+
+/// Calldata decoded and annotated by [`SelectorRegistry::explain_calldata`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Explanation<'r, 'a> {
+    pub function: &'r FunctionEntry,
+    /// One value per parameter, in order.
+    pub args: Vec<DynValue<'a>>,
+    /// Empty for canonical, unambiguous calldata.
+    pub warnings: Vec<Warning<'r>>,
+}
+
+impl Explanation<'_, '_> {
+    /// The Solidity type of each argument, in order.
+    pub fn types(&self) -> &[DynType] {
+        match &self.function.params {
+            DynType::Tuple(types) => types,
+            _ => &[],
+        }
+    }
+
+    pub fn is_canonical(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+/// Renders one argument per line with its type, then the warnings:
+///
+/// ```text
+/// transfer(address,uint256)
+///   [0] address: 0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed
+///   [1] uint256: 100
+/// warning: 4 trailing bytes after the encoded arguments
+/// ```
+impl fmt::Display for Explanation<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.function.signature())?;
+        for (i, (ty, value)) in self.types().iter().zip(&self.args).enumerate() {
+            write!(f, "\n  [{}] {}: ", i, ty)?;
+            write_pretty(f, value, 1)?;
+        }
+        for warning in &self.warnings {
+            write!(f, "\nwarning: {}", warning)?;
+        }
+        Ok(())
+    }
+}
+
+impl SelectorRegistry {
+    /// Decode calldata like [`SelectorRegistry::decode`] and annotate it:
+    /// the argument types alongside their values, plus [`Warning`]s for
+    /// non-canonical encodings and for other registered functions that would
+    /// accept the same bytes.
+    pub fn explain_calldata<'r, 'a>(
+        &'r self,
+        calldata: &'a [u8],
+    ) -> Result<Explanation<'r, 'a>, ZError> {
+        let call = self.decode(calldata)?;
+        let args = match call.args {
+            DynValue::Tuple(args) => args,
+            _ => unreachable!(),
+        };
+        let body = &calldata[4..];
+
+        let mut warnings = Vec::new();
+        let used = match &call.function.params {
+            DynType::Tuple(types) => canonical_len(types.iter(), &args),
+            _ => unreachable!(),
+        };
+        if body.len() > used {
+            warnings.push(Warning::TrailingBytes(body.len() - used));
+        }
+        if !body.len().is_multiple_of(32) {
+            warnings.push(Warning::Misaligned);
+        }
+        for other in self.lookup(decoder::read_selector(calldata)?) {
+            if !core::ptr::eq(other, call.function)
+                && decode_dyn_params(&other.params, body).is_ok()
+            {
+                warnings.push(Warning::Ambiguous(other));
+            }
+        }
+        Ok(Explanation {
+            function: call.function,
+            args,
+            warnings,
+        })
+    }
+}
+
+/// A parameter of a registered event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventParam {
+    /// May be empty.
+    pub name: String,
+    pub ty: DynType,
+    pub indexed: bool,
+}
+
+/// A registered event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventEntry {
+    pub name: String,
+    pub params: Vec<EventParam>,
+}
+
+impl EventEntry {
+    /// Canonical signature, e.g. `"Transfer(address,address,uint256)"`.
+    pub fn signature(&self) -> String {
+        let types = DynType::Tuple(self.params.iter().map(|p| p.ty.clone()).collect());
+        format!("{}{}", self.name, types)
+    }
+
+    fn indexed_count(&self) -> usize {
+        self.params.iter().filter(|p| p.indexed).count()
+    }
+}
+
+/// A log decoded against an [`EventRegistry`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedEvent<'r, 'a> {
+    pub event: &'r EventEntry,
+    /// One value per parameter, in declaration order. Indexed strings, byte
+    /// strings, arrays and tuples are only present as their keccak256 hash,
+    /// given as a 32-byte [`DynValue::FixedBytes`].
+    pub fields: Vec<DynValue<'a>>,
+}
+
+/// Topic 0 to event lookup for decoding logs of unknown contracts.
+#[derive(Clone, Debug, Default)]
+pub struct EventRegistry {
+    events: BTreeMap<[u8; 32], Vec<EventEntry>>,
+}
+
+impl EventRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an event under a known `topic0`, without hashing.
+    ///
+    /// The signature marks indexed parameters and may name them, e.g.
+    /// `"Transfer(address indexed from, address indexed to, uint256 value)"`.
+    /// Registering the same event twice has no effect.
+    pub fn insert(&mut self, topic0: [u8; 32], signature: &str) -> Result<(), ZError> {
+        let entry = parse_event_signature(signature)?;
+        self.push(topic0, entry);
+        Ok(())
+    }
+
+    /// Register an event under the topic 0 computed from its signature.
+    #[cfg(feature = "keccak")]
+    pub fn register(&mut self, signature: &str) -> Result<[u8; 32], ZError> {
+        let entry = parse_event_signature(signature)?;
+        let topic0 = crate::hash::keccak256(entry.signature().as_bytes());
+        self.push(topic0, entry);
+        Ok(topic0)
+    }
+
+    /// Register every non-anonymous event of a contract ABI.
+    #[cfg(all(feature = "serde", feature = "keccak"))]
+    pub fn register_abi(&mut self, abi: &crate::abi::JsonAbi) {
+        for event in abi.events.iter().filter(|e| !e.anonymous) {
+            let params = event
+                .inputs
+                .iter()
+                .map(|p| EventParam {
+                    name: p.name.clone(),
+                    ty: p.ty.clone(),
+                    indexed: p.indexed,
+                })
+                .collect();
+            self.push(
+                event.topic0(),
+                EventEntry {
+                    name: event.name.clone(),
+                    params,
+                },
+            );
+        }
+    }
+
+    fn push(&mut self, topic0: [u8; 32], entry: EventEntry) {
+        let entries = self.events.entry(topic0).or_default();
+        if !entries.contains(&entry) {
+            entries.push(entry);
+        }
+    }
+
+    /// Every event registered under `topic0`, in registration order.
+    pub fn lookup(&self, topic0: &[u8; 32]) -> &[EventEntry] {
+        self.events.get(topic0).map_or(&[], Vec::as_slice)
+    }
+
+    /// Number of registered topics.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Decode a log against the events registered under its topic 0.
+    ///
+    /// Candidates whose indexed parameter count does not match the log's
+    /// topics are skipped. Fails with [`ZError::UnknownTopic`] if nothing
+    /// fits, or with the last candidate's error if none decodes.
+    pub fn decode_any_log<'r, 'a>(
+        &'r self,
+        log: &ZEventLog<'a>,
+    ) -> Result<DecodedEvent<'r, 'a>, ZError> {
+        let topic0 = log.event_signature()?;
+        let mut last_error = ZError::UnknownTopic(*topic0);
+        for event in self.lookup(topic0) {
+            if event.indexed_count() + 1 != log.topic_count() {
+                continue;
+            }
+            match decode_event(event, log) {
+                Ok(fields) => return Ok(DecodedEvent { event, fields }),
+                Err(e) => last_error = e,
+            }
+        }
+        Err(last_error)
+    }
+}
+
+fn decode_event<'a>(event: &EventEntry, log: &ZEventLog<'a>) -> Result<Vec<DynValue<'a>>, ZError> {
+    let data_type = DynType::Tuple(
+        event
+            .params
+            .iter()
+            .filter(|p| !p.indexed)
+            .map(|p| p.ty.clone())
+            .collect(),
+    );
+    let mut data = match decode_dyn_params(&data_type, log.data())? {
+        DynValue::Tuple(values) => values.into_iter(),
+        _ => unreachable!(),
+    };
+    let mut topic = 1;
+    let mut fields = Vec::with_capacity(event.params.len());
+    for (i, param) in event.params.iter().enumerate() {
+        let value = if param.indexed {
+            let word = log.raw_topic(topic)?;
+            topic += 1;
+            match param.ty {
+                DynType::Uint(_)
+                | DynType::Int(_)
+                | DynType::Address
+                | DynType::Bool
+                | DynType::FixedBytes(_) => {
+                    decode_dyn(&param.ty, word, 0).map_err(|e| e.in_field(i))?
+                }
+                _ => DynValue::FixedBytes(word),
+            }
+        } else {
+            data.next().expect("one decoded value per data parameter")
+        };
+        fields.push(value);
+    }
+    Ok(fields)
+}
+
+/// Parse `"Name(type [indexed] [name], ...)"`.
+fn parse_event_signature(signature: &str) -> Result<EventEntry, ZError> {
+    let invalid = || ZError::Message(format!("invalid event signature: {}", signature));
+    let open = signature.find('(').ok_or_else(invalid)?;
+    let name = signature[..open].trim();
+    let inner = signature[open..]
+        .trim_end()
+        .strip_prefix('(')
+        .and_then(|s| s.strip_suffix(')'))
+        .ok_or_else(invalid)?;
+    if name.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut params = Vec::new();
+    if inner.trim().is_empty() {
+        return Ok(EventEntry {
+            name: String::from(name),
+            params,
+        });
+    }
+    for part in split_top_level(inner) {
+        let part = part.trim();
+        // The type runs to the first space outside parentheses.
+        let mut depth = 0usize;
+        let end = part
+            .char_indices()
+            .find(|&(_, c)| {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                depth == 0 && c.is_ascii_whitespace()
+            })
+            .map_or(part.len(), |(i, _)| i);
+        let ty = DynType::parse(&part[..end])?;
+        let mut words = part[end..].split_ascii_whitespace();
+        let mut next = words.next();
+        let indexed = next == Some("indexed");
+        if indexed {
+            next = words.next();
+        }
+        if words.next().is_some() {
+            return Err(invalid());
+        }
+        params.push(EventParam {
+            name: String::from(next.unwrap_or("")),
+            ty,
+            indexed,
+        });
+    }
+    Ok(EventEntry {
+        name: String::from(name),
+        params,
+    })
+}
+
+/// Split on commas outside parentheses and brackets.
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    const TRANSFER: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
+    fn transfer_calldata() -> [u8; 68] {
+        let mut calldata = [0u8; 68];
+        calldata[..4].copy_from_slice(&TRANSFER);
+        calldata[16..36].copy_from_slice(&[0x33; 20]);
+        calldata[67] = 9;
+        calldata
+    }
+
+    #[test]
+    fn test_selector_registry() {
+        let mut registry = SelectorRegistry::new();
+        assert!(registry.is_empty());
+        // A colliding entry whose parameters reject the calldata comes first.
+        registry.insert(TRANSFER, "collision(bool)").unwrap();
+        registry
+            .insert(TRANSFER, "transfer(address, uint256)")
+            .unwrap();
+        registry
+            .insert(TRANSFER, "transfer(address,uint256)")
+            .unwrap();
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.lookup(&TRANSFER).len(), 2);
+
+        let calldata = transfer_calldata();
+        let call = registry.decode(&calldata).unwrap();
+        assert_eq!(call.function.signature(), "transfer(address,uint256)");
+        assert_eq!(
+            call.args
+                .get(1)
+                .and_then(DynValue::as_uint)
+                .and_then(|v| v.to_u64()),
+            Some(9)
+        );
+
+        let unknown = [0u8; 36];
+        assert!(matches!(
+            registry.decode(&unknown),
+            Err(ZError::UnknownSelector([0, 0, 0, 0]))
+        ));
+        assert!(registry.decode(&calldata[..3]).is_err());
+        assert!(registry.insert(TRANSFER, "transfer").is_err());
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn test_register_by_signature() {
+        let mut registry = SelectorRegistry::new();
+        assert_eq!(
+            registry.register("transfer(address,uint)").unwrap(),
+            TRANSFER
+        );
+        assert_eq!(
+            registry.register("approve(address,uint256)").unwrap(),
+            [0x09, 0x5e, 0xa7, 0xb3]
+        );
+        assert_eq!(registry.len(), 2);
+        let calldata = transfer_calldata();
+        assert_eq!(
+            registry.decode(&calldata).unwrap().function.name,
+            "transfer"
+        );
+    }
+
+    #[cfg(all(feature = "serde", feature = "keccak"))]
+    #[test]
+    fn test_register_abi() {
+        let abi: crate::abi::JsonAbi = serde_json::from_str(
+            r#"[{ "type": "function", "name": "transfer",
+                  "inputs": [{ "name": "to", "type": "address" }, { "name": "amount", "type": "uint256" }] }]"#,
+        )
+        .unwrap();
+        let mut registry = SelectorRegistry::new();
+        registry.register_abi(&abi);
+        registry.register_abi(&abi);
+        assert_eq!(registry.lookup(&TRANSFER).len(), 1);
+        let calldata = transfer_calldata();
+        assert_eq!(
+            registry.decode(&calldata).unwrap().function.name,
+            "transfer"
+        );
+    }
+
+    fn transfer_log_parts() -> ([[u8; 32]; 3], [u8; 32]) {
+        let mut topics = [[0u8; 32]; 3];
+        topics[0] = [0xdd; 32];
+        topics[1][12..].copy_from_slice(&[0xaa; 20]);
+        topics[2][12..].copy_from_slice(&[0xbb; 20]);
+        let mut data = [0u8; 32];
+        data[31] = 100;
+        (topics, data)
+    }
+
+    #[test]
+    fn test_event_registry() {
+        let mut registry = EventRegistry::new();
+        // ERC-721 shares topic 0 with ERC-20 but indexes all three parameters.
+        registry
+            .insert(
+                [0xdd; 32],
+                "Transfer(address indexed from, address indexed to, uint256 indexed id)",
+            )
+            .unwrap();
+        registry
+            .insert(
+                [0xdd; 32],
+                "Transfer(address indexed from, address indexed to, uint256 value)",
+            )
+            .unwrap();
+        registry
+            .insert(
+                [0xee; 32],
+                "Note(string indexed tag, (uint8, bool)[] items)",
+            )
+            .unwrap();
+        assert_eq!(registry.len(), 2);
+
+        let (topics, data) = transfer_log_parts();
+        let log = ZEventLog::from_topics(&topics, &data);
+        let decoded = registry.decode_any_log(&log).unwrap();
+        assert_eq!(
+            decoded.event.signature(),
+            "Transfer(address,address,uint256)"
+        );
+        assert_eq!(decoded.event.params[2].name, "value");
+        assert_eq!(
+            decoded.fields[1].as_address().unwrap().as_bytes(),
+            &[0xbb; 20]
+        );
+        assert_eq!(
+            decoded.fields[2].as_uint().and_then(|v| v.to_u64()),
+            Some(100)
+        );
+
+        let note = &registry.lookup(&[0xee; 32])[0];
+        assert_eq!(note.signature(), "Note(string,(uint8,bool)[])");
+        assert!(note.params[0].indexed);
+
+        // Indexed dynamic values come back as their hash.
+        let mut note_topics = [[0u8; 32]; 2];
+        note_topics[0] = [0xee; 32];
+        note_topics[1] = [0x42; 32];
+        let mut note_data = [0u8; 64];
+        note_data[31] = 32;
+        let note_log = ZEventLog::from_topics(&note_topics, &note_data);
+        let decoded = registry.decode_any_log(&note_log).unwrap();
+        assert_eq!(decoded.fields[0], DynValue::FixedBytes(&[0x42; 32]));
+        assert_eq!(decoded.fields[1], DynValue::Array(Vec::new()));
+
+        let unknown = ZEventLog::from_topics(&topics[1..], &data);
+        assert!(matches!(
+            registry.decode_any_log(&unknown),
+            Err(ZError::UnknownTopic(_))
+        ));
+        assert!(matches!(
+            registry.decode_any_log(&ZEventLog::from_topics(&topics[..2], &data)),
+            Err(ZError::UnknownTopic(topic)) if topic == topics[0]
+        ));
+        assert!(registry
+            .insert([0; 32], "Bad(uint256 indexed a b)")
+            .is_err());
+        assert!(registry.insert([0; 32], "Bad(uint256").is_err());
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn test_register_event() {
+        let mut registry = EventRegistry::new();
+        let topic0 = registry
+            .register("Transfer(address indexed from, address indexed to, uint value)")
+            .unwrap();
+        assert_eq!(topic0[..4], [0xdd, 0xf2, 0x52, 0xad]);
+
+        let (mut topics, data) = transfer_log_parts();
+        topics[0] = topic0;
+        let log = ZEventLog::from_topics(&topics, &data);
+        assert_eq!(
+            registry.decode_any_log(&log).unwrap().event.name,
+            "Transfer"
+        );
+    }
+
+    #[test]
+    fn test_explain_calldata() {
+        let mut registry = SelectorRegistry::new();
+        registry
+            .insert(TRANSFER, "transfer(address,uint256)")
+            .unwrap();
+        let calldata = transfer_calldata();
+        let explanation = registry.explain_calldata(&calldata).unwrap();
+        assert!(explanation.is_canonical());
+        assert_eq!(explanation.types(), &[DynType::Address, DynType::Uint(256)]);
+        let text = explanation.to_string();
+        assert!(text.starts_with("transfer(address,uint256)\n  [0] address: 0x"));
+        assert!(text.ends_with("\n  [1] uint256: 9"));
+
+        // Extra bytes, and a colliding signature that also fits.
+        let mut padded = [0u8; 72];
+        padded[..68].copy_from_slice(&calldata);
+        registry.insert(TRANSFER, "sweep(uint160)").unwrap();
+        let explanation = registry.explain_calldata(&padded).unwrap();
+        assert_eq!(explanation.function.name, "transfer");
+        assert_eq!(
+            explanation.warnings,
+            [
+                Warning::TrailingBytes(4),
+                Warning::Misaligned,
+                Warning::Ambiguous(&registry.lookup(&TRANSFER)[1])
+            ]
+        );
+        assert!(explanation
+            .to_string()
+            .ends_with("warning: also decodes as sweep(uint160)"));
+    }
+
+    #[test]
+    fn test_explain_dynamic_args() {
+        // f(string,uint8[]) = ("gm", [1]) is 4 + 32 * 7 bytes when canonical.
+        let mut registry = SelectorRegistry::new();
+        registry.insert([1, 2, 3, 4], "f(string,uint8[])").unwrap();
+        let mut calldata = [0u8; 4 + 32 * 7];
+        calldata[..4].copy_from_slice(&[1, 2, 3, 4]);
+        calldata[4 + 31] = 64;
+        calldata[4 + 63] = 128;
+        calldata[4 + 95] = 2;
+        calldata[4 + 96..4 + 98].copy_from_slice(b"gm");
+        calldata[4 + 159] = 1;
+        calldata[4 + 191] = 1;
+        let explanation = registry.explain_calldata(&calldata[..4 + 32 * 6]).unwrap();
+        assert!(explanation.is_canonical());
+        assert_eq!(
+            explanation.to_string(),
+            "f(string,uint8[])\n  [0] string: \"gm\"\n  [1] uint8[]: [\n    1\n  ]"
+        );
+        let explanation = registry.explain_calldata(&calldata).unwrap();
+        assert_eq!(explanation.warnings, [Warning::TrailingBytes(32)]);
+    }
+}
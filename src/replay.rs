@@ -0,0 +1,184 @@
+//! Deterministic ordering of logs gathered from several sources.
+//!
+//! Logs are totally ordered on chain by `(block_number, log_index)`. The
+//! wrappers here carry that position next to the log so streams from
+//! different providers or receipts can be sorted and merged before any
+//! decoding happens.
+
+use core::cmp::Ordering;
+use core::iter::Peekable;
+
+use crate::event::ZEventLog;
+
+/// The on-chain position of a log.
+///
+/// Ordering is by block number, then by log index within the block.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LogPosition {
+    pub block_number: u64,
+    pub log_index: u64,
+}
+
+impl LogPosition {
+    #[inline]
+    pub const fn new(block_number: u64, log_index: u64) -> Self {
+        Self {
+            block_number,
+            log_index,
+        }
+    }
+}
+
+/// Anything that carries a [`LogPosition`].
+pub trait Positioned {
+    fn position(&self) -> LogPosition;
+}
+
+impl Positioned for LogPosition {
+    #[inline]
+    fn position(&self) -> LogPosition {
+        *self
+    }
+}
+
+impl<T: Positioned + ?Sized> Positioned for &T {
+    #[inline]
+    fn position(&self) -> LogPosition {
+        (**self).position()
+    }
+}
+
+/// A [`ZEventLog`] together with its position.
+#[derive(Clone, Copy)]
+pub struct ZPositionedLog<'a> {
+    pub position: LogPosition,
+    pub log: ZEventLog<'a>,
+}
+
+impl<'a> ZPositionedLog<'a> {
+    #[inline]
+    pub fn new(block_number: u64, log_index: u64, log: ZEventLog<'a>) -> Self {
+        Self {
+            position: LogPosition::new(block_number, log_index),
+            log,
+        }
+    }
+}
+
+impl Positioned for ZPositionedLog<'_> {
+    #[inline]
+    fn position(&self) -> LogPosition {
+        self.position
+    }
+}
+
+/// Compare two logs by position.
+#[inline]
+pub fn compare_logs<A: Positioned, B: Positioned>(a: &A, b: &B) -> Ordering {
+    a.position().cmp(&b.position())
+}
+
+/// Sort logs by position. The sort is stable: logs reported at the same
+/// position (e.g. by two providers) keep their relative order.
+#[cfg(feature = "alloc")]
+pub fn sort_logs<T: Positioned>(logs: &mut [T]) {
+    logs.sort_by_key(Positioned::position);
+}
+
+/// Merge two position-sorted streams into one sorted stream.
+///
+/// On equal positions the item from `left` comes first, so merging is stable
+/// with respect to the argument order. Duplicates are kept.
+pub fn merge_logs<L, R>(left: L, right: R) -> MergeLogs<L::IntoIter, R::IntoIter>
+where
+    L: IntoIterator,
+    R: IntoIterator<Item = L::Item>,
+    L::Item: Positioned,
+{
+    MergeLogs {
+        left: left.into_iter().peekable(),
+        right: right.into_iter().peekable(),
+    }
+}
+
+/// Iterator returned by [`merge_logs`].
+pub struct MergeLogs<L: Iterator, R: Iterator> {
+    left: Peekable<L>,
+    right: Peekable<R>,
+}
+
+impl<L, R> Iterator for MergeLogs<L, R>
+where
+    L: Iterator,
+    R: Iterator<Item = L::Item>,
+    L::Item: Positioned,
+{
+    type Item = L::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let take_left = match (self.left.peek(), self.right.peek()) {
+            (Some(l), Some(r)) => compare_logs(l, r) != Ordering::Greater,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        if take_left {
+            self.left.next()
+        } else {
+            self.right.next()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (l_lo, l_hi) = self.left.size_hint();
+        let (r_lo, r_hi) = self.right.size_hint();
+        let hi = match (l_hi, r_hi) {
+            (Some(l), Some(r)) => l.checked_add(r),
+            _ => None,
+        };
+        (l_lo.saturating_add(r_lo), hi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn log(block_number: u64, log_index: u64, data: &[u8]) -> ZPositionedLog<'_> {
+        ZPositionedLog::new(block_number, log_index, ZEventLog::from_topics(&[], data))
+    }
+
+    #[test]
+    fn test_position_order() {
+        assert!(LogPosition::new(1, 9) < LogPosition::new(2, 0));
+        assert!(LogPosition::new(2, 0) < LogPosition::new(2, 1));
+        assert_eq!(
+            compare_logs(&log(3, 4, &[]), &LogPosition::new(3, 4)),
+            Ordering::Equal
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_sort_logs_is_stable() {
+        let mut logs = [
+            log(2, 0, &[1]),
+            log(1, 5, &[2]),
+            log(2, 0, &[3]),
+            log(1, 0, &[4]),
+        ];
+        sort_logs(&mut logs);
+        let order: Vec<u8> = logs.iter().map(|l| l.log.data()[0]).collect();
+        assert_eq!(order, [4, 2, 1, 3]);
+    }
+
+    #[test]
+    fn test_merge_logs() {
+        let a = [log(1, 0, &[1]), log(2, 1, &[2]), log(4, 0, &[3])];
+        let b = [log(1, 1, &[4]), log(2, 1, &[5]), log(3, 0, &[6])];
+        let merged = merge_logs(&a, &b);
+        assert_eq!(merged.size_hint(), (6, Some(6)));
+        let order: Vec<u8> = merged.map(|l| l.log.data()[0]).collect();
+        assert_eq!(order, [1, 4, 2, 5, 6, 3]);
+    }
+}
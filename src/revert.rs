@@ -0,0 +1,266 @@
+//! Interpretation of revert data.
+//!
+//! A failed call returns revert data in one of three shapes:
+//! - `Error(string)`, from `require(cond, "reason")` and `revert("reason")`;
+//! - `Panic(uint256)`, from failed assertions, overflow, division by zero and
+//!   similar compiler-inserted checks;
+//! - a custom error, `selector ++ abi.encode(args)`.
+//!
+//! A bare `revert()` or `require(cond)` returns no data at all.
+//! [`decode_revert`] tells these apart without allocating.
+
+use core::fmt;
+
+use crate::decoder::{read_selector, read_string, read_u256};
+use crate::error::ZError;
+use crate::types::ZString;
+
+/// Selector of `Error(string)`.
+pub const ERROR_STRING_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of `Panic(uint256)`.
+pub const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// The reason carried by `Panic(uint256)`, as assigned by the Solidity compiler.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PanicCode {
+    /// `0x00`: generic compiler-inserted panic.
+    Generic,
+    /// `0x01`: `assert` with a false condition.
+    AssertionFailed,
+    /// `0x11`: arithmetic overflow or underflow outside `unchecked`.
+    ArithmeticOverflow,
+    /// `0x12`: division or modulo by zero.
+    DivisionByZero,
+    /// `0x21`: conversion of an out-of-range value to an enum.
+    EnumConversion,
+    /// `0x22`: access to an incorrectly encoded storage byte array.
+    InvalidStorageByteArray,
+    /// `0x31`: `.pop()` on an empty array.
+    EmptyArrayPop,
+    /// `0x32`: array or slice index out of bounds.
+    ArrayOutOfBounds,
+    /// `0x41`: too much memory allocated or an oversized array created.
+    OutOfMemory,
+    /// `0x51`: call through a zero-initialized internal function pointer.
+    ZeroFunctionPointer,
+    /// Any other code, as its raw word.
+    Unknown([u8; 32]),
+}
+
+impl PanicCode {
+    /// Classify a `Panic(uint256)` code word.
+    pub fn from_word(word: &[u8; 32]) -> Self {
+        if word[..31].iter().any(|&b| b != 0) {
+            return PanicCode::Unknown(*word);
+        }
+        match word[31] {
+            0x00 => PanicCode::Generic,
+            0x01 => PanicCode::AssertionFailed,
+            0x11 => PanicCode::ArithmeticOverflow,
+            0x12 => PanicCode::DivisionByZero,
+            0x21 => PanicCode::EnumConversion,
+            0x22 => PanicCode::InvalidStorageByteArray,
+            0x31 => PanicCode::EmptyArrayPop,
+            0x32 => PanicCode::ArrayOutOfBounds,
+            0x41 => PanicCode::OutOfMemory,
+            0x51 => PanicCode::ZeroFunctionPointer,
+            _ => PanicCode::Unknown(*word),
+        }
+    }
+
+    /// The numeric code, or `None` for an unknown code that does not fit a `u8`.
+    pub fn code(&self) -> Option<u8> {
+        Some(match self {
+            PanicCode::Generic => 0x00,
+            PanicCode::AssertionFailed => 0x01,
+            PanicCode::ArithmeticOverflow => 0x11,
+            PanicCode::DivisionByZero => 0x12,
+            PanicCode::EnumConversion => 0x21,
+            PanicCode::InvalidStorageByteArray => 0x22,
+            PanicCode::EmptyArrayPop => 0x31,
+            PanicCode::ArrayOutOfBounds => 0x32,
+            PanicCode::OutOfMemory => 0x41,
+            PanicCode::ZeroFunctionPointer => 0x51,
+            PanicCode::Unknown(word) => {
+                if word[..31].iter().any(|&b| b != 0) {
+                    return None;
+                }
+                word[31]
+            }
+        })
+    }
+}
+
+impl fmt::Display for PanicCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            PanicCode::Generic => "generic panic",
+            PanicCode::AssertionFailed => "assertion failed",
+            PanicCode::ArithmeticOverflow => "arithmetic overflow or underflow",
+            PanicCode::DivisionByZero => "division or modulo by zero",
+            PanicCode::EnumConversion => "invalid enum conversion",
+            PanicCode::InvalidStorageByteArray => "invalid storage byte array encoding",
+            PanicCode::EmptyArrayPop => "pop on empty array",
+            PanicCode::ArrayOutOfBounds => "array index out of bounds",
+            PanicCode::OutOfMemory => "out of memory",
+            PanicCode::ZeroFunctionPointer => "call to zero-initialized function pointer",
+            PanicCode::Unknown(word) => {
+                write!(f, "unknown panic code 0x")?;
+                for byte in word {
+                    write!(f, "{:02x}", byte)?;
+                }
+                return Ok(());
+            }
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+/// Decoded revert data.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RevertError<'a> {
+    /// No revert data: `revert()` or `require(cond)` without a reason.
+    Empty,
+    /// `Error(string)` with its reason.
+    ErrorString(ZString<'a>),
+    /// `Panic(uint256)` with its code.
+    Panic(PanicCode),
+    /// Any other selector. `data` is the encoded arguments after it; decode
+    /// the full revert data with the matching `sol!` error's `decode_revert`.
+    Custom {
+        selector: &'a [u8; 4],
+        data: &'a [u8],
+    },
+}
+
+impl RevertError<'_> {
+    /// Returns the selector, or `None` for empty revert data.
+    pub fn selector(&self) -> Option<[u8; 4]> {
+        match self {
+            RevertError::Empty => None,
+            RevertError::ErrorString(_) => Some(ERROR_STRING_SELECTOR),
+            RevertError::Panic(_) => Some(PANIC_SELECTOR),
+            RevertError::Custom { selector, .. } => Some(**selector),
+        }
+    }
+}
+
+impl fmt::Display for RevertError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RevertError::Empty => write!(f, "reverted without data"),
+            RevertError::ErrorString(reason) => write!(f, "reverted: {}", reason),
+            RevertError::Panic(code) => write!(f, "panicked: {}", code),
+            RevertError::Custom { selector, .. } => {
+                write!(f, "reverted with custom error 0x")?;
+                for byte in selector.iter() {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Classify and decode revert data.
+///
+/// Fails only if the data is malformed: shorter than a selector, or an
+/// `Error(string)`/`Panic(uint256)` payload that does not decode.
+///
+/// ```
+/// use zabi_rs::revert::{decode_revert, RevertError, ERROR_STRING_SELECTOR};
+///
+/// let mut data = ERROR_STRING_SELECTOR.to_vec();
+/// data.extend_from_slice(&[0u8; 31]);
+/// data.push(32); // offset
+/// data.extend_from_slice(&[0u8; 31]);
+/// data.push(2); // length
+/// data.extend_from_slice(b"no");
+/// data.extend_from_slice(&[0u8; 30]);
+///
+/// match decode_revert(&data).unwrap() {
+///     RevertError::ErrorString(reason) => assert_eq!(reason.0, "no"),
+///     other => panic!("unexpected {}", other),
+/// }
+/// assert_eq!(decode_revert(&[]).unwrap(), RevertError::Empty);
+/// ```
+pub fn decode_revert(data: &[u8]) -> Result<RevertError<'_>, ZError> {
+    if data.is_empty() {
+        return Ok(RevertError::Empty);
+    }
+    let selector = read_selector(data)?;
+    let params = &data[4..];
+    match *selector {
+        ERROR_STRING_SELECTOR => Ok(RevertError::ErrorString(read_string(params, 0)?)),
+        PANIC_SELECTOR => Ok(RevertError::Panic(PanicCode::from_word(
+            read_u256(params, 0)?.0,
+        ))),
+        _ => Ok(RevertError::Custom {
+            selector,
+            data: params,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{calldata, word};
+
+    #[test]
+    fn test_decode_panic() {
+        let data = calldata(PANIC_SELECTOR, &[word(0x11)]);
+        let revert = decode_revert(&data).unwrap();
+        assert_eq!(revert.selector(), Some(PANIC_SELECTOR));
+        assert_eq!(revert, RevertError::Panic(PanicCode::ArithmeticOverflow));
+        assert_eq!(
+            std::format!("{}", revert),
+            "panicked: arithmetic overflow or underflow"
+        );
+        assert!(decode_revert(&PANIC_SELECTOR).is_err());
+    }
+
+    #[test]
+    fn test_panic_codes() {
+        for code in [0x00, 0x01, 0x11, 0x12, 0x21, 0x22, 0x31, 0x32, 0x41, 0x51] {
+            let panic = PanicCode::from_word(&word(code));
+            assert!(!matches!(panic, PanicCode::Unknown(_)));
+            assert_eq!(panic.code(), Some(code));
+        }
+        assert_eq!(
+            PanicCode::from_word(&word(0x32)),
+            PanicCode::ArrayOutOfBounds
+        );
+
+        let unknown = PanicCode::from_word(&word(0x99));
+        assert_eq!(unknown.code(), Some(0x99));
+        assert!(std::format!("{}", unknown).ends_with("0099"));
+        let mut wide = word(0x01);
+        wide[0] = 1;
+        assert_eq!(PanicCode::from_word(&wide).code(), None);
+    }
+
+    #[test]
+    fn test_decode_custom_and_malformed() {
+        let data = calldata([0xde, 0xad, 0xbe, 0xef], &[word(1)]);
+        let revert = decode_revert(&data).unwrap();
+        assert!(
+            matches!(revert, RevertError::Custom { selector: [0xde, 0xad, 0xbe, 0xef], data } if data.len() == 32)
+        );
+        assert_eq!(
+            std::format!("{}", revert),
+            "reverted with custom error 0xdeadbeef"
+        );
+
+        assert!(matches!(
+            decode_revert(&[0x08, 0xc3]),
+            Err(ZError::OutOfBounds(4, 2))
+        ));
+        // Error(string) whose offset points past the end.
+        let data = calldata(ERROR_STRING_SELECTOR, &[word(0x80)]);
+        assert!(matches!(
+            decode_revert(&data),
+            Err(ZError::OffsetOutOfRange { .. })
+        ));
+    }
+}
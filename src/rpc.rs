@@ -0,0 +1,247 @@
+//! Owned log buffers deserialized from JSON-RPC responses.
+//!
+//! `eth_getLogs` and receipts return logs as JSON objects with hex strings:
+//!
+//! ```json
+//! { "address": "0x…", "topics": ["0x…", …], "data": "0x…" }
+//! ```
+//!
+//! [`LogBuf`] deserializes that shape (other fields are ignored) and lends
+//! out a [`ZEventLog`] view for the zero-copy decoders. [`PositionedLogBuf`]
+//! also reads `blockNumber` and `logIndex` for use with [`crate::replay`].
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use serde::Deserialize;
+
+use crate::event::ZEventLog;
+use crate::hex::{hex_digit, parse_hex_into, parse_hex_vec};
+use crate::replay::{LogPosition, Positioned, ZPositionedLog};
+
+/// An owned log: emitting address, topics and data.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RpcLog")]
+pub struct LogBuf {
+    address: [u8; 20],
+    topics: Vec<[u8; 32]>,
+    data: Vec<u8>,
+}
+
+impl LogBuf {
+    /// Create a log buffer from already decoded parts.
+    #[inline]
+    pub fn new(address: [u8; 20], topics: Vec<[u8; 32]>, data: Vec<u8>) -> Self {
+        Self {
+            address,
+            topics,
+            data,
+        }
+    }
+
+    /// Returns the emitting contract address.
+    #[inline]
+    pub fn address(&self) -> &[u8; 20] {
+        &self.address
+    }
+
+    /// Returns the topics.
+    #[inline]
+    pub fn topics(&self) -> &[[u8; 32]] {
+        &self.topics
+    }
+
+    /// Returns the non-indexed data.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Borrow the log as a [`ZEventLog`], with the address attached.
+    #[inline]
+    pub fn view(&self) -> ZEventLog<'_> {
+        ZEventLog::from_topics(&self.topics, &self.data).with_address(&self.address)
+    }
+}
+
+/// An owned log with its block number and log index.
+///
+/// Pending logs (with `null` positions) are rejected.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RpcLog")]
+pub struct PositionedLogBuf {
+    pub position: LogPosition,
+    pub log: LogBuf,
+}
+
+impl PositionedLogBuf {
+    #[inline]
+    pub fn new(block_number: u64, log_index: u64, log: LogBuf) -> Self {
+        Self {
+            position: LogPosition::new(block_number, log_index),
+            log,
+        }
+    }
+
+    /// Borrow the log as a [`ZPositionedLog`].
+    #[inline]
+    pub fn view(&self) -> ZPositionedLog<'_> {
+        ZPositionedLog {
+            position: self.position,
+            log: self.log.view(),
+        }
+    }
+}
+
+impl Positioned for PositionedLogBuf {
+    #[inline]
+    fn position(&self) -> LogPosition {
+        self.position
+    }
+}
+
+/// The JSON-RPC log shape, before hex decoding.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcLog {
+    address: String,
+    topics: Vec<String>,
+    data: String,
+    #[serde(default)]
+    block_number: Option<String>,
+    #[serde(default)]
+    log_index: Option<String>,
+}
+
+impl TryFrom<RpcLog> for PositionedLogBuf {
+    type Error = &'static str;
+
+    fn try_from(log: RpcLog) -> Result<Self, Self::Error> {
+        let block_number = log
+            .block_number
+            .as_deref()
+            .ok_or("log has no blockNumber")?;
+        let block_number =
+            parse_quantity(block_number).ok_or("blockNumber is not a hex quantity")?;
+        let log_index = log.log_index.as_deref().ok_or("log has no logIndex")?;
+        let log_index = parse_quantity(log_index).ok_or("logIndex is not a hex quantity")?;
+        Ok(PositionedLogBuf::new(
+            block_number,
+            log_index,
+            LogBuf::try_from(log)?,
+        ))
+    }
+}
+
+impl TryFrom<RpcLog> for LogBuf {
+    type Error = &'static str;
+
+    fn try_from(log: RpcLog) -> Result<Self, Self::Error> {
+        let topics = log
+            .topics
+            .iter()
+            .map(|t| parse_hex_array::<32>(t).ok_or("topic is not a 32-byte hex string"))
+            .collect::<Result<Vec<_>, _>>()?;
+        if topics.len() > 4 {
+            return Err("log has more than 4 topics");
+        }
+        Ok(LogBuf {
+            address: parse_hex_array::<20>(&log.address)
+                .ok_or("address is not a 20-byte hex string")?,
+            topics,
+            data: parse_hex_vec(&log.data).ok_or("data is not a hex string")?,
+        })
+    }
+}
+
+/// Parses a JSON-RPC quantity: `0x`-prefixed hex without padding requirements.
+fn parse_quantity(s: &str) -> Option<u64> {
+    let digits = s.strip_prefix("0x")?;
+    if digits.is_empty() || digits.len() > 16 {
+        return None;
+    }
+    digits
+        .bytes()
+        .try_fold(0u64, |acc, c| Some(acc << 4 | u64::from(hex_digit(c)?)))
+}
+
+fn parse_hex_array<const N: usize>(s: &str) -> Option<[u8; N]> {
+    let mut out = [0u8; N];
+    parse_hex_into(s, &mut out)?;
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSFER_LOG: &str = r#"{
+        "address": "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+        "topics": [
+            "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+            "0x000000000000000000000000000000000000000000000000000000000000aaaa",
+            "0x000000000000000000000000000000000000000000000000000000000000bbbb"
+        ],
+        "data": "0x0000000000000000000000000000000000000000000000000000000000000064",
+        "blockNumber": "0x10",
+        "logIndex": "0x0",
+        "removed": false
+    }"#;
+
+    #[test]
+    fn test_log_buf_from_json() {
+        let log: LogBuf = serde_json::from_str(TRANSFER_LOG).unwrap();
+        assert_eq!(log.address()[0], 0xa0);
+        assert_eq!(log.topics().len(), 3);
+        assert_eq!(log.data()[31], 0x64);
+
+        let view = log.view();
+        assert_eq!(view.address().unwrap().as_bytes()[19], 0x48);
+        assert_eq!(
+            view.event_signature().unwrap()[..4],
+            [0xdd, 0xf2, 0x52, 0xad]
+        );
+        assert_eq!(
+            view.topic_as_address(2).unwrap().as_bytes()[18..],
+            [0xbb, 0xbb]
+        );
+        assert_eq!(view.decode_data_as::<u64>().unwrap(), 100);
+    }
+
+    #[test]
+    fn test_log_buf_rejects_bad_hex() {
+        let short_topic = TRANSFER_LOG.replace(
+            "0x000000000000000000000000000000000000000000000000000000000000aaaa",
+            "0xaaaa",
+        );
+        assert!(serde_json::from_str::<LogBuf>(&short_topic).is_err());
+        let odd_data = TRANSFER_LOG.replace("0064\"", "064\"");
+        assert!(serde_json::from_str::<LogBuf>(&odd_data).is_err());
+        let bad_address = TRANSFER_LOG.replace("0xa0b8", "0xz0b8");
+        assert!(serde_json::from_str::<LogBuf>(&bad_address).is_err());
+
+        let empty =
+            r#"{"address":"0x0000000000000000000000000000000000000000","topics":[],"data":"0x"}"#;
+        let log: LogBuf = serde_json::from_str(empty).unwrap();
+        assert_eq!(log.view().topic_count(), 0);
+        assert!(log.data().is_empty());
+    }
+
+    #[test]
+    fn test_positioned_log_buf() {
+        let log: PositionedLogBuf = serde_json::from_str(TRANSFER_LOG).unwrap();
+        assert_eq!(log.position, LogPosition::new(16, 0));
+        assert_eq!(log.view().log.topic_count(), 3);
+        assert_eq!(
+            log.log,
+            serde_json::from_str::<LogBuf>(TRANSFER_LOG).unwrap()
+        );
+
+        let pending = TRANSFER_LOG.replace("\"0x10\"", "null");
+        assert!(serde_json::from_str::<PositionedLogBuf>(&pending).is_err());
+        assert!(serde_json::from_str::<LogBuf>(&pending).is_ok());
+        let bad_index = TRANSFER_LOG.replace("\"0x0\"", "\"0xg\"");
+        assert!(serde_json::from_str::<PositionedLogBuf>(&bad_index).is_err());
+    }
+}
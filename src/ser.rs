@@ -0,0 +1,189 @@
+//! `serde` support for the zero-copy views and the owned types.
+//!
+//! Addresses, words and byte strings serialize as `0x`-prefixed hex strings,
+//! with words written in full (64 digits; two's complement for `int256`).
+//! Strings and booleans map to their JSON counterparts and arrays to
+//! sequences, decoding each element as it is written.
+//!
+//! The owned types use the same hex form in both directions, so they
+//! round-trip through JSON; deserializing goes through their `from_hex`.
+
+use core::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Error, Serialize, SerializeSeq, Serializer};
+
+use crate::owned::{Address, Bytes32, I256, U256};
+use crate::types::{ZAddress, ZArray, ZBool, ZBytes, ZFixedArray, ZInt256, ZString, ZU256};
+use crate::zbytes_fixed::ZBytesN;
+use crate::ZDecode;
+
+macro_rules! impl_serialize_hex {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl Serialize for $ty {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.collect_str(&format_args!("{:#x}", self))
+                }
+            }
+        )+
+    };
+}
+
+impl_serialize_hex!(ZAddress<'_>, ZU256<'_>, ZInt256<'_>, ZBytes<'_>);
+
+impl<const N: usize> Serialize for ZBytesN<'_, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{:#x}", self))
+    }
+}
+
+impl Serialize for ZString<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+
+impl Serialize for ZBool {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bool(self.0)
+    }
+}
+
+/// Elements are decoded while serializing; a malformed element fails the
+/// whole array with a custom serializer error.
+impl<'a, T> Serialize for ZArray<'a, T>
+where
+    T: ZDecode<'a> + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for i in 0..self.len() {
+            let item = self.get(i).map_err(S::Error::custom)?;
+            seq.serialize_element(&item)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'a, T, const N: usize> Serialize for ZFixedArray<'a, T, N>
+where
+    T: ZDecode<'a> + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+macro_rules! impl_serde_owned {
+    ($($owned:ident => $expecting:literal),+ $(,)?) => {
+        $(
+            impl Serialize for $owned {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    self.as_view().serialize(serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $owned {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    struct HexVisitor;
+
+                    impl<'de> Visitor<'de> for HexVisitor {
+                        type Value = $owned;
+
+                        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                            f.write_str($expecting)
+                        }
+
+                        fn visit_str<E: de::Error>(self, v: &str) -> Result<$owned, E> {
+                            $owned::from_hex(v).map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+                        }
+                    }
+
+                    deserializer.deserialize_str(HexVisitor)
+                }
+            }
+        )+
+    };
+}
+
+impl_serde_owned!(
+    Address => "a 20-byte hex string",
+    U256 => "a 32-byte hex string",
+    I256 => "a 32-byte hex string",
+    Bytes32 => "a 32-byte hex string",
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::format;
+
+    #[test]
+    fn test_serialize_views() {
+        let word = [0xab; 20];
+        assert_eq!(
+            serde_json::to_value(ZAddress(&word)).unwrap(),
+            json!(format!("0x{}", "ab".repeat(20)))
+        );
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        assert_eq!(
+            serde_json::to_value(ZU256(&one)).unwrap(),
+            json!(format!("0x{}01", "00".repeat(31)))
+        );
+        assert_eq!(
+            serde_json::to_value(ZInt256(&[0xff; 32])).unwrap(),
+            json!(format!("0x{}", "ff".repeat(32)))
+        );
+        assert_eq!(
+            serde_json::to_value(ZBytes(&[0xde, 0xad])).unwrap(),
+            json!("0xdead")
+        );
+        assert_eq!(serde_json::to_value(ZBytes(&[])).unwrap(), json!("0x"));
+        assert_eq!(
+            serde_json::to_value(ZBytesN(&[0x12, 0x34, 0x56, 0x78])).unwrap(),
+            json!("0x12345678")
+        );
+        assert_eq!(serde_json::to_value(ZString("gm")).unwrap(), json!("gm"));
+        assert_eq!(serde_json::to_value(ZBool(true)).unwrap(), json!(true));
+    }
+
+    #[test]
+    fn test_serialize_array() {
+        // uint8[] = [1, 2] behind an offset word.
+        let mut data = [0u8; 128];
+        data[31] = 32;
+        data[63] = 2;
+        data[95] = 1;
+        data[127] = 2;
+        let array: ZArray<u8> = crate::decoder::read_array_dyn(&data, 0).unwrap();
+        assert_eq!(serde_json::to_value(array).unwrap(), json!([1, 2]));
+
+        data[126] = 1; // dirty padding in element 1
+        let array: ZArray<u8> = crate::decoder::read_array_dyn(&data, 0).unwrap();
+        assert!(serde_json::to_value(array).is_err());
+    }
+
+    #[test]
+    fn test_owned_round_trip() {
+        let address = Address([0x7a; 20]);
+        let json = serde_json::to_string(&address).unwrap();
+        assert_eq!(json, format!("\"0x{}\"", "7a".repeat(20)));
+        assert_eq!(serde_json::from_str::<Address>(&json).unwrap(), address);
+
+        let amount = U256::from(1_000u64);
+        let back: U256 = serde_json::from_value(serde_json::to_value(amount).unwrap()).unwrap();
+        assert_eq!(back, amount);
+        let hash: Bytes32 =
+            serde_json::from_value(json!(format!("0x{}", "11".repeat(32)))).unwrap();
+        assert_eq!(hash, Bytes32([0x11; 32]));
+        let minus_one: I256 = serde_json::from_value(json!("ff".repeat(32))).unwrap();
+        assert_eq!(minus_one.as_view().sign(), -1);
+
+        let err = serde_json::from_value::<Address>(json!("0x1234")).unwrap_err();
+        assert!(format!("{}", err).contains("a 20-byte hex string"));
+        assert!(serde_json::from_value::<U256>(json!(5)).is_err());
+    }
+}
@@ -0,0 +1,362 @@
+//! Long-running decoder service (requires the `std` feature).
+//!
+//! A single worker thread pulls `(schema_id, bytes)` jobs from a bounded queue,
+//! takes up to `batch_size` of them at a time, decodes each batch with
+//! [`SchemaRegistry::decode_batch`], and sends the batch's owned results back
+//! over a channel in one message. The bounded queue provides backpressure:
+//! `submit` blocks and `try_submit` fails once it is full.
+//!
+//! Decoded views borrow from the job buffer, so each registered decoder maps
+//! the zero-copy view into an owned result type `R` before it leaves the worker.
+//!
+//! [`SchemaRegistry`] is not built on [`crate::registry::SelectorRegistry`]:
+//! that one resolves ABI signatures by selector and yields dynamic
+//! [`DynValue`](crate::dynamic::DynValue)s, while a schema here is an
+//! arbitrary caller-chosen id bound to a typed decoder, and needs neither
+//! signature parsing nor the `keccak` feature.
+
+use std::collections::BTreeMap;
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::thread::{self, JoinHandle};
+use std::vec::Vec;
+
+use crate::error::ZError;
+
+/// Identifier used to select a decoder for a job.
+pub type SchemaId = u32;
+
+/// Decoder function registered for a schema.
+pub type DecodeFn<R> = fn(&[u8]) -> Result<R, ZError>;
+
+/// Table of decoders keyed by schema id.
+pub struct SchemaRegistry<R> {
+    entries: BTreeMap<SchemaId, DecodeFn<R>>,
+}
+
+impl<R> SchemaRegistry<R> {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Register (or replace) the decoder for `schema_id`.
+    pub fn register(&mut self, schema_id: SchemaId, decoder: DecodeFn<R>) -> &mut Self {
+        self.entries.insert(schema_id, decoder);
+        self
+    }
+
+    /// Look up the decoder for `schema_id`.
+    pub fn get(&self, schema_id: SchemaId) -> Option<DecodeFn<R>> {
+        self.entries.get(&schema_id).copied()
+    }
+
+    /// Decode `bytes` with the decoder registered for `schema_id`.
+    pub fn decode(&self, schema_id: SchemaId, bytes: &[u8]) -> Result<R, ZError> {
+        match self.get(schema_id) {
+            Some(decoder) => decoder(bytes),
+            None => Err(ZError::UnknownSchema(schema_id)),
+        }
+    }
+
+    /// Decode every job of a batch, in order. A failed job does not stop the
+    /// batch; its error is reported in its own [`JobResult`].
+    pub fn decode_batch(&self, jobs: &[Job]) -> Vec<JobResult<R>> {
+        jobs.iter()
+            .map(|job| JobResult {
+                id: job.id,
+                schema_id: job.schema_id,
+                result: self.decode(job.schema_id, &job.bytes),
+            })
+            .collect()
+    }
+
+    /// Returns the number of registered schemas.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if no schema is registered.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<R> Default for SchemaRegistry<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A unit of work submitted to the service.
+#[derive(Debug)]
+pub struct Job {
+    pub id: u64,
+    pub schema_id: SchemaId,
+    pub bytes: Vec<u8>,
+}
+
+/// The outcome of decoding a single job.
+#[derive(Debug)]
+pub struct JobResult<R> {
+    pub id: u64,
+    pub schema_id: SchemaId,
+    pub result: Result<R, ZError>,
+}
+
+/// Reason a job could not be queued. The job is handed back to the caller.
+#[derive(Debug)]
+pub enum SubmitError {
+    /// The queue is at capacity; retry later.
+    Full(Job),
+    /// The worker has stopped.
+    Closed(Job),
+}
+
+/// Tuning knobs for [`DecoderService`].
+#[derive(Debug, Clone, Copy)]
+pub struct ServiceConfig {
+    /// Maximum number of pending jobs before submission applies backpressure.
+    pub queue_capacity: usize,
+    /// Maximum number of jobs decoded, and results delivered, per batch.
+    pub batch_size: usize,
+}
+
+impl Default for ServiceConfig {
+    fn default() -> Self {
+        Self {
+            queue_capacity: 1024,
+            batch_size: 64,
+        }
+    }
+}
+
+/// Queue-driven decoder worker.
+pub struct DecoderService<R> {
+    sender: Option<SyncSender<Job>>,
+    results: Receiver<Vec<JobResult<R>>>,
+    worker: Option<JoinHandle<()>>,
+    next_id: u64,
+}
+
+impl<R: Send + 'static> DecoderService<R> {
+    /// Spawn the worker thread.
+    pub fn spawn(registry: SchemaRegistry<R>, config: ServiceConfig) -> Self {
+        let (sender, jobs) = mpsc::sync_channel::<Job>(config.queue_capacity.max(1));
+        let (result_sender, results) = mpsc::channel();
+        let batch_size = config.batch_size.max(1);
+
+        let worker = thread::spawn(move || {
+            let mut batch = Vec::with_capacity(batch_size);
+            while next_batch(&jobs, &mut batch, batch_size) {
+                let out = registry.decode_batch(&batch);
+                batch.clear();
+                if result_sender.send(out).is_err() {
+                    return;
+                }
+            }
+        });
+
+        Self {
+            sender: Some(sender),
+            results,
+            worker: Some(worker),
+            next_id: 0,
+        }
+    }
+
+    /// Queue a job, blocking while the queue is full. Returns the job id.
+    pub fn submit(&mut self, schema_id: SchemaId, bytes: Vec<u8>) -> Result<u64, SubmitError> {
+        let job = self.make_job(schema_id, bytes);
+        let id = job.id;
+        match &self.sender {
+            Some(sender) => sender
+                .send(job)
+                .map(|_| id)
+                .map_err(|e| SubmitError::Closed(e.0)),
+            None => Err(SubmitError::Closed(job)),
+        }
+    }
+
+    /// Queue a job without blocking. Returns the job id.
+    pub fn try_submit(&mut self, schema_id: SchemaId, bytes: Vec<u8>) -> Result<u64, SubmitError> {
+        let job = self.make_job(schema_id, bytes);
+        let id = job.id;
+        match &self.sender {
+            Some(sender) => sender.try_send(job).map(|_| id).map_err(|e| match e {
+                TrySendError::Full(job) => SubmitError::Full(job),
+                TrySendError::Disconnected(job) => SubmitError::Closed(job),
+            }),
+            None => Err(SubmitError::Closed(job)),
+        }
+    }
+
+    /// Channel on which decoded batches are delivered, in submission order.
+    /// Each batch holds between 1 and `batch_size` results.
+    pub fn results(&self) -> &Receiver<Vec<JobResult<R>>> {
+        &self.results
+    }
+
+    /// Stop accepting jobs, wait for the worker to drain the queue,
+    /// and return every result not yet received.
+    pub fn shutdown(mut self) -> Vec<JobResult<R>> {
+        self.stop();
+        self.results.try_iter().flatten().collect()
+    }
+
+    fn make_job(&mut self, schema_id: SchemaId, bytes: Vec<u8>) -> Job {
+        let id = self.next_id;
+        self.next_id += 1;
+        Job {
+            id,
+            schema_id,
+            bytes,
+        }
+    }
+}
+
+/// Blocks for the next job, then adds whatever is already queued to `batch`,
+/// up to `batch_size` jobs. Returns false once the queue is closed.
+fn next_batch(jobs: &Receiver<Job>, batch: &mut Vec<Job>, batch_size: usize) -> bool {
+    match jobs.recv() {
+        Ok(job) => batch.push(job),
+        Err(_) => return false,
+    }
+    while batch.len() < batch_size {
+        match jobs.try_recv() {
+            Ok(job) => batch.push(job),
+            Err(_) => break,
+        }
+    }
+    true
+}
+
+impl<R> DecoderService<R> {
+    fn stop(&mut self) {
+        // Dropping the sender ends the worker loop once the queue is drained.
+        self.sender.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl<R> Drop for DecoderService<R> {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::{read_address_from_word, read_u256};
+
+    fn decode_amount(data: &[u8]) -> Result<u128, ZError> {
+        read_u256(data, 0)?
+            .to_u128()
+            .ok_or(ZError::Custom("amount overflows u128"))
+    }
+
+    fn decode_sender(data: &[u8]) -> Result<[u8; 20], ZError> {
+        Ok(read_address_from_word(data, 0)?.to_bytes())
+    }
+
+    #[test]
+    fn test_service_decodes_in_order() {
+        let mut registry = SchemaRegistry::new();
+        registry.register(1, decode_amount);
+        let mut service = DecoderService::spawn(
+            registry,
+            ServiceConfig {
+                queue_capacity: 4,
+                batch_size: 2,
+            },
+        );
+
+        for i in 0..10u8 {
+            let mut word = [0u8; 32];
+            word[31] = i;
+            service.submit(1, word.to_vec()).unwrap();
+        }
+        service.submit(7, std::vec![0u8; 32]).unwrap();
+        service.submit(1, std::vec![0u8; 8]).unwrap();
+
+        let mut results = Vec::new();
+        while results.len() < 12 {
+            let batch = service.results().recv().unwrap();
+            assert!((1..=2).contains(&batch.len()));
+            results.extend(batch);
+        }
+        assert!(service.shutdown().is_empty());
+        for (i, out) in results.iter().enumerate() {
+            assert_eq!(out.id, i as u64);
+        }
+        for (i, out) in results.drain(..10).enumerate() {
+            assert_eq!(out.result.unwrap(), i as u128);
+        }
+        assert!(matches!(results[0].result, Err(ZError::UnknownSchema(7))));
+        assert!(results[1].result.is_err()); // truncated word
+    }
+
+    #[test]
+    fn test_next_batch() {
+        let (sender, jobs) = mpsc::sync_channel(8);
+        for id in 0..5 {
+            sender
+                .send(Job {
+                    id,
+                    schema_id: 1,
+                    bytes: Vec::new(),
+                })
+                .unwrap();
+        }
+        drop(sender);
+
+        let mut batch = Vec::new();
+        let mut sizes = Vec::new();
+        while next_batch(&jobs, &mut batch, 2) {
+            sizes.push(batch.len());
+            batch.clear();
+        }
+        assert_eq!(sizes, [2, 2, 1]);
+    }
+
+    #[test]
+    fn test_decode_batch() {
+        let mut registry = SchemaRegistry::new();
+        registry.register(1, decode_amount);
+        let job = |id, schema_id, last| {
+            let mut bytes = std::vec![0u8; 32];
+            bytes[31] = last;
+            Job {
+                id,
+                schema_id,
+                bytes,
+            }
+        };
+        let out = registry.decode_batch(&[job(0, 1, 5), job(1, 2, 6), job(2, 1, 7)]);
+        assert_eq!(out.iter().map(|r| r.id).collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(out[0].result.as_ref().unwrap(), &5);
+        assert!(matches!(out[1].result, Err(ZError::UnknownSchema(2))));
+        assert_eq!(out[2].result.as_ref().unwrap(), &7);
+    }
+
+    #[test]
+    fn test_registry_replace() {
+        let mut registry: SchemaRegistry<[u8; 20]> = SchemaRegistry::default();
+        registry
+            .register(3, decode_sender)
+            .register(3, decode_sender);
+        assert_eq!(registry.len(), 1);
+
+        let mut word = [0u8; 32];
+        word[31] = 0xAA;
+        assert_eq!(registry.decode(3, &word).unwrap()[19], 0xAA);
+        assert!(matches!(
+            registry.decode(4, &word),
+            Err(ZError::UnknownSchema(4))
+        ));
+    }
+}
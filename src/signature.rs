@@ -0,0 +1,209 @@
+//! Compile-time construction of canonical Solidity type strings.
+//!
+//! `ZDecode::SOL_TYPE` for composite types (tuples, arrays, derived structs)
+//! is assembled from the `SOL_TYPE` of their components. Since generic
+//! associated consts cannot size an array, the strings are built in a
+//! fixed-capacity buffer inside a `const` context.
+
+/// Maximum length of a generated type string.
+pub const SOL_TYPE_CAPACITY: usize = 512;
+
+/// Fixed-capacity string builder usable in `const` contexts.
+#[derive(Clone, Copy)]
+pub struct SolTypeBuf {
+    buf: [u8; SOL_TYPE_CAPACITY],
+    len: usize,
+}
+
+impl SolTypeBuf {
+    /// Create an empty buffer.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            buf: [0u8; SOL_TYPE_CAPACITY],
+            len: 0,
+        }
+    }
+
+    /// Append a string slice.
+    /// Panics (at compile time when used in a const) if the capacity is exceeded.
+    pub const fn push_str(mut self, s: &str) -> Self {
+        let bytes = s.as_bytes();
+        if self.len + bytes.len() > SOL_TYPE_CAPACITY {
+            panic!("Solidity type string exceeds SOL_TYPE_CAPACITY");
+        }
+        let mut i = 0;
+        while i < bytes.len() {
+            self.buf[self.len] = bytes[i];
+            self.len += 1;
+            i += 1;
+        }
+        self
+    }
+
+    /// Append the decimal representation of `n`.
+    pub const fn push_usize(mut self, n: usize) -> Self {
+        let mut digits = [0u8; 20];
+        let mut count = 0;
+        let mut rest = n;
+        loop {
+            digits[count] = b'0' + (rest % 10) as u8;
+            count += 1;
+            rest /= 10;
+            if rest == 0 {
+                break;
+            }
+        }
+        if self.len + count > SOL_TYPE_CAPACITY {
+            panic!("Solidity type string exceeds SOL_TYPE_CAPACITY");
+        }
+        while count > 0 {
+            count -= 1;
+            self.buf[self.len] = digits[count];
+            self.len += 1;
+        }
+        self
+    }
+
+    /// Append a comma-separated list of strings wrapped in parentheses.
+    /// Panics (at compile time when used in a const) on an empty item: a
+    /// member without a `SOL_TYPE` would otherwise vanish from the type.
+    pub const fn push_tuple(self, items: &[&str]) -> Self {
+        let mut this = self.push_str("(");
+        let mut i = 0;
+        while i < items.len() {
+            if items[i].is_empty() {
+                panic!("tuple member has no SOL_TYPE");
+            }
+            if i > 0 {
+                this = this.push_str(",");
+            }
+            this = this.push_str(items[i]);
+            i += 1;
+        }
+        this.push_str(")")
+    }
+
+    /// Append the tuple type of a derived struct. Each field is its
+    /// `SOL_TYPE` and whether it is `#[zabi(flatten)]`: a flattened field
+    /// contributes its members instead, which is nothing for an empty struct.
+    /// Panics like [`push_tuple`](Self::push_tuple) on a field without a
+    /// `SOL_TYPE`.
+    pub const fn push_fields(self, fields: &[(&str, bool)]) -> Self {
+        let mut this = self.push_str("(");
+        let mut first = true;
+        let mut i = 0;
+        while i < fields.len() {
+            let (ty, flatten) = fields[i];
+            if ty.is_empty() {
+                panic!("tuple member has no SOL_TYPE");
+            }
+            let item = if flatten { tuple_members(ty) } else { ty };
+            if !item.is_empty() {
+                if !first {
+                    this = this.push_str(",");
+                }
+                this = this.push_str(item);
+                first = false;
+            }
+            i += 1;
+        }
+        this.push_str(")")
+    }
+
+    /// Returns the number of bytes written so far.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if nothing has been written.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// View the written bytes as a string slice.
+    pub const fn as_str(&self) -> &str {
+        match core::str::from_utf8(self.buf.split_at(self.len).0) {
+            Ok(s) => s,
+            Err(_) => panic!("Solidity type string is not valid UTF-8"),
+        }
+    }
+}
+
+/// The member list of a tuple type string: `"(uint256,bool)"` -> `"uint256,bool"`.
+///
+/// Used to splice `#[zabi(flatten)]` fields into the enclosing tuple.
+/// Panics (at compile time when used in a const) if `tuple` is not a tuple type.
+pub const fn tuple_members(tuple: &str) -> &str {
+    let bytes = tuple.as_bytes();
+    let len = bytes.len();
+    if len < 2 || bytes[0] != b'(' || bytes[len - 1] != b')' {
+        panic!("flattened field is not a tuple type");
+    }
+    let (inner, _) = bytes.split_at(len - 1);
+    let (_, inner) = inner.split_at(1);
+    match core::str::from_utf8(inner) {
+        Ok(s) => s,
+        Err(_) => panic!("Solidity type string is not valid UTF-8"),
+    }
+}
+
+impl Default for SolTypeBuf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_tuple() {
+        const S: &str = SolTypeBuf::new()
+            .push_tuple(&["uint256", "address", "bool"])
+            .as_str();
+        assert_eq!(S, "(uint256,address,bool)");
+    }
+
+    #[test]
+    fn test_push_usize() {
+        let buf = SolTypeBuf::new().push_str("bytes").push_usize(32);
+        assert_eq!(buf.as_str(), "bytes32");
+        assert_eq!(SolTypeBuf::new().push_usize(0).as_str(), "0");
+    }
+
+    #[test]
+    fn test_tuple_members() {
+        const INNER: &str = tuple_members("(uint256,bool)");
+        assert_eq!(INNER, "uint256,bool");
+        assert_eq!(tuple_members("()"), "");
+    }
+
+    #[test]
+    fn test_push_fields() {
+        let fields = [
+            ("address", false),
+            ("(uint256,bool)", true),
+            ("()", true),
+            ("()", false),
+            ("string", false),
+        ];
+        let buf = SolTypeBuf::new().push_fields(&fields);
+        assert_eq!(buf.as_str(), "(address,uint256,bool,(),string)");
+    }
+
+    #[test]
+    #[should_panic(expected = "tuple member has no SOL_TYPE")]
+    fn test_push_tuple_rejects_empty_member() {
+        SolTypeBuf::new().push_tuple(&["address", ""]);
+    }
+
+    #[test]
+    #[should_panic(expected = "tuple member has no SOL_TYPE")]
+    fn test_push_fields_rejects_empty_member() {
+        SolTypeBuf::new().push_fields(&[("", true)]);
+    }
+}
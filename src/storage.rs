@@ -0,0 +1,241 @@
+//! Solidity storage layout: raw slot words as returned by `eth_getStorageAt`.
+//!
+//! - Value types smaller than a word are packed into one slot, starting at
+//!   the low-order end. [`packed`] and its typed variants take the byte
+//!   offset from the right that `solc --storage-layout` reports.
+//! - `string` and `bytes` of up to 31 bytes live in their slot with
+//!   `2 * len` in the lowest byte; longer ones store `2 * len + 1` there and
+//!   the data from [`data_slot`]. See [`bytes_slot`].
+//! - A dynamic array's slot holds its length ([`array_length`]); elements
+//!   start at [`data_slot`].
+//! - A mapping value lives at [`mapping_slot`] (requires `keccak`).
+
+use crate::types::ZAddress;
+use crate::ZError;
+
+/// The word for a declared slot number.
+pub fn slot(n: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&n.to_be_bytes());
+    word
+}
+
+/// `base + n`, wrapping like slot arithmetic in the EVM. Element `i` of an
+/// array of one-slot values is at `slot_add(&data_slot(&p), i)`.
+pub fn slot_add(base: &[u8; 32], n: u64) -> [u8; 32] {
+    let mut out = *base;
+    let mut carry = n as u128;
+    for byte in out.iter_mut().rev() {
+        if carry == 0 {
+            break;
+        }
+        let sum = *byte as u128 + (carry & 0xff);
+        *byte = sum as u8;
+        carry = (carry >> 8) + (sum >> 8);
+    }
+    out
+}
+
+/// The `size` bytes of a packed variable at byte `offset` from the right of
+/// the slot, most significant first.
+pub fn packed(word: &[u8; 32], offset: usize, size: usize) -> Result<&[u8], ZError> {
+    let end = offset
+        .checked_add(size)
+        .filter(|&end| end <= 32 && size > 0);
+    match end {
+        Some(end) => Ok(&word[32 - end..32 - offset]),
+        None => Err(ZError::OutOfBounds(offset.saturating_add(size), 32)),
+    }
+}
+
+/// A packed `uintN` of `size` bytes, up to `uint128`.
+pub fn packed_uint(word: &[u8; 32], offset: usize, size: usize) -> Result<u128, ZError> {
+    if size > 16 {
+        return Err(ZError::InvalidLength(16, size));
+    }
+    let mut buf = [0u8; 16];
+    buf[16 - size..].copy_from_slice(packed(word, offset, size)?);
+    Ok(u128::from_be_bytes(buf))
+}
+
+/// A packed `intN` of `size` bytes, up to `int128`, sign-extended.
+pub fn packed_int(word: &[u8; 32], offset: usize, size: usize) -> Result<i128, ZError> {
+    if size > 16 {
+        return Err(ZError::InvalidLength(16, size));
+    }
+    let bytes = packed(word, offset, size)?;
+    let fill = if bytes[0] & 0x80 != 0 { 0xff } else { 0x00 };
+    let mut buf = [fill; 16];
+    buf[16 - size..].copy_from_slice(bytes);
+    Ok(i128::from_be_bytes(buf))
+}
+
+/// A packed `address`.
+pub fn packed_address(word: &[u8; 32], offset: usize) -> Result<ZAddress<'_>, ZError> {
+    Ok(ZAddress(packed(word, offset, 20)?.try_into().unwrap()))
+}
+
+/// A packed `bool`; any byte other than 0 or 1 is rejected.
+pub fn packed_bool(word: &[u8; 32], offset: usize) -> Result<bool, ZError> {
+    match packed(word, offset, 1)?[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ZError::InvalidBool {
+            offset: 31 - offset,
+        }),
+    }
+}
+
+/// The length stored in a dynamic array's slot.
+pub fn array_length(word: &[u8; 32]) -> Result<usize, ZError> {
+    crate::decoder::read_u64(word, 0).map(|len| len as usize)
+}
+
+/// The main slot of a `string` or `bytes` variable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageBytes<'a> {
+    /// Up to 31 bytes, stored in the slot itself.
+    Inline(&'a [u8]),
+    /// `len` bytes stored from [`data_slot`] of the variable's slot on,
+    /// 32 per slot.
+    Long { len: usize },
+}
+
+impl StorageBytes<'_> {
+    pub fn len(&self) -> usize {
+        match self {
+            StorageBytes::Inline(bytes) => bytes.len(),
+            StorageBytes::Long { len } => *len,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Decode the main slot of a `string` or `bytes` variable.
+pub fn bytes_slot(word: &[u8; 32]) -> Result<StorageBytes<'_>, ZError> {
+    if word[31] & 1 == 0 {
+        let len = word[31] as usize / 2;
+        if len > 31 {
+            return Err(ZError::InvalidLength(31, len));
+        }
+        Ok(StorageBytes::Inline(&word[..len]))
+    } else {
+        let encoded = crate::decoder::read_u64(word, 0)?;
+        Ok(StorageBytes::Long {
+            len: (encoded / 2) as usize,
+        })
+    }
+}
+
+/// `keccak256(slot)`: where a dynamic array's elements, or a long
+/// `string`/`bytes` value's data, begin.
+#[cfg(feature = "keccak")]
+pub fn data_slot(slot: &[u8; 32]) -> [u8; 32] {
+    crate::hash::keccak256(slot)
+}
+
+/// The slot of `mapping[key]` for a mapping at `slot`, with a value-type key
+/// encoded as its ABI word (addresses and small ints left-padded, `bytesN`
+/// right-padded).
+#[cfg(feature = "keccak")]
+pub fn mapping_slot(key: &[u8; 32], slot: &[u8; 32]) -> [u8; 32] {
+    let mut buf = [0u8; 64];
+    buf[..32].copy_from_slice(key);
+    buf[32..].copy_from_slice(slot);
+    crate::hash::keccak256(&buf)
+}
+
+/// The slot of `mapping[key]` for a `string` or `bytes` key, which is
+/// hashed unpadded.
+#[cfg(feature = "keccak")]
+pub fn mapping_slot_bytes(key: &[u8], slot: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = crate::hash::Keccak256::new();
+    hasher.update(key).update(slot);
+    hasher.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packed() {
+        // uint64 a (offset 0), address b (offset 8), bool c (offset 28),
+        // int16 d (offset 29).
+        let mut word = [0u8; 32];
+        word[24..].copy_from_slice(&42u64.to_be_bytes());
+        word[4..24].copy_from_slice(&[0xaa; 20]);
+        word[3] = 1;
+        word[1..3].copy_from_slice(&(-2i16).to_be_bytes());
+
+        assert_eq!(packed_uint(&word, 0, 8).unwrap(), 42);
+        assert_eq!(packed_address(&word, 8).unwrap().as_bytes(), &[0xaa; 20]);
+        assert!(packed_bool(&word, 28).unwrap());
+        assert_eq!(packed_int(&word, 29, 2).unwrap(), -2);
+        assert_eq!(packed(&word, 31, 1).unwrap(), &[0]);
+
+        assert!(matches!(
+            packed(&word, 31, 2),
+            Err(ZError::OutOfBounds(33, 32))
+        ));
+        assert!(matches!(
+            packed_uint(&word, 0, 17),
+            Err(ZError::InvalidLength(16, 17))
+        ));
+        assert!(matches!(
+            packed_bool(&word, 8),
+            Err(ZError::InvalidBool { offset: 23 })
+        ));
+    }
+
+    #[test]
+    fn test_bytes_slot() {
+        let mut word = [0u8; 32];
+        word[..5].copy_from_slice(b"hello");
+        word[31] = 10;
+        assert_eq!(bytes_slot(&word).unwrap(), StorageBytes::Inline(b"hello"));
+        assert!(bytes_slot(&[0u8; 32]).unwrap().is_empty());
+
+        let long = slot(2 * 100 + 1);
+        assert_eq!(bytes_slot(&long).unwrap(), StorageBytes::Long { len: 100 });
+        word[31] = 64;
+        assert!(matches!(
+            bytes_slot(&word),
+            Err(ZError::InvalidLength(31, 32))
+        ));
+    }
+
+    #[test]
+    fn test_slot_arithmetic() {
+        assert_eq!(array_length(&slot(3)).unwrap(), 3);
+        assert_eq!(slot_add(&slot(1), 2), slot(3));
+        let mut base = [0u8; 32];
+        base[31] = 0xff;
+        base[30] = 0xff;
+        let sum = slot_add(&base, 1);
+        assert_eq!(sum[29..], [1, 0, 0]);
+        assert_eq!(slot_add(&[0xff; 32], 1), [0u8; 32]);
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn test_mapping_slot() {
+        use crate::hash::keccak256;
+
+        let zero = data_slot(&slot(0));
+        assert_eq!(zero[..4], [0x29, 0x0d, 0xec, 0xd9]);
+        assert_eq!(zero[28..], [0x0e, 0xf3, 0xe5, 0x63]);
+
+        let mut key = [0u8; 32];
+        key[12..].copy_from_slice(&[0xaa; 20]);
+        let expected = keccak256(&[key, slot(9)].concat());
+        assert_eq!(mapping_slot(&key, &slot(9)), expected);
+        assert_eq!(
+            mapping_slot_bytes(b"abc", &slot(9)),
+            keccak256(&[&b"abc"[..], &slot(9)].concat())
+        );
+    }
+}
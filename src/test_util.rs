@@ -0,0 +1,26 @@
+//! Fixtures shared by the unit tests.
+
+use alloc::vec::Vec;
+
+/// A word whose last byte is `last`.
+pub(crate) fn word(last: u8) -> [u8; 32] {
+    let mut w = [0u8; 32];
+    w[31] = last;
+    w
+}
+
+/// A word holding `value` big-endian: an offset, a length or a wider uint.
+pub(crate) fn uint_word(value: u64) -> [u8; 32] {
+    let mut w = [0u8; 32];
+    w[24..].copy_from_slice(&value.to_be_bytes());
+    w
+}
+
+/// `selector` followed by `words`: calldata or revert data.
+pub(crate) fn calldata(selector: [u8; 4], words: &[[u8; 32]]) -> Vec<u8> {
+    let mut out = selector.to_vec();
+    for w in words {
+        out.extend_from_slice(w);
+    }
+    out
+}
@@ -1,6 +1,7 @@
 use core::fmt;
 use core::marker::PhantomData;
 use crate::ZError;
+use crate::owned::{I256, U256};
 
 // We need to refer to ZDecode trait. 
 // Since we are in a submodule, we can use crate::ZDecode
@@ -41,8 +42,33 @@ impl<'a, T> ZArray<'a, T> {
         if index >= self.length {
             return Err(ZError::OutOfBounds(index, self.length));
         }
-        let offset = self.start_offset + index * 32;
-        T::decode(self.data, offset)
+        // Elements are HEAD_SIZE apart; offsets of dynamic elements are
+        // relative to the array body.
+        let result = if T::IS_DYNAMIC {
+            T::decode_member(&self.data[self.start_offset..], index * T::HEAD_SIZE)
+        } else {
+            T::decode_member(self.data, self.start_offset + index * T::HEAD_SIZE)
+        };
+        result.map_err(|e| e.in_field(index))
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<'a, T: ZDecode<'a>> ZArray<'a, T> {
+    /// Decode every element into a bounded `heapless::Vec`, without `alloc`:
+    /// `let v: heapless::Vec<ZU256, 8> = array.collect_into()?;`
+    ///
+    /// An array longer than `N` fails with `InvalidLength(N, len)`.
+    pub fn collect_into<const N: usize>(&self) -> Result<heapless::Vec<T, N>, ZError> {
+        if self.length > N {
+            return Err(ZError::InvalidLength(N, self.length));
+        }
+        let mut out = heapless::Vec::new();
+        for i in 0..self.length {
+            // Cannot fail: the length was checked above.
+            let _ = out.push(self.get(i)?);
+        }
+        Ok(out)
     }
 }
 
@@ -52,8 +78,91 @@ impl<'a, T: fmt::Debug> fmt::Debug for ZArray<'a, T> {
     }
 }
 
+/// A fixed-size array `T[N]`, whose length comes from the type instead of
+/// a length word. Derefs to the underlying [`ZArray`] for `get` and `len`.
+#[derive(Clone, Copy)]
+pub struct ZFixedArray<'a, T, const N: usize>(pub ZArray<'a, T>);
+
+impl<'a, T, const N: usize> core::ops::Deref for ZFixedArray<'a, T, N> {
+    type Target = ZArray<'a, T>;
+
+    #[inline]
+    fn deref(&self) -> &ZArray<'a, T> {
+        &self.0
+    }
+}
+
+impl<'a, T, const N: usize> fmt::Debug for ZFixedArray<'a, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ZFixedArray(len={})", N)
+    }
+}
+
+/// Writes `bytes` as hex for the `LowerHex`/`UpperHex` impls, honoring `#`
+/// (the `0x` prefix), width, fill, alignment and `0` padding. Every byte is
+/// written, so words keep their leading zeros.
+pub(crate) fn fmt_hex(bytes: &[u8], upper: bool, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    use fmt::Write;
+
+    let prefix = if f.alternate() { "0x" } else { "" };
+    let len = prefix.len() + bytes.len() * 2;
+    let pad = f.width().map_or(0, |w| w.saturating_sub(len));
+    let (fill, pre, post) = if f.sign_aware_zero_pad() {
+        ('0', 0, 0)
+    } else {
+        match f.align() {
+            Some(fmt::Alignment::Left) => (f.fill(), 0, pad),
+            Some(fmt::Alignment::Center) => (f.fill(), pad / 2, pad - pad / 2),
+            _ => (f.fill(), pad, 0),
+        }
+    };
+
+    for _ in 0..pre {
+        f.write_char(fill)?;
+    }
+    f.write_str(prefix)?;
+    if f.sign_aware_zero_pad() {
+        for _ in 0..pad {
+            f.write_char('0')?;
+        }
+    }
+    for byte in bytes {
+        if upper {
+            write!(f, "{:02X}", byte)?;
+        } else {
+            write!(f, "{:02x}", byte)?;
+        }
+    }
+    for _ in 0..post {
+        f.write_char(fill)?;
+    }
+    Ok(())
+}
+
+/// Implements `LowerHex` and `UpperHex` over the bytes of `self.0`.
+macro_rules! impl_hex_fmt {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl fmt::LowerHex for $ty {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    $crate::types::fmt_hex(&self.0[..], false, f)
+                }
+            }
+
+            impl fmt::UpperHex for $ty {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    $crate::types::fmt_hex(&self.0[..], true, f)
+                }
+            }
+        )+
+    };
+}
+pub(crate) use impl_hex_fmt;
+
+impl_hex_fmt!(ZAddress<'_>, ZU256<'_>, ZInt256<'_>, ZBytes<'_>);
+
 /// Wrapper around a 20-byte Ethereum address reference.
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ZAddress<'a>(pub &'a [u8; 20]);
 
 impl<'a> fmt::Debug for ZAddress<'a> {
@@ -88,10 +197,34 @@ impl<'a> ZAddress<'a> {
     pub fn as_bytes(&self) -> &[u8; 20] {
         self.0
     }
+
+    /// Check if this is the zero address.
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|&b| b == 0)
+    }
+
+    /// Render the address with its EIP-55 mixed-case checksum, e.g.
+    /// `0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed`.
+    #[cfg(feature = "keccak")]
+    pub fn to_checksum<'b>(&self, buf: &'b mut [u8; 42]) -> &'b str {
+        buf[0] = b'0';
+        buf[1] = b'x';
+        crate::hex::write_digits(self.0, &mut buf[2..]);
+        let hash = crate::hash::keccak256(&buf[2..]);
+        for (i, c) in buf[2..].iter_mut().enumerate() {
+            let nibble = if i % 2 == 0 { hash[i / 2] >> 4 } else { hash[i / 2] & 0x0f };
+            if c.is_ascii_lowercase() && nibble >= 8 {
+                c.make_ascii_uppercase();
+            }
+        }
+        // Only ASCII hex digits and the prefix were written.
+        core::str::from_utf8(buf).unwrap()
+    }
 }
 
 /// Wrapper around a 32-byte EVM word (uint256) reference.
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ZU256<'a>(pub &'a [u8; 32]);
 
 impl<'a> fmt::Debug for ZU256<'a> {
@@ -104,14 +237,55 @@ impl<'a> fmt::Debug for ZU256<'a> {
     }
 }
 
+/// Formats the value in base 10. Use `{:?}` for the raw hex word.
 impl<'a> fmt::Display for ZU256<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "0x")?;
-        for byte in self.0 {
-            write!(f, "{:02x}", byte)?;
+        let mut buf = [0u8; MAX_DECIMAL_LEN];
+        f.pad_integral(true, "", self.to_decimal(&mut buf))
+    }
+}
+
+/// Longest base-10 rendering of a 256-bit word: 78 digits for uint256 max,
+/// or a sign and 77 digits for int256 min.
+pub const MAX_DECIMAL_LEN: usize = 78;
+
+/// Writes the base-10 digits of `word` into the end of `out`, returning the
+/// index of the first digit.
+fn write_decimal(word: &[u8; 32], out: &mut [u8; MAX_DECIMAL_LEN]) -> usize {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(word.chunks_exact(8)) {
+        *limb = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+    let mut start = MAX_DECIMAL_LEN;
+    loop {
+        // Long division of the limbs by 10, most significant first.
+        let mut rem = 0u128;
+        for limb in limbs.iter_mut() {
+            let cur = (rem << 64) | *limb as u128;
+            *limb = (cur / 10) as u64;
+            rem = cur % 10;
         }
-        Ok(())
+        start -= 1;
+        out[start] = b'0' + rem as u8;
+        if limbs.iter().all(|&l| l == 0) {
+            return start;
+        }
+    }
+}
+
+/// Copies the digits into the front of `buf`.
+///
+/// # Panics
+/// If `buf` is too short.
+fn copy_decimal<'b>(negative: bool, digits: &[u8], buf: &'b mut [u8]) -> &'b str {
+    let len = digits.len() + negative as usize;
+    assert!(buf.len() >= len, "decimal buffer too small: need {} bytes", len);
+    if negative {
+        buf[0] = b'-';
     }
+    buf[negative as usize..len].copy_from_slice(digits);
+    // Only ASCII digits and '-' were written.
+    core::str::from_utf8(&buf[..len]).unwrap()
 }
 
 impl<'a> ZU256<'a> {
@@ -163,6 +337,12 @@ impl<'a> ZU256<'a> {
         self.0.iter().all(|&b| b == 0)
     }
 
+    /// Check if the value is `type(uint256).max`, e.g. an unlimited approval.
+    #[inline]
+    pub fn is_max(&self) -> bool {
+        self.0.iter().all(|&b| b == 0xff)
+    }
+
     /// Convert to u32 if the value fits (upper 28 bytes are zero).
     /// Returns None if the value overflows u32.
     #[inline]
@@ -189,11 +369,163 @@ impl<'a> ZU256<'a> {
     pub fn to_u8(&self) -> Option<u8> {
         self.to_u32().and_then(|v| v.try_into().ok())
     }
+
+    /// Render the value in base 10 into `buf` without allocating.
+    ///
+    /// # Panics
+    /// If `buf` is shorter than the number of digits; [`MAX_DECIMAL_LEN`]
+    /// bytes always suffice.
+    pub fn to_decimal<'b>(&self, buf: &'b mut [u8]) -> &'b str {
+        let mut digits = [0u8; MAX_DECIMAL_LEN];
+        let start = write_decimal(self.0, &mut digits);
+        copy_decimal(false, &digits[start..], buf)
+    }
+
+    /// Render the value as a decimal amount with `decimals` fractional
+    /// digits, e.g. `1500000000000000000` with 18 decimals as `"1.5"`.
+    /// Trailing fractional zeros are dropped, along with the point for whole
+    /// amounts.
+    ///
+    /// # Panics
+    /// If `buf` is too short; `MAX_DECIMAL_LEN + decimals + 2` bytes always suffice.
+    pub fn format_units<'b>(&self, decimals: u8, buf: &'b mut [u8]) -> &'b str {
+        let mut digits = [0u8; MAX_DECIMAL_LEN];
+        let start = write_decimal(self.0, &mut digits);
+        let digits = &digits[start..];
+        let decimals = decimals as usize;
+
+        let (int_part, frac_part): (&[u8], &[u8]) = if digits.len() > decimals {
+            digits.split_at(digits.len() - decimals)
+        } else {
+            (b"0", digits)
+        };
+        let leading_zeros = decimals - frac_part.len();
+        let frac_part = match frac_part.iter().rposition(|&d| d != b'0') {
+            Some(last) => &frac_part[..=last],
+            None => &[],
+        };
+
+        let mut len = int_part.len();
+        if !frac_part.is_empty() {
+            len += 1 + leading_zeros + frac_part.len();
+        }
+        assert!(buf.len() >= len, "decimal buffer too small: need {} bytes", len);
+        buf[..int_part.len()].copy_from_slice(int_part);
+        if !frac_part.is_empty() {
+            let mut pos = int_part.len();
+            buf[pos] = b'.';
+            pos += 1;
+            buf[pos..pos + leading_zeros].fill(b'0');
+            pos += leading_zeros;
+            buf[pos..len].copy_from_slice(frac_part);
+        }
+        // Only ASCII digits and '.' were written.
+        core::str::from_utf8(&buf[..len]).unwrap()
+    }
+
+    /// Returns bit `i`, counting from the least significant bit.
+    /// Bits at or above 256 read as zero.
+    #[inline]
+    pub fn bit(&self, i: usize) -> bool {
+        if i >= 256 {
+            return false;
+        }
+        self.0[31 - i / 8] >> (i % 8) & 1 != 0
+    }
+
+    /// Returns byte `i`, counting from the most significant byte, like the
+    /// EVM `BYTE` opcode. Indices at or above 32 read as zero.
+    #[inline]
+    pub fn byte(&self, i: usize) -> u8 {
+        if i >= 32 {
+            return 0;
+        }
+        self.0[i]
+    }
+
+    /// Number of leading zero bits; 256 for zero.
+    #[inline]
+    pub fn leading_zeros(&self) -> u32 {
+        match self.0.iter().position(|&b| b != 0) {
+            Some(i) => i as u32 * 8 + self.0[i].leading_zeros(),
+            None => 256,
+        }
+    }
+
+    /// Number of bits needed to represent the value; 0 for zero.
+    #[inline]
+    pub fn bit_len(&self) -> u32 {
+        256 - self.leading_zeros()
+    }
+
+    /// Logical left shift, like the EVM `SHL` opcode. Shifts of 256 or more yield zero.
+    pub fn shl(&self, n: u32) -> U256 {
+        let mut out = [0u8; 32];
+        if n >= 256 {
+            return U256(out);
+        }
+        let bytes = (n / 8) as usize;
+        let bits = n % 8;
+        for (i, b) in out.iter_mut().take(32 - bytes).enumerate() {
+            *b = self.0[i + bytes] << bits;
+            if bits > 0 && i + bytes + 1 < 32 {
+                *b |= self.0[i + bytes + 1] >> (8 - bits);
+            }
+        }
+        U256(out)
+    }
+
+    /// Logical right shift, like the EVM `SHR` opcode. Shifts of 256 or more yield zero.
+    pub fn shr(&self, n: u32) -> U256 {
+        let mut out = [0u8; 32];
+        if n >= 256 {
+            return U256(out);
+        }
+        let bytes = (n / 8) as usize;
+        let bits = n % 8;
+        for (i, b) in out.iter_mut().enumerate().skip(bytes) {
+            *b = self.0[i - bytes] >> bits;
+            if bits > 0 && i > bytes {
+                *b |= self.0[i - bytes - 1] << (8 - bits);
+            }
+        }
+        U256(out)
+    }
+}
+
+/// Wrapping big-endian word addition; returns the sum and the carry out.
+#[inline]
+pub(crate) fn add_words(a: &[u8; 32], b: &[u8; 32]) -> ([u8; 32], bool) {
+    let mut out = [0u8; 32];
+    let mut carry = 0u16;
+    for i in (0..32).rev() {
+        let sum = a[i] as u16 + b[i] as u16 + carry;
+        out[i] = sum as u8;
+        carry = sum >> 8;
+    }
+    (out, carry != 0)
+}
+
+/// Wrapping big-endian word subtraction; returns the difference and the borrow out.
+#[inline]
+pub(crate) fn sub_words(a: &[u8; 32], b: &[u8; 32]) -> ([u8; 32], bool) {
+    let mut out = [0u8; 32];
+    let mut borrow = 0i16;
+    for i in (0..32).rev() {
+        let mut diff = a[i] as i16 - b[i] as i16 - borrow;
+        borrow = 0;
+        if diff < 0 {
+            diff += 256;
+            borrow = 1;
+        }
+        out[i] = diff as u8;
+    }
+    (out, borrow != 0)
 }
 
 /// Wrapper around a 32-byte EVM word (int256) reference.
 /// Semantically represents a signed integer.
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ZInt256<'a>(pub &'a [u8; 32]);
 
 impl<'a> fmt::Debug for ZInt256<'a> {
@@ -206,14 +538,29 @@ impl<'a> fmt::Debug for ZInt256<'a> {
     }
 }
 
+/// Formats the signed value in base 10; `{:?}` still shows the two's-complement word.
 impl<'a> fmt::Display for ZInt256<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // We display hex for now, interpreting as signed decimal would require big logic
-        write!(f, "0x")?;
-        for byte in self.0 {
-            write!(f, "{:02x}", byte)?;
-        }
-        Ok(())
+        let mut digits = [0u8; MAX_DECIMAL_LEN];
+        let start = write_decimal(&self.abs().0, &mut digits);
+        let magnitude = core::str::from_utf8(&digits[start..]).unwrap();
+        f.pad_integral(!self.is_negative(), "", magnitude)
+    }
+}
+
+impl<'a> PartialOrd for ZInt256<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a> Ord for ZInt256<'a> {
+    /// Signed comparison: negative values order below non-negative ones.
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        other
+            .is_negative()
+            .cmp(&self.is_negative())
+            .then_with(|| self.0.cmp(other.0))
     }
 }
 
@@ -267,6 +614,76 @@ impl<'a> ZInt256<'a> {
         self.0[0] & 0x80 != 0
     }
 
+    /// Check if the value is zero.
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|&b| b == 0)
+    }
+
+    /// Render the value in base 10, with a leading `-` if negative, into
+    /// `buf` without allocating.
+    ///
+    /// # Panics
+    /// If `buf` is too short; [`MAX_DECIMAL_LEN`] bytes always suffice.
+    pub fn to_decimal<'b>(&self, buf: &'b mut [u8]) -> &'b str {
+        let mut digits = [0u8; MAX_DECIMAL_LEN];
+        let start = write_decimal(&self.abs().0, &mut digits);
+        copy_decimal(self.is_negative(), &digits[start..], buf)
+    }
+
+    /// Returns -1, 0 or 1 according to the sign of the value.
+    #[inline]
+    pub fn sign(&self) -> i8 {
+        if self.is_negative() {
+            -1
+        } else if self.is_zero() {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Two's-complement negation. Wraps for `int256` min, which negates to itself.
+    #[inline]
+    pub fn neg(&self) -> I256 {
+        I256(sub_words(&[0u8; 32], self.0).0)
+    }
+
+    /// Absolute value as an unsigned word. Never overflows: the magnitude of
+    /// `int256` min is 2^255.
+    #[inline]
+    pub fn abs(&self) -> U256 {
+        if self.is_negative() {
+            U256(self.neg().0)
+        } else {
+            U256(*self.0)
+        }
+    }
+
+    /// Checked signed addition; `None` on overflow.
+    #[inline]
+    pub fn checked_add(&self, other: &ZInt256<'_>) -> Option<I256> {
+        let (sum, _) = add_words(self.0, other.0);
+        let negative = sum[0] & 0x80 != 0;
+        // Overflow iff both operands share a sign the result does not.
+        if self.is_negative() == other.is_negative() && negative != self.is_negative() {
+            return None;
+        }
+        Some(I256(sum))
+    }
+
+    /// Checked signed subtraction; `None` on overflow.
+    #[inline]
+    pub fn checked_sub(&self, other: &ZInt256<'_>) -> Option<I256> {
+        let (diff, _) = sub_words(self.0, other.0);
+        let negative = diff[0] & 0x80 != 0;
+        // Overflow iff the operands differ in sign and the result takes the subtrahend's.
+        if self.is_negative() != other.is_negative() && negative != self.is_negative() {
+            return None;
+        }
+        Some(I256(diff))
+    }
+
     /// Convert to i32 if the value fits.
     #[inline]
     pub fn to_i32(&self) -> Option<i32> {
@@ -295,8 +712,101 @@ impl<'a> ZInt256<'a> {
     }
 }
 
+// A value too wide for the target fails with `Overflow`: the word itself is
+// well formed, so there is no offset to report.
+macro_rules! impl_try_from_word {
+    ($view:ident, $($t:ty => $to:ident),+ $(,)?) => {
+        $(
+            impl<'a> TryFrom<$view<'a>> for $t {
+                type Error = ZError;
+
+                #[inline]
+                fn try_from(value: $view<'a>) -> Result<Self, ZError> {
+                    value
+                        .$to()
+                        .and_then(|v| v.try_into().ok())
+                        .ok_or(ZError::Overflow { ty: stringify!($t) })
+                }
+            }
+        )+
+    };
+}
+
+impl_try_from_word!(ZU256,
+    u8 => to_u8, u16 => to_u16, u32 => to_u32, u64 => to_u64, u128 => to_u128, usize => to_u64,
+);
+impl_try_from_word!(ZInt256,
+    i8 => to_i8, i16 => to_i16, i32 => to_i32, i64 => to_i64, i128 => to_i128, isize => to_i64,
+);
+
+// Comparisons against plain values, so `addr == KNOWN_ROUTER` or
+// `amount == 0u64` work without building a view first.
+macro_rules! impl_eq_bytes {
+    ($view:ident, $($rhs:ty),+) => {
+        $(
+            impl<'a> PartialEq<$rhs> for $view<'a> {
+                #[inline]
+                fn eq(&self, other: &$rhs) -> bool {
+                    self.0[..] == other[..]
+                }
+            }
+        )+
+    };
+}
+
+impl_eq_bytes!(ZAddress, [u8; 20], &[u8]);
+impl_eq_bytes!(ZU256, [u8; 32]);
+impl_eq_bytes!(ZInt256, [u8; 32]);
+impl_eq_bytes!(ZBytes, [u8], &[u8]);
+
+impl<'a> PartialEq<u64> for ZU256<'a> {
+    #[inline]
+    fn eq(&self, other: &u64) -> bool {
+        self.to_u64() == Some(*other)
+    }
+}
+
+impl<'a> PartialEq<u128> for ZU256<'a> {
+    #[inline]
+    fn eq(&self, other: &u128) -> bool {
+        self.to_u128() == Some(*other)
+    }
+}
+
+impl<'a> PartialEq<i64> for ZInt256<'a> {
+    #[inline]
+    fn eq(&self, other: &i64) -> bool {
+        self.to_i64() == Some(*other)
+    }
+}
+
+impl<'a> PartialEq<i128> for ZInt256<'a> {
+    #[inline]
+    fn eq(&self, other: &i128) -> bool {
+        self.to_i128() == Some(*other)
+    }
+}
+
+// Constant-time equality for comparing decoded signatures, commitments
+// and keys without leaking the position of the first differing byte.
+#[cfg(feature = "subtle")]
+impl<'a> subtle::ConstantTimeEq for ZAddress<'a> {
+    #[inline]
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        self.0[..].ct_eq(&other.0[..])
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl<'a> subtle::ConstantTimeEq for ZU256<'a> {
+    #[inline]
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        self.0[..].ct_eq(&other.0[..])
+    }
+}
+
 /// Wrapper around a variable-length byte array reference.
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ZBytes<'a>(pub &'a [u8]);
 
 impl<'a> fmt::Debug for ZBytes<'a> {
@@ -341,11 +851,43 @@ impl<'a> ZBytes<'a> {
     pub fn as_slice(&self) -> &[u8] {
         self.0
     }
+
+    /// Returns a sub-slice borrowed from the input buffer, or `None` if the
+    /// range is out of bounds.
+    #[inline]
+    pub fn get<R>(&self, range: R) -> Option<&'a [u8]>
+    where
+        R: core::slice::SliceIndex<[u8], Output = [u8]>,
+    {
+        self.0.get(range)
+    }
+
+    /// Check whether the bytes begin with `prefix`, e.g. a selector.
+    #[inline]
+    pub fn starts_with(&self, prefix: &[u8]) -> bool {
+        self.0.starts_with(prefix)
+    }
+
+    /// Iterate over `n`-byte chunks; the last one may be shorter.
+    ///
+    /// # Panics
+    /// If `n` is zero.
+    #[inline]
+    pub fn chunks(&self, n: usize) -> core::slice::Chunks<'a, u8> {
+        self.0.chunks(n)
+    }
+
+    /// Interpret the bytes as UTF-8 text.
+    /// The error offset is relative to the start of the bytes.
+    #[inline]
+    pub fn as_str(&self) -> Result<&'a str, ZError> {
+        core::str::from_utf8(self.0).map_err(|e| ZError::InvalidUtf8 { offset: e.valid_up_to() })
+    }
 }
 
 /// Wrapper around a boolean value.
 /// Note: EVM booleans are uint256 (0 or 1).
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ZBool(pub bool);
 
 impl fmt::Debug for ZBool {
@@ -368,6 +910,54 @@ impl ZBool {
     }
 }
 
+impl From<ZBool> for bool {
+    #[inline]
+    fn from(b: ZBool) -> Self {
+        b.0
+    }
+}
+
+impl From<bool> for ZBool {
+    #[inline]
+    fn from(b: bool) -> Self {
+        ZBool(b)
+    }
+}
+
+impl PartialEq<bool> for ZBool {
+    #[inline]
+    fn eq(&self, other: &bool) -> bool {
+        self.0 == *other
+    }
+}
+
+impl core::ops::Not for ZBool {
+    type Output = ZBool;
+
+    #[inline]
+    fn not(self) -> ZBool {
+        ZBool(!self.0)
+    }
+}
+
+impl core::ops::BitAnd for ZBool {
+    type Output = ZBool;
+
+    #[inline]
+    fn bitand(self, rhs: ZBool) -> ZBool {
+        ZBool(self.0 & rhs.0)
+    }
+}
+
+impl core::ops::BitOr for ZBool {
+    type Output = ZBool;
+
+    #[inline]
+    fn bitor(self, rhs: ZBool) -> ZBool {
+        ZBool(self.0 | rhs.0)
+    }
+}
+
 /// Wrapper around a UTF-8 string slice reference.
 #[derive(Clone, Copy, PartialEq)]
 pub struct ZString<'a>(pub &'a str);
@@ -384,6 +974,20 @@ impl<'a> fmt::Display for ZString<'a> {
     }
 }
 
+impl<'a> PartialEq<str> for ZString<'a> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl<'a> PartialEq<&str> for ZString<'a> {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
 impl<'a> ZString<'a> {
     /// Returns the length of the string in bytes.
     #[inline]
@@ -397,9 +1001,48 @@ impl<'a> ZString<'a> {
         self.0.is_empty()
     }
 
-    /// Returns the inner string slice.
+    /// Returns the inner string slice, borrowed from the input buffer.
+    #[inline]
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+
+    /// Copy into a bounded `heapless::String`, without `alloc`. A string
+    /// longer than `N` bytes fails with `InvalidLength(N, len)`.
+    #[cfg(feature = "heapless")]
+    pub fn to_heapless<const N: usize>(&self) -> Result<heapless::String<N>, ZError> {
+        let mut out = heapless::String::new();
+        out.push_str(self.0).map_err(|_| ZError::InvalidLength(N, self.0.len()))?;
+        Ok(out)
+    }
+}
+
+impl<'a> core::ops::Deref for ZString<'a> {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.0
+    }
+}
+
+impl<'a> AsRef<str> for ZString<'a> {
     #[inline]
-    pub fn as_str(&self) -> &str {
+    fn as_ref(&self) -> &str {
         self.0
     }
 }
+
+impl<'a> AsRef<[u8]> for ZString<'a> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl<'a> From<ZString<'a>> for &'a str {
+    #[inline]
+    fn from(s: ZString<'a>) -> Self {
+        s.0
+    }
+}
@@ -0,0 +1,225 @@
+//! WAD (UD60x18) fixed-point values.
+//!
+//! Many DeFi protocols store rates, prices and balances as uint256 scaled by
+//! 1e18 ("WAD"). [`ZWad`] is a zero-copy view over such a word with the
+//! usual fixed-point helpers. Products and quotients go through a 512-bit
+//! intermediate, so they only fail when the result itself overflows.
+
+use core::fmt;
+
+use crate::decoder;
+use crate::error::ZError;
+use crate::owned::U256;
+use crate::types::ZU256;
+use crate::ZDecode;
+
+/// `1e18`, the WAD scale.
+pub const WAD: u64 = 1_000_000_000_000_000_000;
+
+/// A uint256 word interpreted as a WAD-scaled (1e18) fixed-point number.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ZWad<'a>(pub &'a [u8; 32]);
+
+impl<'a> ZWad<'a> {
+    /// The underlying integer view.
+    #[inline]
+    pub fn as_u256(&self) -> ZU256<'a> {
+        ZU256(self.0)
+    }
+
+    /// Approximate value as `f64`, for display and analytics only.
+    pub fn to_f64_lossy(&self) -> f64 {
+        let limbs = to_limbs(self.0);
+        let mut value = 0f64;
+        for limb in limbs.iter().rev() {
+            value = value * 18446744073709551616.0 + *limb as f64;
+        }
+        value / WAD as f64
+    }
+
+    /// `self * other / 1e18`, rounded down; `None` if the result overflows uint256.
+    pub fn mul_wad(&self, other: &ZWad<'_>) -> Option<U256> {
+        let a = to_limbs(self.0);
+        let b = to_limbs(other.0);
+        let mut product = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for j in 0..4 {
+                let cur = a[i] as u128 * b[j] as u128 + product[i + j] as u128 + carry;
+                product[i + j] = cur as u64;
+                carry = cur >> 64;
+            }
+            product[i + 4] = carry as u64;
+        }
+        let mut rem = 0u128;
+        for limb in product.iter_mut().rev() {
+            let cur = (rem << 64) | *limb as u128;
+            *limb = (cur / WAD as u128) as u64;
+            rem = cur % WAD as u128;
+        }
+        if product[4..].iter().any(|&l| l != 0) {
+            return None;
+        }
+        Some(from_limbs(&product[..4]))
+    }
+
+    /// `self * 1e18 / other`, rounded down; `None` on division by zero or
+    /// if the result overflows uint256.
+    pub fn div_wad(&self, other: &ZWad<'_>) -> Option<U256> {
+        let divisor = to_limbs(other.0);
+        if divisor.iter().all(|&l| l == 0) {
+            return None;
+        }
+        let a = to_limbs(self.0);
+        let mut numerator = [0u64; 5];
+        let mut carry = 0u128;
+        for i in 0..4 {
+            let cur = a[i] as u128 * WAD as u128 + carry;
+            numerator[i] = cur as u64;
+            carry = cur >> 64;
+        }
+        numerator[4] = carry as u64;
+
+        // Shift-subtract long division; the remainder stays below the divisor
+        // but needs a fifth limb while shifted.
+        let mut quotient = [0u64; 5];
+        let mut rem = [0u64; 5];
+        for bit in (0..320).rev() {
+            for i in (1..5).rev() {
+                rem[i] = rem[i] << 1 | rem[i - 1] >> 63;
+            }
+            rem[0] = rem[0] << 1 | (numerator[bit / 64] >> (bit % 64)) & 1;
+            if !less_than(&rem, &divisor) {
+                subtract(&mut rem, &divisor);
+                quotient[bit / 64] |= 1 << (bit % 64);
+            }
+        }
+        if quotient[4] != 0 {
+            return None;
+        }
+        Some(from_limbs(&quotient[..4]))
+    }
+
+    /// Render as a decimal with up to 18 fractional digits, e.g. `"1.5"`.
+    /// See [`ZU256::format_units`].
+    pub fn format<'b>(&self, buf: &'b mut [u8]) -> &'b str {
+        self.as_u256().format_units(18, buf)
+    }
+}
+
+/// Formats the value as a decimal, e.g. `1.5` for `1500000000000000000`.
+impl<'a> fmt::Display for ZWad<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = [0u8; crate::types::MAX_DECIMAL_LEN + 20];
+        f.pad(self.format(&mut buf))
+    }
+}
+
+impl<'a> fmt::Debug for ZWad<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ZWad({})", self)
+    }
+}
+
+impl<'a> From<ZU256<'a>> for ZWad<'a> {
+    #[inline]
+    fn from(value: ZU256<'a>) -> Self {
+        ZWad(value.0)
+    }
+}
+
+impl<'a> ZDecode<'a> for ZWad<'a> {
+    const HEAD_SIZE: usize = 32;
+    const SOL_TYPE: &'static str = "uint256";
+    fn decode(data: &'a [u8], offset: usize) -> Result<Self, ZError> {
+        decoder::read_u256(data, offset).map(ZWad::from)
+    }
+}
+
+/// Big-endian word to little-endian limbs.
+fn to_limbs(word: &[u8; 32]) -> [u64; 4] {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().rev().zip(word.chunks_exact(8)) {
+        *limb = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+    limbs
+}
+
+/// Little-endian limbs to an owned big-endian word.
+fn from_limbs(limbs: &[u64]) -> U256 {
+    let mut bytes = [0u8; 32];
+    for (chunk, limb) in bytes.chunks_exact_mut(8).zip(limbs.iter().rev()) {
+        chunk.copy_from_slice(&limb.to_be_bytes());
+    }
+    U256(bytes)
+}
+
+fn less_than(a: &[u64; 5], b: &[u64; 4]) -> bool {
+    if a[4] != 0 {
+        return false;
+    }
+    for i in (0..4).rev() {
+        if a[i] != b[i] {
+            return a[i] < b[i];
+        }
+    }
+    false
+}
+
+fn subtract(a: &mut [u64; 5], b: &[u64; 4]) {
+    let mut borrow = false;
+    for i in 0..5 {
+        let rhs = if i < 4 { b[i] } else { 0 };
+        let (d1, b1) = a[i].overflowing_sub(rhs);
+        let (d2, b2) = d1.overflowing_sub(borrow as u64);
+        a[i] = d2;
+        borrow = b1 || b2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wad(value: u128) -> [u8; 32] {
+        *U256::from(value).as_bytes()
+    }
+
+    #[test]
+    fn test_mul_div_wad() {
+        let one_and_half = wad(1_500_000_000_000_000_000);
+        let two = wad(2 * WAD as u128);
+        let product = ZWad(&one_and_half).mul_wad(&ZWad(&two)).unwrap();
+        assert_eq!(product.to_u128(), Some(3 * WAD as u128));
+        let quotient = ZWad(&one_and_half).div_wad(&ZWad(&two)).unwrap();
+        assert_eq!(quotient.to_u128(), Some(750_000_000_000_000_000));
+
+        // Intermediates past 256 bits are fine as long as the result fits.
+        let max = [0xff; 32];
+        let one = wad(WAD as u128);
+        assert_eq!(ZWad(&max).mul_wad(&ZWad(&one)), Some(U256::MAX));
+        assert_eq!(ZWad(&max).div_wad(&ZWad(&one)), Some(U256::MAX));
+        assert_eq!(ZWad(&max).mul_wad(&ZWad(&two)), None);
+        assert_eq!(ZWad(&max).div_wad(&ZWad(&wad(WAD as u128 / 2))), None);
+        assert_eq!(ZWad(&one).div_wad(&ZWad(&[0u8; 32])), None);
+
+        // Rounds down.
+        let third = ZWad(&one).div_wad(&ZWad(&wad(3 * WAD as u128))).unwrap();
+        assert_eq!(third.to_u128(), Some(333_333_333_333_333_333));
+    }
+
+    #[test]
+    fn test_format_and_float() {
+        let value = wad(1_500_000_000_000_000_000);
+        assert_eq!(std::format!("{}", ZWad(&value)), "1.5");
+        assert_eq!(std::format!("{:?}", ZWad(&value)), "ZWad(1.5)");
+        assert!((ZWad(&value).to_f64_lossy() - 1.5).abs() < 1e-12);
+        assert_eq!(ZWad(&[0u8; 32]).to_f64_lossy(), 0.0);
+        assert!(ZWad(&[0xff; 32]).to_f64_lossy() > 1.1e59);
+
+        let mut data = [0u8; 32];
+        data.copy_from_slice(&value);
+        assert_eq!(ZWad::decode(&data, 0).unwrap(), ZWad(&value));
+        assert_eq!(<ZWad as ZDecode>::SOL_TYPE, "uint256");
+    }
+}
@@ -5,11 +5,13 @@
 
 use core::fmt;
 use core::convert::TryInto;
+use crate::decoder::peek_word;
 use crate::error::ZError;
+use crate::types::ZAddress;
 
 /// Wrapper for fixed-size bytes (bytes1 to bytes32).
 /// The bytes are left-aligned in the 32-byte EVM word.
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ZBytesN<'a, const N: usize>(pub &'a [u8; N]);
 
 impl<'a, const N: usize> ZBytesN<'a, N> {
@@ -36,6 +38,46 @@ impl<'a, const N: usize> ZBytesN<'a, N> {
     pub fn to_bytes(&self) -> [u8; N] {
         *self.0
     }
+
+    /// Copy the bytes out as an owned `[u8; N]`, for any `N`, e.g. to keep a
+    /// selector or hash after the buffer is gone. (`ZBytesN<32>::to_owned`
+    /// returns a [`crate::Bytes32`] instead.)
+    #[inline]
+    pub const fn to_array(&self) -> [u8; N] {
+        *self.0
+    }
+}
+
+impl<'a> ZBytesN<'a, 4> {
+    /// View a `bytes4` as a function or error selector, e.g. to match it
+    /// against a `SELECTOR` constant.
+    #[inline]
+    pub fn as_selector(&self) -> &'a [u8; 4] {
+        self.0
+    }
+}
+
+impl<'a, const N: usize> From<ZBytesN<'a, N>> for [u8; N] {
+    #[inline]
+    fn from(b: ZBytesN<'a, N>) -> Self {
+        *b.0
+    }
+}
+
+/// Reinterprets a `bytes20` as an address; both are the same 20 bytes.
+impl<'a> From<ZBytesN<'a, 20>> for ZAddress<'a> {
+    #[inline]
+    fn from(b: ZBytesN<'a, 20>) -> Self {
+        ZAddress(b.0)
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl<'a, const N: usize> subtle::ConstantTimeEq for ZBytesN<'a, N> {
+    #[inline]
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        self.0[..].ct_eq(&other.0[..])
+    }
 }
 
 impl<'a, const N: usize> fmt::Debug for ZBytesN<'a, N> {
@@ -58,17 +100,40 @@ impl<'a, const N: usize> fmt::Display for ZBytesN<'a, N> {
     }
 }
 
-/// Helper to read a 32-byte word from a slice at a given offset.
-#[inline(always)]
-fn peek_word(data: &[u8], offset: usize) -> Result<&[u8; 32], ZError> {
-    if offset + 32 > data.len() {
-        return Err(ZError::OutOfBounds(offset + 32, data.len()));
+impl<'a, const N: usize> PartialEq<[u8; N]> for ZBytesN<'a, N> {
+    #[inline]
+    fn eq(&self, other: &[u8; N]) -> bool {
+        self.0 == other
+    }
+}
+
+impl<'a, const N: usize> PartialEq<&[u8]> for ZBytesN<'a, N> {
+    #[inline]
+    fn eq(&self, other: &&[u8]) -> bool {
+        self.0[..] == **other
+    }
+}
+
+impl<'a, const N: usize> fmt::LowerHex for ZBytesN<'a, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        crate::types::fmt_hex(self.0, false, f)
     }
-    let slice = &data[offset..offset + 32];
-    let array_ref: &[u8; 32] = slice.try_into().map_err(|_| ZError::Custom("Slice conversion failed"))?;
-    Ok(array_ref)
 }
 
+impl<'a, const N: usize> fmt::UpperHex for ZBytesN<'a, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        crate::types::fmt_hex(self.0, true, f)
+    }
+}
+
+/// Solidity names of `bytes1`..`bytes32`, indexed by `N - 1`.
+const BYTES_N_NAMES: [&str; 32] = [
+    "bytes1", "bytes2", "bytes3", "bytes4", "bytes5", "bytes6", "bytes7", "bytes8",
+    "bytes9", "bytes10", "bytes11", "bytes12", "bytes13", "bytes14", "bytes15", "bytes16",
+    "bytes17", "bytes18", "bytes19", "bytes20", "bytes21", "bytes22", "bytes23", "bytes24",
+    "bytes25", "bytes26", "bytes27", "bytes28", "bytes29", "bytes30", "bytes31", "bytes32",
+];
+
 /// Generic function to read fixed-size bytes (bytesN) from ABI-encoded data.
 /// Fixed-size bytes are left-aligned in the 32-byte word.
 /// The remaining bytes must be zero-padded.
@@ -82,16 +147,28 @@ pub fn read_bytes_n<'a, const N: usize>(data: &'a [u8], offset: usize) -> Result
     
     // Check that trailing bytes are zero (right-padded)
     if word.iter().skip(N).any(|&b| b != 0) {
-        return Err(ZError::Custom("bytesN has non-zero padding bytes"));
+        return Err(ZError::InvalidPadding { offset, ty: BYTES_N_NAMES[N - 1] });
     }
     
     // Get reference to the first N bytes
-    let bytes_slice = &data[offset..offset + N];
-    let bytes_ref: &[u8; N] = bytes_slice.try_into().map_err(|_| ZError::Custom("bytesN slice conversion failed"))?;
+    let bytes_ref: &[u8; N] = word[..N].try_into().map_err(|_| ZError::Custom("bytesN slice conversion failed"))?;
     
     Ok(ZBytesN(bytes_ref))
 }
 
+/// Like [`read_bytes_n`], but ignores non-zero padding bytes.
+#[inline]
+pub fn read_bytes_n_lenient<'a, const N: usize>(data: &'a [u8], offset: usize) -> Result<ZBytesN<'a, N>, ZError> {
+    if N == 0 || N > 32 {
+        return Err(ZError::Custom("bytesN size must be between 1 and 32"));
+    }
+
+    let word = peek_word(data, offset)?;
+    let bytes_ref: &[u8; N] = word[..N].try_into().map_err(|_| ZError::Custom("bytesN slice conversion failed"))?;
+
+    Ok(ZBytesN(bytes_ref))
+}
+
 /// Read bytes1 from ABI-encoded data.
 #[inline]
 pub fn read_bytes1(data: &[u8], offset: usize) -> Result<ZBytesN<'_, 1>, ZError> {
@@ -143,6 +220,8 @@ pub fn read_bytes32(data: &[u8], offset: usize) -> Result<ZBytesN<'_, 32>, ZErro
 }
 
 #[cfg(test)]
+// These unit tests predate the lints and keep their original form.
+#[allow(clippy::needless_range_loop)]
 mod tests {
     use super::*;
     extern crate alloc;
@@ -195,6 +274,9 @@ mod tests {
 
         let result = read_bytes4(&data, 0);
         assert!(result.is_err());
+
+        let lenient = read_bytes_n_lenient::<4>(&data, 0).expect("lenient bytes4");
+        assert_eq!(lenient.0, &[0xde, 0xad, 0xbe, 0xef]);
     }
 
     #[test]
@@ -210,10 +292,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_conversions() {
+        let mut data = [0u8; 32];
+        data[..4].copy_from_slice(&[0xa9, 0x05, 0x9c, 0xbb]);
+        let selector = read_bytes4(&data, 0).unwrap();
+        assert_eq!(selector.as_selector(), &[0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(<[u8; 4]>::from(selector), [0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(selector.to_array(), [0xa9, 0x05, 0x9c, 0xbb]);
+
+        let word = [0x11u8; 32];
+        let mut padded = word;
+        padded[20..].fill(0);
+        let raw = read_bytes20(&padded, 0).unwrap();
+        assert_eq!(raw.to_array(), [0x11u8; 20]);
+        assert_eq!(read_bytes32(&word, 0).unwrap().to_array(), word);
+        let addr: ZAddress = raw.into();
+        assert_eq!(addr, [0x11u8; 20]);
+    }
+
     #[test]
     fn test_out_of_bounds() {
         let data = [0u8; 16]; // Too small for a 32-byte word
         let result = read_bytes4(&data, 0);
         assert!(result.is_err());
+
+        // An offset near usize::MAX is out of bounds, not an overflow.
+        let data = [0u8; 64];
+        assert!(matches!(read_bytes_n_lenient::<4>(&data, usize::MAX - 8), Err(ZError::OutOfBounds(usize::MAX, 64))));
+        assert!(matches!(read_bytes_n::<4>(&data, usize::MAX - 8), Err(ZError::OutOfBounds(usize::MAX, 64))));
     }
 }
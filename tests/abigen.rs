@@ -0,0 +1,67 @@
+use zabi_rs::{abigen, ZDecode};
+
+mod common;
+use common::word;
+
+abigen!(Token, "tests/abi/token.json");
+
+#[test]
+fn test_abigen_selectors() {
+    assert_eq!(Token::transferCall::SELECTOR, [0xa9, 0x05, 0x9c, 0xbb]);
+    assert_eq!(Token::balanceOfCall::SELECTOR, [0x70, 0xa0, 0x82, 0x31]);
+    // Overloads keep their Solidity signature but get suffixed Rust names.
+    assert_eq!(
+        Token::safeTransferFromCall::SELECTOR,
+        [0x42, 0x84, 0x2e, 0x0e]
+    );
+    assert_eq!(
+        Token::safeTransferFrom_1Call::SELECTOR,
+        [0xb8, 0x8d, 0x4f, 0xde]
+    );
+    assert_eq!(
+        Token::submitCall::SIGNATURE,
+        "submit((address,uint256,uint8))"
+    );
+    assert_eq!(Token::Transfer::TOPIC0[..4], [0xdd, 0xf2, 0x52, 0xad]);
+}
+
+#[test]
+fn test_abigen_decode() {
+    let mut calldata = Token::submitCall::SELECTOR.to_vec();
+    calldata.extend_from_slice(&word(0xAA));
+    calldata.extend_from_slice(&word(10));
+    calldata.extend_from_slice(&word(3));
+
+    match Token::decode_call(&calldata).expect("failed to decode submit") {
+        Token::Call::submit(call) => {
+            assert_eq!(call.order.maker.as_bytes()[19], 0xAA);
+            assert_eq!(call.order.r#type, 3);
+        }
+        other => panic!("unexpected call {:?}", other),
+    }
+
+    let data = word(9);
+    let ret = Token::balanceOfReturn::decode(&data, 0).expect("failed to decode return");
+    assert_eq!(ret._0.to_u64(), Some(9));
+}
+
+abigen!(Orders, "tests/abi/orders.json");
+
+#[test]
+fn test_abigen_struct_name_collision() {
+    // `Exchange.Order` is reused as-is; `Auction.Order` has other members
+    // and falls back to its qualified name.
+    assert_eq!(Orders::fillCall::SIGNATURE, "fill((address,uint256))");
+    assert_eq!(Orders::cancelCall::SIGNATURE, "cancel((address,uint256))");
+    assert_eq!(Orders::bidCall::SIGNATURE, "bid((uint64,address,uint256))");
+
+    let data = [word(7), word(0xAA), word(100)].concat();
+    let order =
+        <Orders::Auction_Order as ZDecode>::decode(&data, 0).expect("failed to decode order");
+    assert_eq!(order.lot, 7);
+    assert_eq!(order.bidder.as_bytes()[19], 0xAA);
+
+    let order = <Orders::Order as ZDecode>::decode(&data[32..], 0).expect("failed to decode order");
+    assert_eq!(order.maker.as_bytes()[19], 0xAA);
+    assert_eq!(order.amount.to_u64(), Some(100));
+}
@@ -0,0 +1,8 @@
+//! Fixtures shared by the integration tests.
+
+/// A word whose last byte is `last`.
+pub fn word(last: u8) -> [u8; 32] {
+    let mut w = [0u8; 32];
+    w[31] = last;
+    w
+}
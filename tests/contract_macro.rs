@@ -0,0 +1,79 @@
+use zabi_rs::{contract, ZError, ZEventLog};
+
+mod common;
+use common::word;
+
+contract! {
+    pub Token {
+        function transfer(address to, uint256 amount) external returns (bool);
+        function pause() external;
+        event Transfer(address indexed from, address indexed to, uint256 value);
+        event Paused(bool paused);
+        error InsufficientBalance(uint256 available, uint256 required);
+        error Unauthorized();
+    }
+}
+
+#[test]
+fn test_contract_decode_call() {
+    let mut calldata = Token::transferCall::SELECTOR.to_vec();
+    calldata.extend_from_slice(&word(0xAA));
+    calldata.extend_from_slice(&word(5));
+
+    match Token::decode_call(&calldata).expect("failed to decode call") {
+        Token::Call::transfer(call) => {
+            assert_eq!(call.to.as_bytes()[19], 0xAA);
+            assert_eq!(call.amount.to_u64(), Some(5));
+        }
+        other => panic!("unexpected call {:?}", other),
+    }
+
+    let pause = Token::pauseCall::SELECTOR;
+    assert!(matches!(
+        Token::decode_call(&pause),
+        Ok(Token::Call::pause(_))
+    ));
+    assert!(matches!(
+        Token::decode_call(&[0, 0, 0, 0]),
+        Err(ZError::UnknownSelector([0, 0, 0, 0]))
+    ));
+}
+
+#[test]
+fn test_contract_decode_event() {
+    let topics = [&Token::Paused::TOPIC0];
+    let data = word(1);
+    match Token::decode_event(&ZEventLog::new(&topics, &data)).expect("failed to decode event") {
+        Token::Event::Paused(event) => assert!(event.paused.as_bool()),
+        other => panic!("unexpected event {:?}", other),
+    }
+
+    let unknown = [0u8; 32];
+    let topics = [&unknown];
+    assert!(
+        matches!(Token::decode_event(&ZEventLog::new(&topics, &data)), Err(ZError::UnknownTopic(topic)) if topic == unknown)
+    );
+}
+
+#[test]
+fn test_contract_decode_error() {
+    let mut revert = Token::InsufficientBalance::SELECTOR.to_vec();
+    revert.extend_from_slice(&word(1));
+    revert.extend_from_slice(&word(2));
+
+    match Token::decode_error(&revert).expect("failed to decode error") {
+        Token::Error::InsufficientBalance(err) => {
+            assert_eq!(err.available.to_u64(), Some(1));
+            assert_eq!(err.required.to_u64(), Some(2));
+        }
+        other => panic!("unexpected error {:?}", other),
+    }
+    assert!(matches!(
+        Token::decode_error(&Token::Unauthorized::SELECTOR),
+        Ok(Token::Error::Unauthorized(_))
+    ));
+    assert!(matches!(
+        Token::decode_error(&[1, 2, 3, 4]),
+        Err(ZError::UnknownSelector([1, 2, 3, 4]))
+    ));
+}
@@ -1,4 +1,6 @@
-use zabi_rs::{ZDecode, ZU256, ZAddress, ZBool, ZArray, ZString};
+#![allow(clippy::bool_assert_comparison)]
+
+use zabi_rs::{decode_tuple, Address, I256, IntoOwned, ZDecode, ZU256, ZInt256, ZAddress, ZBool, ZArray, ZString, ZBytesN, U256};
 
 #[derive(Debug, ZDecode, PartialEq)]
 struct InnerStruct<'a> {
@@ -51,6 +53,73 @@ fn test_derive_and_nested() {
     assert_eq!(decoded.message.as_str(), "Hello");
 }
 
+#[derive(Debug, ZDecode)]
+struct FlatOuter<'a> {
+    addr: ZAddress<'a>,
+    #[zabi(flatten)]
+    inner: InnerStruct<'a>,
+    message: ZString<'a>,
+}
+
+#[test]
+fn test_derive_flatten() {
+    assert_eq!(FlatOuter::SOL_TYPE, "(address,uint256,bool,string)");
+    assert_eq!(FlatOuter::SIGNATURE, "FlatOuter(address,uint256,bool,string)");
+    assert_eq!(FlatOuter::HEAD_SIZE, OuterStruct::HEAD_SIZE);
+
+    // Same wire layout as `OuterStruct`.
+    let mut data = [0u8; 32 * 6];
+    data[31] = 0xAA;
+    data[63] = 42;
+    data[95] = 1;
+    data[127] = 128;
+    data[159] = 5;
+    data[160..165].copy_from_slice(b"Hello");
+
+    let flat = FlatOuter::decode(&data, 0).expect("failed to decode FlatOuter");
+    let nested = OuterStruct::decode(&data, 0).unwrap();
+    assert_eq!(flat.inner, nested.inner);
+    assert_eq!(flat.addr, nested.addr);
+    assert_eq!(flat.message.as_str(), "Hello");
+}
+
+mod sol_types {
+    zabi_rs::sol! {
+        struct Tagged {
+            uint8 id;
+            string tag;
+        }
+    }
+}
+
+#[derive(Debug, ZDecode)]
+struct FlatTagged<'a> {
+    #[zabi(flatten)]
+    tagged: sol_types::Tagged<'a>,
+    amount: ZU256<'a>,
+}
+
+#[test]
+fn test_derive_flatten_dynamic_tuple() {
+    // As a member, the sol! struct is one offset word; flattened, its
+    // members sit inline and `tag` resolves against the outer encoding.
+    assert_eq!(<sol_types::Tagged as ZDecode>::HEAD_SIZE, 32);
+    assert_eq!(FlatTagged::SOL_TYPE, "(uint8,string,uint256)");
+    assert_eq!(FlatTagged::HEAD_SIZE, 96);
+
+    let mut data = [0u8; 32 * 5];
+    data[31] = 7;
+    data[63] = 96;
+    data[95] = 42;
+    data[127] = 2;
+    data[128..130].copy_from_slice(b"hi");
+
+    let flat = FlatTagged::decode(&data, 0).expect("failed to decode FlatTagged");
+    assert_eq!(flat.tagged.id, 7);
+    assert_eq!(flat.tagged.tag.as_str(), "hi");
+    assert_eq!(flat.amount.as_bytes()[31], 42);
+}
+
 #[test]
 fn test_tuple_decode() {
     let mut data = [0u8; 96];
@@ -63,3 +132,534 @@ fn test_tuple_decode() {
     assert_eq!(b.as_bytes()[31], 2);
     assert_eq!(c.as_bytes()[31], 3);
 }
+
+#[test]
+fn test_tuple_dynamic_members() {
+    const { assert!(<(ZString, u8)>::IS_DYNAMIC) };
+    assert_eq!(<(ZString, u8)>::HEAD_SIZE, 32);
+    const { assert!(!<(ZU256, ZBool)>::IS_DYNAMIC) };
+    assert_eq!(<(ZU256, ZBool)>::HEAD_SIZE, 64);
+
+    // (uint8, (string, uint8), string)
+    let mut data = [0u8; 288];
+    data[31] = 7;
+    data[63] = 96; // offset to the inner tuple
+    data[95] = 224; // offset to the outer string
+    data[127] = 64; // inner string offset, relative to the inner tuple
+    data[159] = 5;
+    data[191] = 2;
+    data[192..194].copy_from_slice(b"hi");
+    data[255] = 3;
+    data[256..259].copy_from_slice(b"abc");
+
+    let (a, (s, b), t) = decode_tuple!(&data, u8, (ZString, u8), ZString).expect("failed to decode tuple");
+    assert_eq!(a, 7);
+    assert_eq!(s.as_str(), "hi");
+    assert_eq!(b, 5);
+    assert_eq!(t.as_str(), "abc");
+
+    // As a member, the dynamic tuple sits behind an offset word.
+    let mut nested = [0u8; 32].to_vec();
+    nested[31] = 32;
+    nested.extend_from_slice(&data);
+    let (a, (s, _), t) = <(u8, (ZString, u8), ZString)>::decode_member_lenient(&nested, 0).expect("failed to decode nested");
+    assert_eq!(a, 7);
+    assert_eq!(s.as_str(), "hi");
+    assert_eq!(t.as_str(), "abc");
+
+    data[127] = 0xFF;
+    assert!(decode_tuple!(&data, u8, (ZString, u8), ZString).is_err());
+}
+
+#[test]
+fn test_tuple_top_level_dynamic() {
+    // (uint256, string) as a parameter list: the tuple starts at data[0].
+    let mut data = [0u8; 128];
+    data[31] = 9;
+    data[63] = 64;
+    data[95] = 2;
+    data[96..98].copy_from_slice(b"ok");
+
+    let (n, s) = <(ZU256, ZString)>::decode(&data, 0).expect("failed to decode params");
+    assert_eq!(n.as_bytes()[31], 9);
+    assert_eq!(s.as_str(), "ok");
+    let (_, s) = <(ZU256, ZString)>::decode_params(&data).unwrap();
+    assert_eq!(s.as_str(), "ok");
+}
+
+#[test]
+fn test_tuple_offset_high_bytes() {
+    // A dynamic tuple behind an offset word of 2^192 + 32 must not be read at 32.
+    let mut data = [0u8; 96];
+    data[7] = 1;
+    data[31] = 32;
+    data[63] = 32;
+    assert!(matches!(
+        zabi_rs::decoder::read_tuple_body(&data, 0, true),
+        Err(zabi_rs::ZError::OffsetOutOfRange { at: 0, .. })
+    ));
+    data[7] = 0;
+    assert_eq!(zabi_rs::decoder::read_tuple_body(&data, 0, true).unwrap().len(), 64);
+}
+
+#[test]
+fn test_tuple_arity_16() {
+    type Wide = (u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u16);
+    let mut data = [0u8; 512];
+    data[511] = 16;
+    assert_eq!(Wide::HEAD_SIZE, 512);
+    let wide = Wide::decode(&data, 0).unwrap();
+    assert_eq!(wide.15, 16);
+    assert!(Wide::SOL_TYPE.ends_with(",uint8,uint16)"));
+}
+
+#[allow(dead_code)]
+#[derive(Debug, ZDecode)]
+struct WithArray<'a> {
+    id: u64,
+    tag: ZBytesN<'a, 4>,
+    values: ZArray<'a, ZU256<'a>>,
+}
+
+#[test]
+fn test_derive_signature() {
+    assert_eq!(<InnerStruct as ZDecode>::SOL_TYPE, "(uint256,bool)");
+    assert_eq!(<OuterStruct as ZDecode>::SOL_TYPE, "(address,(uint256,bool),string)");
+    assert_eq!(OuterStruct::SIGNATURE, "OuterStruct(address,(uint256,bool),string)");
+    assert_eq!(WithArray::SIGNATURE, "WithArray(uint64,bytes4,uint256[])");
+    assert_eq!(<(ZU256, ZAddress, ZBool)>::SOL_TYPE, "(uint256,address,bool)");
+}
+
+#[derive(Debug, ZDecode)]
+struct Pair<'a, T> {
+    a: T,
+    b: ZU256<'a>,
+}
+
+#[derive(Debug, ZDecode, PartialEq)]
+struct Flags {
+    enabled: ZBool,
+    count: u32,
+}
+
+#[test]
+fn test_derive_generic_struct() {
+    let mut data = [0u8; 128];
+    data[31] = 7;
+    data[63] = 1;
+    data[95] = 1;
+    data[127] = 9;
+
+    let pair: Pair<u64> = Pair::decode(&data, 0).expect("failed to decode Pair<u64>");
+    assert_eq!(pair.a, 7);
+    assert_eq!(pair.b.as_bytes()[31], 1);
+    assert_eq!(Pair::<u64>::SIGNATURE, "Pair(uint64,uint256)");
+
+    let nested: Pair<Flags> = Pair::decode(&data, 32).expect("failed to decode Pair<Flags>");
+    assert_eq!(nested.a, Flags { enabled: ZBool(true), count: 1 });
+    assert_eq!(nested.b.as_bytes()[31], 9);
+    assert_eq!(<Pair<Flags> as ZDecode>::HEAD_SIZE, 96);
+    assert_eq!(<Pair<Flags> as ZDecode>::SOL_TYPE, "((bool,uint32),uint256)");
+}
+
+#[derive(Debug, ZDecode)]
+struct Forced<'a> {
+    #[zabi(as = "bytes32")]
+    hash: &'a [u8; 32],
+    #[zabi(as = "uint64")]
+    amount: u64,
+    #[zabi(as = "address")]
+    owner: &'a [u8; 20],
+    #[zabi(as = "string")]
+    name: &'a str,
+}
+
+#[test]
+fn test_derive_forced_abi_type() {
+    let mut data = [0u8; 32 * 6];
+    data[0] = 0xde; // bytes32, left-aligned
+    data[63] = 5; // uint64
+    data[95] = 0xAA; // address
+    data[127] = 128; // offset to string
+    data[159] = 2; // string length
+    data[160..162].copy_from_slice(b"ok");
+
+    let decoded = Forced::decode(&data, 0).expect("failed to decode Forced");
+    assert_eq!(decoded.hash[0], 0xde);
+    assert_eq!(decoded.amount, 5);
+    assert_eq!(decoded.owner[19], 0xAA);
+    assert_eq!(decoded.name, "ok");
+    assert_eq!(Forced::SIGNATURE, "Forced(bytes32,uint64,address,string)");
+
+    // The forced uint64 reader rejects dirty high bits.
+    let mut bad = data;
+    bad[32] = 1;
+    assert!(Forced::decode(&bad, 0).is_err());
+}
+
+#[derive(Debug, ZDecode)]
+struct ForcedWords<'a> {
+    #[zabi(as = "uint256")]
+    amount: ZU256<'a>,
+    #[zabi(as = "int256")]
+    delta: ZInt256<'a>,
+    #[zabi(as = "uint24")]
+    fee: ZU256<'a>,
+}
+
+#[test]
+fn test_derive_forced_full_words() {
+    let mut data = [0u8; 96];
+    data[0] = 0x80; // uint256 uses every bit
+    data[32..64].fill(0xff); // int256 -1
+    data[95] = 30;
+
+    let decoded = ForcedWords::decode(&data, 0).expect("failed to decode ForcedWords");
+    assert_eq!(decoded.amount.0[0], 0x80);
+    assert_eq!(decoded.delta.to_i64(), Some(-1));
+    assert_eq!(decoded.fee.to_u64(), Some(30));
+    assert_eq!(ForcedWords::SIGNATURE, "ForcedWords(uint256,int256,uint24)");
+}
+
+#[derive(Debug, ZDecode)]
+struct PartlyLenient {
+    #[zabi(lenient)]
+    flag: ZBool,
+    #[zabi(lenient, as = "uint8")]
+    small: u8,
+    strict: u16,
+}
+
+#[derive(Debug, ZDecode)]
+#[zabi(lenient)]
+struct AllLenient {
+    flag: ZBool,
+    inner: Flags,
+}
+
+#[test]
+fn test_derive_lenient() {
+    let mut data = [0u8; 96];
+    data[31] = 2; // non-canonical bool
+    data[62] = 0xff; // dirty uint8 high bits
+    data[63] = 7;
+    data[95] = 3;
+
+    let decoded = PartlyLenient::decode(&data, 0).expect("lenient fields should decode");
+    assert!(decoded.flag.as_bool());
+    assert_eq!(decoded.small, 7);
+    assert_eq!(decoded.strict, 3);
+
+    // The strict field still rejects dirty padding.
+    data[93] = 1;
+    assert!(PartlyLenient::decode(&data, 0).is_err());
+    assert_eq!(PartlyLenient::decode_lenient(&data, 0).unwrap().strict, 3);
+
+    // Struct-level lenient applies to nested fields as well.
+    let decoded = AllLenient::decode(&data, 0).expect("lenient struct should decode");
+    assert!(decoded.flag.as_bool());
+    assert!(decoded.inner.enabled.as_bool());
+    assert_eq!(decoded.inner.count, 0x0001_0003);
+}
+
+#[derive(zabi_rs::ZView)]
+#[allow(dead_code)]
+struct Record<'a> {
+    owner: ZAddress<'a>,
+    inner: InnerStruct<'a>,
+    #[zabi(as = "uint64")]
+    nonce: u64,
+    note: ZString<'a>,
+}
+
+#[test]
+fn test_derive_view() {
+    let mut data = [0u8; 32 * 7];
+    data[31] = 0xAA; // owner
+    data[63] = 42; // inner.val
+    data[95] = 1; // inner.flag
+    data[127] = 9; // nonce
+    data[159] = 160; // offset to note
+    data[191] = 2; // note length
+    data[192..194].copy_from_slice(b"hi");
+
+    assert_eq!(RecordView::HEAD_SIZE, 160);
+    let view = RecordView::new(&data, 0).expect("failed to create view");
+    assert_eq!(view.nonce().unwrap(), 9);
+    assert_eq!(view.inner().unwrap().val.as_bytes()[31], 42);
+    assert_eq!(view.note().unwrap().as_str(), "hi");
+    assert_eq!(view.owner().unwrap().as_bytes()[19], 0xAA);
+
+    // Fields are decoded independently: a bad flag only fails its own getter.
+    data[95] = 2;
+    let view = RecordView::new(&data, 0).unwrap();
+    assert!(view.inner().is_err());
+    assert_eq!(view.nonce().unwrap(), 9);
+    assert!(RecordView::new(&data[..64], 0).is_err());
+    assert!(matches!(RecordView::new(&data, usize::MAX), Err(zabi_rs::ZError::OutOfBounds(usize::MAX, _))));
+}
+
+#[derive(ZDecode, Debug)]
+#[zabi(outputs(balance: ZU256<'a>, memo: ZString<'a>, #[zabi(as = "uint8")] decimals: u8))]
+#[zabi(outputs_derive(Debug, Clone, Copy))]
+struct BalanceOf<'a> {
+    pub owner: ZAddress<'a>,
+}
+
+// `InnerStruct` is not `Copy`, and the outputs fields stay private.
+#[allow(dead_code)]
+#[derive(ZDecode)]
+#[zabi(outputs(inner: InnerStruct<'a>, pub count: u64))]
+struct Stats<'a> {
+    pub owner: ZAddress<'a>,
+}
+
+#[allow(dead_code)]
+#[derive(ZDecode, Debug)]
+#[zabi(outputs(ok: ZBool))]
+struct Approve<'a> {
+    pub spender: ZAddress<'a>,
+    pub amount: u64,
+}
+
+#[test]
+fn test_derive_outputs() {
+    let mut args = [0u8; 32];
+    args[31] = 0x11;
+    let call = BalanceOf::decode(&args, 0).unwrap();
+    assert_eq!(call.owner.as_bytes()[19], 0x11);
+
+    let mut ret = [0u8; 32 * 5];
+    ret[31] = 100; // balance
+    ret[63] = 96; // offset to memo
+    ret[95] = 18; // decimals
+    ret[127] = 2; // memo length
+    ret[128..130].copy_from_slice(b"ok");
+    let out: BalanceOfOutputs = BalanceOf::decode_returns(&ret).unwrap();
+    assert_eq!(out.balance.as_bytes()[31], 100);
+    assert_eq!(out.memo.as_str(), "ok");
+    assert_eq!(out.decimals, 18);
+    assert_eq!(BalanceOfOutputs::HEAD_SIZE, 96);
+    assert_eq!(BalanceOfOutputs::SIGNATURE, "BalanceOfOutputs(uint256,string,uint8)");
+    let copy = out;
+    assert!(format!("{:?}", copy).contains("decimals: 18"));
+
+    let mut ret = [0u8; 96];
+    ret[31] = 7;
+    ret[63] = 1;
+    ret[95] = 3;
+    let stats: StatsOutputs = Stats::decode_returns(&ret).unwrap();
+    assert_eq!(stats.inner, InnerStruct::decode(&ret, 0).unwrap());
+    assert_eq!(stats.count, 3);
+
+    let mut ret = [0u8; 32];
+    ret[31] = 1;
+    let out: ApproveOutputs = Approve::decode_returns(&ret).unwrap();
+    assert!(out.ok.0);
+    assert!(Approve::decode_returns(&ret[..16]).is_err());
+}
+
+fn non_zero_address(addr: &ZAddress) -> Result<(), zabi_rs::ZError> {
+    if addr.as_bytes().iter().all(|&b| b == 0) {
+        return Err(zabi_rs::ZError::Custom("zero address"));
+    }
+    Ok(())
+}
+
+fn below_cap(amount: &u64) -> Result<(), zabi_rs::ZError> {
+    if *amount > 1_000 {
+        return Err(zabi_rs::ZError::Custom("amount above cap"));
+    }
+    Ok(())
+}
+
+#[derive(ZDecode, Debug)]
+#[zabi(transparent)]
+struct Recipient<'a>(#[zabi(validate = "non_zero_address")] ZAddress<'a>);
+
+#[derive(ZDecode, Debug)]
+struct Payout<'a> {
+    pub to: Recipient<'a>,
+    #[zabi(validate = "below_cap")]
+    pub amount: u64,
+}
+
+#[test]
+fn test_derive_validate() {
+    assert_eq!(Recipient::SOL_TYPE, "address");
+    assert_eq!(Recipient::SIGNATURE, "Recipient(address)");
+    assert_eq!(Payout::SOL_TYPE, "(address,uint64)");
+
+    let mut data = [0u8; 64];
+    data[31] = 0x01;
+    data[63] = 100;
+    let payout = Payout::decode(&data, 0).expect("valid payout");
+    assert_eq!(payout.to.0.as_bytes()[19], 0x01);
+    assert_eq!(payout.amount, 100);
+
+    data[62] = 0x10; // 4196 > cap
+    let err = Payout::decode(&data, 0).unwrap_err();
+    assert!(matches!(err.root_cause(), zabi_rs::ZError::Custom("amount above cap")));
+
+    data[62] = 0;
+    data[31] = 0;
+    let err = Payout::decode(&data, 0).unwrap_err();
+    assert!(matches!(err.root_cause(), zabi_rs::ZError::Custom("zero address")));
+    let err = Payout::decode_lenient(&data, 0).unwrap_err();
+    assert!(matches!(err.root_cause(), zabi_rs::ZError::Custom("zero address")));
+}
+
+#[cfg(feature = "alloc")]
+#[derive(ZDecode, Debug)]
+struct Batch<'a> {
+    pub nonce: u64,
+    pub payouts: ZArray<'a, u8>,
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_error_chain() {
+    let mut data = [0u8; 160];
+    data[63] = 64; // payouts offset
+    data[95] = 2; // length
+    data[127] = 7;
+    data[158] = 1; // dirty uint8 high bits in element 1
+    let batch = Batch::decode(&data, 0).unwrap();
+    assert_eq!(batch.nonce, 0);
+    assert_eq!(batch.payouts.get(0).unwrap(), 7);
+    let err = batch.payouts.get(1).unwrap_err();
+    assert!(matches!(err, zabi_rs::ZError::Field { index: 1, .. }));
+    assert!(matches!(err.root_cause(), zabi_rs::ZError::InvalidPadding { offset: 128, ty: "uint8" }));
+    assert_eq!(err.word_offset(), Some(128));
+    assert!(std::error::Error::source(&err).is_some());
+
+    data[0] = 0x01; // dirty nonce high bits
+    let err = Batch::decode(&data, 0).unwrap_err();
+    #[cfg(not(feature = "tiny-errors"))]
+    assert_eq!(err.to_string(), "In field 0: Invalid uint64 padding in word at offset 0");
+    assert_eq!(err.code(), 9);
+
+    // A transparent newtype does not add a level of its own.
+    let mut data = [0u8; 64];
+    data[63] = 1;
+    let err = Payout::decode(&data, 0).unwrap_err();
+    match &err {
+        zabi_rs::ZError::Field { index: 0, source } => assert!(matches!(**source, zabi_rs::ZError::Custom(_))),
+        other => panic!("unexpected {:?}", other),
+    }
+    #[cfg(not(feature = "tiny-errors"))]
+    assert_eq!(err.to_string(), "In field 0: Error: zero address");
+}
+
+#[derive(Debug, ZDecode)]
+struct Order<'a> {
+    id: ZBytesN<'a, 8>,
+    salt: ZBytesN<'a, 32>,
+    #[zabi(lenient)]
+    flags: ZBytesN<'a, 1>,
+}
+
+#[test]
+fn test_derive_fixed_bytes() {
+    assert_eq!(<Order as ZDecode>::SOL_TYPE, "(bytes8,bytes32,bytes1)");
+    let mut data = [0u8; 96];
+    data[..8].copy_from_slice(b"order-01");
+    data[32..64].fill(0x5a);
+    data[64] = 0x80;
+    data[95] = 0xff; // dirty padding, tolerated by the lenient field
+    let order = Order::decode(&data, 0).unwrap();
+    assert_eq!(order.id, *b"order-01");
+    assert_eq!(order.salt, [0x5a; 32]);
+    assert_eq!(order.flags.0, &[0x80]);
+
+    data[8] = 1; // dirty bytes8 padding
+    assert!(matches!(
+        Order::decode(&data, 0).unwrap_err().root_cause(),
+        zabi_rs::ZError::InvalidPadding { offset: 0, ty: "bytes8" }
+    ));
+}
+
+#[derive(ZDecode)]
+#[zabi(owned(Debug, Clone, PartialEq))]
+struct Leg<'a> {
+    /// Pool the leg trades through.
+    pub pool: ZAddress<'a>,
+    pub zero_for_one: ZBool,
+}
+
+#[derive(ZDecode)]
+#[zabi(owned(Debug, PartialEq))]
+struct Swap<'a> {
+    pub leg: Leg<'a>,
+    pub amount: ZU256<'a>,
+    pub tag: ZBytesN<'a, 4>,
+    #[zabi(as = "uint8")]
+    pub decimals: u8,
+}
+
+#[derive(ZDecode)]
+#[zabi(owned)]
+struct Quote<'a>(ZInt256<'a>, u64);
+
+#[test]
+fn test_derive_owned() {
+    let owned: SwapOwned = {
+        let mut data = vec![0u8; 32 * 5];
+        data[31] = 0xaa;
+        data[63] = 1;
+        data[95] = 100;
+        data[96..100].copy_from_slice(b"swap");
+        data[159] = 18;
+        Swap::decode(&data, 0).unwrap().into_owned().unwrap()
+    };
+    assert_eq!(
+        owned,
+        SwapOwned {
+            leg: LegOwned { pool: Address(hex_address(0xaa)), zero_for_one: true },
+            amount: U256::from(100u64),
+            tag: *b"swap",
+            decimals: 18,
+        }
+    );
+
+    let mut data = [0u8; 64];
+    data[..32].fill(0xff);
+    data[63] = 3;
+    let QuoteOwned(price, size) = Quote::decode(&data, 0).unwrap().into_owned().unwrap();
+    assert_eq!(price, I256([0xff; 32]));
+    assert_eq!(size, 3);
+}
+
+fn hex_address(last: u8) -> [u8; 20] {
+    let mut addr = [0u8; 20];
+    addr[19] = last;
+    addr
+}
+
+#[cfg(feature = "alloc")]
+#[derive(ZDecode)]
+#[zabi(owned(Debug))]
+struct Route<'a> {
+    pub memo: ZString<'a>,
+    pub legs: ZArray<'a, Leg<'a>>,
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_derive_owned_dynamic() {
+    let mut data = [0u8; 32 * 7];
+    data[31] = 64; // offset to memo
+    data[63] = 128; // offset to legs
+    data[95] = 2; // memo length
+    data[96..98].copy_from_slice(b"hi");
+    data[159] = 1; // one leg
+    data[191] = 0xbb;
+    data[223] = 2; // invalid bool
+    let err = Route::decode(&data, 0).unwrap().into_owned().unwrap_err();
+    assert!(matches!(err.root_cause(), zabi_rs::ZError::InvalidBool { .. }));
+
+    data[223] = 0;
+    let route = Route::decode(&data, 0).unwrap().into_owned().unwrap();
+    assert_eq!(route.memo, "hi");
+    assert_eq!(route.legs, [LegOwned { pool: Address(hex_address(0xbb)), zero_for_one: false }]);
+}
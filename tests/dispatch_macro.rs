@@ -0,0 +1,74 @@
+use zabi_rs::{dispatch, sol, ZDecode, ZError, ZU256};
+
+sol! {
+    function balanceOf(address owner) external returns (uint256);
+}
+
+#[derive(Debug, PartialEq)]
+enum Routed {
+    Transfer(u8),
+    Approve(usize),
+    BalanceOf(u8),
+    Raw(usize),
+}
+
+fn handle_transfer(params: &[u8]) -> Result<Routed, ZError> {
+    let (_, amount) = <(ZU256, ZU256)>::decode(params, 0)?;
+    Ok(Routed::Transfer(amount.as_bytes()[31]))
+}
+
+fn route(calldata: &[u8]) -> Result<Routed, ZError> {
+    dispatch!(calldata, {
+        "transfer(address, uint256)" => handle_transfer,
+        0x095ea7b3 => |params: &[u8]| Ok(Routed::Approve(params.len())),
+        balanceOfCall::SELECTOR => |params| {
+            let call = balanceOfCall::decode(params, 0)?;
+            Ok(Routed::BalanceOf(call.owner.as_bytes()[19]))
+        },
+    })
+}
+
+fn with_calldata(selector: [u8; 4], words: &[u8]) -> Vec<u8> {
+    let mut calldata = selector.to_vec();
+    for &w in words {
+        let mut word = [0u8; 32];
+        word[31] = w;
+        calldata.extend_from_slice(&word);
+    }
+    calldata
+}
+
+#[test]
+fn test_dispatch_routes_by_selector() {
+    let transfer = with_calldata([0xa9, 0x05, 0x9c, 0xbb], &[1, 7]);
+    assert_eq!(route(&transfer).unwrap(), Routed::Transfer(7));
+
+    let approve = with_calldata([0x09, 0x5e, 0xa7, 0xb3], &[1, 2]);
+    assert_eq!(route(&approve).unwrap(), Routed::Approve(64));
+
+    let balance = with_calldata(balanceOfCall::SELECTOR, &[0x42]);
+    assert_eq!(route(&balance).unwrap(), Routed::BalanceOf(0x42));
+
+    // Handler errors propagate unchanged.
+    assert!(route(&transfer[..36]).is_err());
+    assert!(matches!(
+        route(&[0, 0, 0, 0]),
+        Err(ZError::UnknownSelector([0, 0, 0, 0]))
+    ));
+    assert!(matches!(route(&[0xa9]), Err(ZError::OutOfBounds(4, 1))));
+}
+
+#[test]
+fn test_dispatch_fallback() {
+    let route = |calldata: &[u8]| -> Result<Routed, ZError> {
+        dispatch!(calldata, {
+            [0xa9, 0x05, 0x9c, 0xbb] => |_| Ok(Routed::Transfer(0)),
+            _ => |calldata: &[u8]| Ok(Routed::Raw(calldata.len())),
+        })
+    };
+    assert_eq!(
+        route(&[0xa9, 0x05, 0x9c, 0xbb]).unwrap(),
+        Routed::Transfer(0)
+    );
+    assert_eq!(route(&[1, 2, 3, 4, 5]).unwrap(), Routed::Raw(5));
+}
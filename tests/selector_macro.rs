@@ -0,0 +1,30 @@
+use zabi_rs::{event_topic, selector, sol};
+
+const APPROVE: [u8; 4] = selector!("approve(address,uint256)");
+const TRANSFER_TOPIC: [u8; 32] = event_topic!("Transfer(address,address,uint256)");
+
+sol! {
+    function transfer(address to, uint256 amount) external returns (bool);
+    event Transfer(address indexed from, address indexed to, uint256 value);
+}
+
+#[test]
+fn test_selector_macro() {
+    assert_eq!(APPROVE, [0x09, 0x5e, 0xa7, 0xb3]);
+    assert_eq!(
+        selector!("transfer(address, uint256)"),
+        transferCall::SELECTOR
+    );
+
+    match [0xa9, 0x05, 0x9c, 0xbb] {
+        selector!("transfer(address,uint256)") => {}
+        _ => panic!("selector pattern did not match"),
+    }
+}
+
+#[test]
+fn test_event_topic_macro() {
+    assert_eq!(TRANSFER_TOPIC[..4], [0xdd, 0xf2, 0x52, 0xad]);
+    assert_eq!(TRANSFER_TOPIC[28..], [0xf5, 0x23, 0xb3, 0xef]);
+    assert_eq!(TRANSFER_TOPIC, Transfer::TOPIC0);
+}
@@ -0,0 +1,212 @@
+use zabi_rs::{sol, ZDecode, ZError, ZEventLog};
+
+mod common;
+use common::word;
+
+sol! {
+    struct Order {
+        address maker;
+        uint256 amount;
+        uint64 expiry;
+    }
+
+    struct Limits {
+        bool enabled;
+        uint32 count;
+    }
+
+    function transfer(address to, uint256 amount) external returns (bool);
+    function fill(Order order, bytes signature) external;
+    function setLimits(Limits limits) external;
+
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Memo(string indexed tag, string message);
+    event Deposit(address indexed from, uint256 indexed id, uint256 amount) anonymous;
+
+    struct Tick {
+        uint24 fee;
+        int40 delta;
+    }
+
+    function setFee(uint24 fee, Tick tick) external;
+    event Swap(int40 indexed delta, uint160 price);
+
+    function route(address[2] path, string[2] labels, uint8 hops) external;
+}
+
+#[test]
+fn test_sol_function_call() {
+    assert_eq!(transferCall::SIGNATURE, "transfer(address,uint256)");
+    assert_eq!(transferCall::SELECTOR, [0xa9, 0x05, 0x9c, 0xbb]);
+    assert_eq!(fillCall::SIGNATURE, "fill((address,uint256,uint64),bytes)");
+
+    let mut calldata = Vec::new();
+    calldata.extend_from_slice(&transferCall::SELECTOR);
+    calldata.extend_from_slice(&word(0xAA));
+    calldata.extend_from_slice(&word(42));
+
+    let call = transferCall::decode_calldata(&calldata).expect("failed to decode transfer");
+    assert_eq!(call.to.as_bytes()[19], 0xAA);
+    assert_eq!(call.amount.to_u64(), Some(42));
+
+    calldata[0] = 0;
+    assert!(matches!(
+        transferCall::decode_calldata(&calldata),
+        Err(ZError::SelectorMismatch {
+            expected: [0xa9, 0x05, 0x9c, 0xbb],
+            found: [0x00, 0x05, 0x9c, 0xbb]
+        })
+    ));
+
+    let ret = transferReturn::decode_returns(&word(1)).expect("failed to decode return");
+    assert!(ret._0.as_bool());
+}
+
+#[test]
+fn test_sol_struct() {
+    let mut data = Vec::new();
+    data.extend_from_slice(&word(0xBB));
+    data.extend_from_slice(&word(7));
+    data.extend_from_slice(&word(9));
+
+    let order = Order::decode(&data, 0).expect("failed to decode Order");
+    assert_eq!(order.maker.as_bytes()[19], 0xBB);
+    assert_eq!(order.expiry, 9);
+    assert_eq!(Order::SIGNATURE, "Order(address,uint256,uint64)");
+
+    // Structs of plain values carry no lifetime.
+    let limits = Limits::decode(&[word(1), word(9)].concat(), 0).expect("failed to decode Limits");
+    assert!(limits.enabled.as_bool());
+    assert_eq!(limits.count, 9);
+    assert_eq!(setLimitsCall::SIGNATURE, "setLimits((bool,uint32))");
+}
+
+#[test]
+fn test_sol_event() {
+    assert_eq!(Transfer::TOPIC0[..4], [0xdd, 0xf2, 0x52, 0xad],);
+
+    let from = word(1);
+    let to = word(2);
+    let topics = [&Transfer::TOPIC0, &from, &to];
+    let data = word(100);
+    let log = ZEventLog::new(&topics, &data);
+
+    let event = Transfer::decode_log(&log).expect("failed to decode Transfer");
+    assert_eq!(event.from.as_bytes()[19], 1);
+    assert_eq!(event.to.as_bytes()[19], 2);
+    assert_eq!(event.value.to_u64(), Some(100));
+
+    let other = [&Memo::TOPIC0, &from];
+    assert!(matches!(
+        Transfer::decode_log(&ZEventLog::new(&other, &data)),
+        Err(ZError::TypeMismatch { expected, found }) if expected == Transfer::TOPIC0 && found == Memo::TOPIC0
+    ));
+
+    let event: Transfer = log.try_decode().unwrap();
+    assert_eq!(event.value.to_u64(), Some(100));
+    assert!(log.is::<Transfer>());
+    assert!(!log.is::<Memo>());
+}
+
+#[test]
+fn test_sol_anonymous_event() {
+    let from = word(3);
+    let id = word(4);
+    let data = word(50);
+    let topics = [&from, &id];
+    let log = ZEventLog::new(&topics, &data);
+
+    // Indexed values start at topic 0.
+    let event = Deposit::decode_log(&log).expect("failed to decode Deposit");
+    assert_eq!(event.from.as_bytes()[19], 3);
+    assert_eq!(event.id.to_u64(), Some(4));
+    assert_eq!(event.amount.to_u64(), Some(50));
+    assert!(!log.is::<Deposit>());
+    assert!(Deposit::decode_log(&ZEventLog::new(&topics[..1], &data)).is_err());
+}
+
+#[test]
+fn test_sol_indexed_dynamic_param() {
+    let mut tag_hash = [0u8; 32];
+    tag_hash[0] = 0x77;
+    let topics = [&Memo::TOPIC0, &tag_hash];
+    let mut data = [0u8; 96];
+    data[31] = 32;
+    data[63] = 2;
+    data[64..66].copy_from_slice(b"gm");
+    let log = ZEventLog::new(&topics, &data);
+
+    let memo = Memo::decode_log(&log).expect("failed to decode Memo");
+    let tag: zabi_rs::ZIndexedHash = memo.tag;
+    assert_eq!(tag.as_bytes(), &tag_hash);
+    assert!(tag.verify_with(b"greeting", |_| tag_hash));
+    assert_eq!(memo.message.as_str(), "gm");
+}
+
+#[test]
+fn test_sol_narrow_widths() {
+    // Widths without a native integer keep their declared type everywhere.
+    assert_eq!(<Tick as ZDecode>::SOL_TYPE, "(uint24,int40)");
+    assert_eq!(Tick::SIGNATURE, "Tick(uint24,int40)");
+    assert_eq!(setFeeCall::SIGNATURE, "setFee(uint24,(uint24,int40))");
+    assert_eq!(<setFeeCall as ZDecode>::SOL_TYPE, "(uint24,(uint24,int40))");
+
+    let minus_one = [0xff; 32];
+    let data = [word(3), minus_one].concat();
+    let tick = Tick::decode(&data, 0).expect("failed to decode Tick");
+    assert_eq!(tick.fee.to_u64(), Some(3));
+    assert_eq!(tick.delta, -1i64);
+
+    // Bits above the declared width, or a broken sign extension, are rejected.
+    let mut wide_fee = word(3);
+    wide_fee[28] = 1;
+    let err = Tick::decode(&[wide_fee, minus_one].concat(), 0).unwrap_err();
+    assert!(matches!(
+        err.root_cause(),
+        ZError::InvalidPadding { ty: "uint24", .. }
+    ));
+    let mut bad_sign = minus_one;
+    bad_sign[26] = 0x7f;
+    assert!(Tick::decode(&[word(3), bad_sign].concat(), 0).is_err());
+
+    let mut price = [0u8; 32];
+    price[12] = 0x80;
+    let topics = [&Swap::TOPIC0, &minus_one];
+    let event = Swap::decode_log(&ZEventLog::new(&topics, &price)).expect("failed to decode Swap");
+    assert_eq!(event.delta, -1i64);
+    price[11] = 1;
+    assert!(Swap::decode_log(&ZEventLog::new(&topics, &price)).is_err());
+    let topics = [&Swap::TOPIC0, &bad_sign];
+    assert!(Swap::decode_log(&ZEventLog::new(&topics, &word(0))).is_err());
+}
+
+#[test]
+fn test_sol_fixed_arrays() {
+    assert_eq!(routeCall::SIGNATURE, "route(address[2],string[2],uint8)");
+
+    // path inline in two words, labels behind an offset word, then hops.
+    let mut calldata = routeCall::SELECTOR.to_vec();
+    for w in [word(0xAA), word(0xBB), word(128), word(3)] {
+        calldata.extend_from_slice(&w);
+    }
+    // labels: two offsets relative to its encoding, then the strings.
+    for w in [word(64), word(128), word(2)] {
+        calldata.extend_from_slice(&w);
+    }
+    let mut text = [0u8; 32];
+    text[..2].copy_from_slice(b"in");
+    calldata.extend_from_slice(&text);
+    calldata.extend_from_slice(&word(3));
+    text[..3].copy_from_slice(b"out");
+    calldata.extend_from_slice(&text);
+
+    let call = routeCall::decode_calldata(&calldata).expect("failed to decode route");
+    assert_eq!(call.path.len(), 2);
+    assert_eq!(call.path.get(1).unwrap().as_bytes()[19], 0xBB);
+    assert_eq!(call.labels.get(0).unwrap().as_str(), "in");
+    assert_eq!(call.labels.get(1).unwrap().as_str(), "out");
+    assert_eq!(call.hops, 3);
+
+    // A path cut short is out of bounds.
+    assert!(routeCall::decode_calldata(&calldata[..36]).is_err());
+}
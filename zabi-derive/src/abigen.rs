@@ -0,0 +1,251 @@
+//! `abigen!` — the `contract!` expansion driven by a JSON ABI file.
+//!
+//! ```ignore
+//! abigen!(pub ERC20, "abi/erc20.json");
+//! ```
+//!
+//! The path is relative to `CARGO_MANIFEST_DIR`. Both a bare ABI array and a
+//! compiler artifact object with an `"abi"` key are accepted.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use serde_json::Value;
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, LitStr, Token, Visibility};
+
+use crate::contract::{self, ContractInput};
+use crate::sol::{
+    elementary, Item, Param, SolError, SolEvent, SolFunction, SolInput, SolStruct, SolType,
+};
+
+pub struct AbigenInput {
+    vis: Visibility,
+    name: Ident,
+    path: LitStr,
+}
+
+impl Parse for AbigenInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let vis: Visibility = input.parse()?;
+        let name: Ident = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let path: LitStr = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(AbigenInput { vis, name, path })
+    }
+}
+
+/// Converts JSON ABI entries into `sol!` items.
+struct Builder {
+    span: Span,
+    structs: Vec<SolStruct>,
+    /// Struct name -> the `components` it was registered with.
+    struct_names: HashMap<String, Value>,
+    anonymous_tuples: usize,
+}
+
+impl Builder {
+    fn error(&self, msg: String) -> syn::Error {
+        syn::Error::new(self.span, msg)
+    }
+
+    fn ident(&self, name: &str) -> syn::Result<Ident> {
+        if name.is_empty() || !name.chars().all(|c| c == '_' || c.is_ascii_alphanumeric()) {
+            return Err(self.error(format!("`{}` is not a valid identifier", name)));
+        }
+        // Solidity names may collide with Rust keywords (`type`, `move`, ...).
+        Ok(match syn::parse_str::<Ident>(name) {
+            Ok(ident) => ident,
+            Err(_) => Ident::new_raw(name, self.span),
+        })
+    }
+
+    fn params(&mut self, value: Option<&Value>) -> syn::Result<Vec<Param>> {
+        let entries = match value {
+            Some(Value::Array(entries)) => entries,
+            Some(_) => return Err(self.error("ABI parameters must be an array".to_string())),
+            None => return Ok(Vec::new()),
+        };
+        entries.iter().map(|p| self.param(p)).collect()
+    }
+
+    fn param(&mut self, value: &Value) -> syn::Result<Param> {
+        let ty = value["type"]
+            .as_str()
+            .ok_or_else(|| self.error("ABI parameter is missing `type`".to_string()))?;
+        let (base, suffixes) = match ty.find('[') {
+            Some(i) => (&ty[..i], &ty[i..]),
+            None => (ty, ""),
+        };
+
+        let mut sol_type = if base == "tuple" {
+            SolType::Struct(self.tuple_struct(value)?)
+        } else {
+            let name = elementary(base)
+                .ok_or_else(|| self.error(format!("unsupported ABI type `{}`", ty)))?;
+            SolType::Elementary(name)
+        };
+        for dim in suffixes.split_terminator(']') {
+            let dim = dim.trim_start_matches('[');
+            sol_type = if dim.is_empty() {
+                SolType::Array(Box::new(sol_type))
+            } else {
+                let n = dim
+                    .parse()
+                    .map_err(|_| self.error(format!("invalid array type `{}`", ty)))?;
+                SolType::FixedArray(Box::new(sol_type), n)
+            };
+        }
+
+        let name = match value["name"].as_str() {
+            Some("") | None => None,
+            Some(name) => Some(self.ident(name)?),
+        };
+        Ok(Param {
+            ty: sol_type,
+            name,
+            indexed: value["indexed"].as_bool().unwrap_or(false),
+        })
+    }
+
+    /// Registers the struct for a `tuple` parameter, named after its
+    /// `internalType` (`struct Lib.Order` -> `Order`) when available.
+    ///
+    /// Structs with the same short name but different components
+    /// (`A.Order` vs `B.Order`) fall back to the qualified name (`B_Order`),
+    /// then to a numeric suffix.
+    fn tuple_struct(&mut self, value: &Value) -> syn::Result<Ident> {
+        let components = value.get("components").cloned().unwrap_or(Value::Null);
+        let qualified = match value["internalType"]
+            .as_str()
+            .and_then(|t| t.strip_prefix("struct "))
+            .map(|t| t.split('[').next().unwrap_or(t))
+        {
+            Some(qualified) => qualified.to_string(),
+            None => {
+                self.anonymous_tuples += 1;
+                format!("Tuple{}", self.anonymous_tuples)
+            }
+        };
+        let short = qualified
+            .rsplit('.')
+            .next()
+            .unwrap_or(&qualified)
+            .to_string();
+        let full = qualified.replace('.', "_");
+        let mut attempt = 0;
+        let name = loop {
+            let name = match attempt {
+                0 => short.clone(),
+                1 => full.clone(),
+                n => format!("{}_{}", full, n - 1),
+            };
+            attempt += 1;
+            match self.struct_names.get(&name) {
+                Some(existing) if *existing == components => return self.ident(&name),
+                Some(_) => continue,
+                None => break name,
+            }
+        };
+        let ident = self.ident(&name)?;
+        self.struct_names.insert(name, components);
+        let fields = self.params(value.get("components"))?;
+        self.structs.push(SolStruct {
+            name: ident.clone(),
+            fields,
+        });
+        Ok(ident)
+    }
+
+    fn items(&mut self, abi: &[Value]) -> syn::Result<Vec<Item>> {
+        let mut items = Vec::new();
+        let mut overloads: HashMap<String, usize> = HashMap::new();
+        for entry in abi {
+            let kind = entry["type"].as_str().unwrap_or("function");
+            let name = entry["name"].as_str().unwrap_or_default();
+            match kind {
+                "function" => {
+                    let ident = self.ident(name)?;
+                    // Overloads get `_1`, `_2`, ... suffixes on the generated names.
+                    let count = overloads.entry(name.to_string()).or_insert(0);
+                    let rust_name = match *count {
+                        0 => ident.clone(),
+                        n => self.ident(&format!("{}_{}", name, n))?,
+                    };
+                    *count += 1;
+                    let inputs = self.params(entry.get("inputs"))?;
+                    let outputs = self.params(entry.get("outputs"))?;
+                    items.push(Item::Function(SolFunction {
+                        name: ident,
+                        rust_name,
+                        inputs,
+                        outputs,
+                    }));
+                }
+                "event" => {
+                    let inputs = self.params(entry.get("inputs"))?;
+                    let anonymous = entry["anonymous"].as_bool().unwrap_or(false);
+                    items.push(Item::Event(SolEvent {
+                        name: self.ident(name)?,
+                        inputs,
+                        anonymous,
+                    }));
+                }
+                "error" => {
+                    let inputs = self.params(entry.get("inputs"))?;
+                    items.push(Item::Error(SolError {
+                        name: self.ident(name)?,
+                        inputs,
+                    }));
+                }
+                // constructor, fallback, receive
+                _ => {}
+            }
+        }
+        let mut all: Vec<Item> = self.structs.drain(..).map(Item::Struct).collect();
+        all.extend(items);
+        Ok(all)
+    }
+}
+
+pub fn expand(input: AbigenInput) -> syn::Result<TokenStream2> {
+    let span = input.path.span();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let path: PathBuf = [manifest_dir, input.path.value()].iter().collect();
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| syn::Error::new(span, format!("failed to read {}: {}", path.display(), e)))?;
+    let json: Value = serde_json::from_str(&contents)
+        .map_err(|e| syn::Error::new(span, format!("failed to parse {}: {}", path.display(), e)))?;
+    let abi = match json.get("abi").unwrap_or(&json) {
+        Value::Array(entries) => entries.clone(),
+        _ => {
+            return Err(syn::Error::new(
+                span,
+                "expected a JSON ABI array or an object with an `abi` array",
+            ))
+        }
+    };
+
+    let mut builder = Builder {
+        span,
+        structs: Vec::new(),
+        struct_names: HashMap::new(),
+        anonymous_tuples: 0,
+    };
+    let items = builder.items(&abi)?;
+    let module = contract::expand(ContractInput {
+        vis: input.vis,
+        name: input.name,
+        body: SolInput { items },
+    })?;
+
+    // Recompile when the ABI file changes.
+    let path_str = path.display().to_string();
+    Ok(quote! {
+        #module
+        const _: &str = include_str!(#path_str);
+    })
+}
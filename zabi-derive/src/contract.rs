@@ -0,0 +1,197 @@
+//! `contract!` — a module per contract with call, event and error dispatch.
+//!
+//! ```ignore
+//! contract! {
+//!     pub ERC20 {
+//!         function transfer(address to, uint256 amount) external returns (bool);
+//!         event Transfer(address indexed from, address indexed to, uint256 value);
+//!         error InsufficientBalance(uint256 available, uint256 required);
+//!     }
+//! }
+//! ```
+//!
+//! expands to `pub mod ERC20` containing the `sol!` items plus `Call`, `Event`
+//! and `Error` enums and `decode_call`, `decode_event`, `decode_error`.
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::{braced, Ident, Visibility};
+
+use crate::sol::{expand_items, Ctx, Item, SolInput};
+
+pub struct ContractInput {
+    pub(crate) vis: Visibility,
+    pub(crate) name: Ident,
+    pub(crate) body: SolInput,
+}
+
+impl Parse for ContractInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let vis: Visibility = input.parse()?;
+        let name: Ident = input.parse()?;
+        let content;
+        braced!(content in input);
+        let body: SolInput = content.parse()?;
+        Ok(ContractInput { vis, name, body })
+    }
+}
+
+/// One enum variant wrapping a generated item type.
+struct Variant {
+    ident: Ident,
+    ty: TokenStream2,
+    /// The item's selector or topic constant.
+    key: TokenStream2,
+    borrows: bool,
+}
+
+/// `enum Name<'a> { Variant(Type), ... }`, with the lifetime only when some variant borrows.
+fn variant_enum(name: &Ident, doc: &str, variants: &[Variant]) -> (TokenStream2, TokenStream2) {
+    let borrows = variants.iter().any(|v| v.borrows);
+    let generics = if borrows {
+        quote! { <'a> }
+    } else {
+        quote! {}
+    };
+    let arms = variants.iter().map(|v| {
+        let ident = &v.ident;
+        let ty = &v.ty;
+        quote! { #ident(#ty) }
+    });
+    let tokens = quote! {
+        #[doc = #doc]
+        #[allow(non_camel_case_types)]
+        #[derive(Debug, Clone, Copy)]
+        pub enum #name #generics {
+            #(#arms),*
+        }
+    };
+    (tokens, quote! { #name #generics })
+}
+
+pub fn expand(input: ContractInput) -> syn::Result<TokenStream2> {
+    let ContractInput { vis, name, body } = input;
+    let ctx = Ctx::new(&body.items)?;
+    let items = expand_items(&ctx, &body.items)?;
+
+    let mut calls = Vec::new();
+    let mut events = Vec::new();
+    let mut errors = Vec::new();
+    for item in &body.items {
+        match item {
+            Item::Function(f) => {
+                let call = format_ident!("{}Call", f.rust_name.unraw());
+                let borrows = ctx.params_borrow(&f.inputs)?;
+                let ty = if borrows {
+                    quote! { #call<'a> }
+                } else {
+                    quote! { #call }
+                };
+                calls.push(Variant {
+                    ident: f.rust_name.clone(),
+                    ty,
+                    key: quote! { #call::SELECTOR },
+                    borrows,
+                });
+            }
+            // Anonymous events have no topic 0 to dispatch on.
+            Item::Event(e) if e.anonymous => {}
+            Item::Event(e) => {
+                let ident = &e.name;
+                let borrows = ctx.event_borrows(e)?;
+                let ty = if borrows {
+                    quote! { #ident<'a> }
+                } else {
+                    quote! { #ident }
+                };
+                events.push(Variant {
+                    ident: ident.clone(),
+                    ty,
+                    key: quote! { #ident::TOPIC0 },
+                    borrows,
+                });
+            }
+            Item::Error(e) => {
+                let ident = &e.name;
+                let borrows = ctx.params_borrow(&e.inputs)?;
+                let ty = if borrows {
+                    quote! { #ident<'a> }
+                } else {
+                    quote! { #ident }
+                };
+                errors.push(Variant {
+                    ident: ident.clone(),
+                    ty,
+                    key: quote! { #ident::SELECTOR },
+                    borrows,
+                });
+            }
+            Item::Struct(_) => {}
+        }
+    }
+
+    let call_doc = format!("Any call to `{}`.", name);
+    let (call_enum, call_ty) = variant_enum(&format_ident!("Call"), &call_doc, &calls);
+    let call_arms = calls.iter().map(|v| {
+        let (ident, ty, key) = (&v.ident, &v.ty, &v.key);
+        quote! { #key => Ok(Call::#ident(<#ty as ::zabi_rs::ZDecode<'a>>::decode(params, 0)?)) }
+    });
+
+    let event_doc = format!("Any event emitted by `{}`.", name);
+    let (event_enum, event_ty) = variant_enum(&format_ident!("Event"), &event_doc, &events);
+    let event_arms = events.iter().map(|v| {
+        let (ident, key) = (&v.ident, &v.key);
+        quote! { #key => Ok(Event::#ident(#ident::decode_log(log)?)) }
+    });
+
+    let error_doc = format!("Any custom error reverted by `{}`.", name);
+    let (error_enum, error_ty) = variant_enum(&format_ident!("Error"), &error_doc, &errors);
+    let error_arms = errors.iter().map(|v| {
+        let (ident, ty, key) = (&v.ident, &v.ty, &v.key);
+        quote! { #key => Ok(Error::#ident(<#ty as ::zabi_rs::ZDecode<'a>>::decode(params, 0)?)) }
+    });
+
+    Ok(quote! {
+        #[allow(non_snake_case)]
+        #vis mod #name {
+            #items
+
+            #call_enum
+            #event_enum
+            #error_enum
+
+            /// Decode calldata by dispatching on its selector.
+            #[allow(unreachable_code, unused_variables)]
+            pub fn decode_call<'a>(calldata: &'a [u8]) -> Result<#call_ty, ::zabi_rs::ZError> {
+                let selector = ::zabi_rs::decoder::read_selector(calldata)?;
+                let params = &calldata[4..];
+                match *selector {
+                    #(#call_arms,)*
+                    unknown => Err(::zabi_rs::ZError::UnknownSelector(unknown)),
+                }
+            }
+
+            /// Decode a log by dispatching on topic 0.
+            #[allow(unreachable_code, unused_variables)]
+            pub fn decode_event<'a>(log: &::zabi_rs::ZEventLog<'a>) -> Result<#event_ty, ::zabi_rs::ZError> {
+                match *log.event_signature()? {
+                    #(#event_arms,)*
+                    unknown => Err(::zabi_rs::ZError::UnknownTopic(unknown)),
+                }
+            }
+
+            /// Decode revert data by dispatching on its selector.
+            #[allow(unreachable_code, unused_variables)]
+            pub fn decode_error<'a>(data: &'a [u8]) -> Result<#error_ty, ::zabi_rs::ZError> {
+                let selector = ::zabi_rs::decoder::read_selector(data)?;
+                let params = &data[4..];
+                match *selector {
+                    #(#error_arms,)*
+                    unknown => Err(::zabi_rs::ZError::UnknownSelector(unknown)),
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,166 @@
+//! `dispatch!` — a selector match over calldata.
+//!
+//! ```ignore
+//! let out = dispatch!(calldata, {
+//!     "transfer(address,uint256)" => handle_transfer,
+//!     0x095ea7b3 => |params| handle_approve(params),
+//!     ERC20::balanceOfCall::SELECTOR => handle_balance_of,
+//!     _ => |calldata| Err(ZError::Custom("unsupported")),
+//! });
+//! ```
+//!
+//! Each handler is called with the calldata after the selector and must
+//! return `Result<R, E>` with `E: From<ZError>`; the fallback receives the
+//! full calldata. Without a fallback, unknown selectors yield
+//! `ZError::UnknownSelector`.
+
+use std::collections::HashSet;
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{braced, Expr, ExprArray, ExprLit, ExprPath, Lit, Token};
+
+use crate::selector::hash_signature;
+
+pub struct DispatchInput {
+    calldata: Expr,
+    arms: Punctuated<Arm, Token![,]>,
+}
+
+enum Key {
+    /// A selector known at expansion time.
+    Bytes([u8; 4], Span),
+    /// A `[u8; 4]` constant.
+    Path(ExprPath),
+    Wildcard,
+}
+
+struct Arm {
+    key: Key,
+    handler: Expr,
+}
+
+impl Parse for DispatchInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let calldata: Expr = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let content;
+        braced!(content in input);
+        let arms = Punctuated::parse_terminated(&content)?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(DispatchInput { calldata, arms })
+    }
+}
+
+impl Parse for Arm {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key = if input.peek(Token![_]) {
+            input.parse::<Token![_]>()?;
+            Key::Wildcard
+        } else {
+            parse_key(input.parse()?)?
+        };
+        input.parse::<Token![=>]>()?;
+        let handler: Expr = input.parse()?;
+        Ok(Arm { key, handler })
+    }
+}
+
+fn parse_key(expr: Expr) -> syn::Result<Key> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(sig), ..
+        }) => {
+            let hash = hash_signature(&sig)?;
+            Ok(Key::Bytes([hash[0], hash[1], hash[2], hash[3]], sig.span()))
+        }
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(int), ..
+        }) => {
+            let digits = int.to_string();
+            if !digits.starts_with("0x") || digits.len() != 10 {
+                return Err(syn::Error::new(
+                    int.span(),
+                    "expected a 4-byte hex selector like `0xa9059cbb`",
+                ));
+            }
+            let value: u32 = int.base10_parse()?;
+            Ok(Key::Bytes(value.to_be_bytes(), int.span()))
+        }
+        Expr::Array(ExprArray {
+            elems,
+            bracket_token,
+            ..
+        }) => {
+            let span = bracket_token.span.join();
+            if elems.len() != 4 {
+                return Err(syn::Error::new(span, "a selector has exactly 4 bytes"));
+            }
+            let mut bytes = [0u8; 4];
+            for (byte, elem) in bytes.iter_mut().zip(&elems) {
+                *byte = match elem {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Int(int), ..
+                    }) => int.base10_parse()?,
+                    other => return Err(syn::Error::new_spanned(other, "expected a byte literal")),
+                };
+            }
+            Ok(Key::Bytes(bytes, span))
+        }
+        Expr::Path(path) => Ok(Key::Path(path)),
+        other => Err(syn::Error::new_spanned(
+            other,
+            "expected a signature string, a hex selector, a byte array or a selector constant",
+        )),
+    }
+}
+
+pub fn expand(input: DispatchInput) -> syn::Result<TokenStream2> {
+    let calldata = &input.calldata;
+    let mut seen: HashSet<[u8; 4]> = HashSet::new();
+    let mut arms = Vec::new();
+    let mut fallback = None;
+
+    for arm in &input.arms {
+        let handler = &arm.handler;
+        if fallback.is_some() {
+            return Err(syn::Error::new_spanned(
+                handler,
+                "arms after `_` are unreachable",
+            ));
+        }
+        match &arm.key {
+            Key::Bytes(bytes, span) => {
+                if !seen.insert(*bytes) {
+                    return Err(syn::Error::new(*span, "duplicate selector"));
+                }
+                let [b0, b1, b2, b3] = *bytes;
+                arms.push(quote! { [#b0, #b1, #b2, #b3] => (#handler)(__params) });
+            }
+            Key::Path(path) => arms.push(quote! { #path => (#handler)(__params) }),
+            Key::Wildcard => fallback = Some(quote! { _ => (#handler)(__calldata) }),
+        }
+    }
+    let fallback = fallback.unwrap_or_else(
+        || quote! { __unknown => Err(::zabi_rs::ZError::UnknownSelector(__unknown).into()) },
+    );
+
+    Ok(quote! {
+        {
+            let __calldata: &[u8] = #calldata;
+            match ::zabi_rs::decoder::read_selector(__calldata) {
+                Ok(__selector) => {
+                    let __params = &__calldata[4..];
+                    #[allow(unreachable_patterns)]
+                    match *__selector {
+                        #(#arms,)*
+                        #fallback,
+                    }
+                }
+                Err(e) => Err(e.into()),
+            }
+        }
+    })
+}
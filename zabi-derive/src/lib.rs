@@ -1,88 +1,850 @@
 extern crate proc_macro;
 
+mod abigen;
+mod contract;
+mod dispatch;
+mod selector;
+mod sol;
+
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, DeriveInput, Data, Fields};
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, parse_quote, Attribute, DeriveInput, Data, Field, Fields, GenericParam, Generics, Ident, Lifetime, LifetimeParam, LitStr, Path, Token, Type, Visibility, parenthesized};
 
-#[proc_macro_derive(ZDecode)]
+#[proc_macro_derive(ZDecode, attributes(zabi))]
 pub fn zabi_decode_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    expand_zdecode(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates `<Name>View<'a>`, a lazy view holding `(&[u8], offset)` with one
+/// getter per field that decodes only when called.
+#[proc_macro_derive(ZView, attributes(zabi))]
+pub fn zabi_view_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_zview(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Expands inline Solidity `struct`, `function` and `event` declarations
+/// into zero-copy view structs with selectors, topics and decode helpers.
+#[proc_macro]
+pub fn sol(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as sol::SolInput);
+    sol::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates a module for one contract: its `sol!` items plus `Call`, `Event`
+/// and `Error` enums with selector/topic dispatching decoders.
+#[proc_macro]
+pub fn contract(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as contract::ContractInput);
+    contract::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates the `contract!` module for a JSON ABI file:
+/// `abigen!(pub ERC20, "abi/erc20.json")`, path relative to `CARGO_MANIFEST_DIR`.
+#[proc_macro]
+pub fn abigen(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as abigen::AbigenInput);
+    abigen::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Matches calldata on its selector and calls the handler of the matching arm
+/// with the parameters that follow it:
+/// `dispatch!(calldata, { "transfer(address,uint256)" => handler, 0x095ea7b3 => ..., _ => ... })`.
+#[proc_macro]
+pub fn dispatch(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as dispatch::DispatchInput);
+    dispatch::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// The 4-byte function selector of a signature, computed at compile time:
+/// `selector!("approve(address,uint256)")` is a `[u8; 4]` constant.
+#[proc_macro]
+pub fn selector(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as LitStr);
+    selector::expand_selector(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// The topic 0 of an event signature, computed at compile time:
+/// `event_topic!("Transfer(address,address,uint256)")` is a `[u8; 32]` constant.
+#[proc_macro]
+pub fn event_topic(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as LitStr);
+    selector::expand_event_topic(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_zdecode(input: DeriveInput) -> syn::Result<TokenStream2> {
     let name = input.ident;
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
-
-    let decode_body = match input.data {
-        Data::Struct(data) => {
-            match data.fields {
-                Fields::Named(fields) => {
-                    let field_recurse = fields.named.iter().map(|f| {
-                        let name = &f.ident;
-                        let ty = &f.ty;
-                        quote! {
-                            #name: {
-                                let val = <#ty as ::zabi_rs::ZDecode>::decode(data, offset)?;
-                                offset += <#ty as ::zabi_rs::ZDecode>::HEAD_SIZE;
-                                val
-                            }
-                        }
-                    });
-                    let head_size_recurse = fields.named.iter().map(|f| {
-                        let ty = &f.ty;
-                        quote! { <#ty as ::zabi_rs::ZDecode>::HEAD_SIZE }
-                    });
-                    
-                    quote! {
-                        const HEAD_SIZE: usize = 0 #(+ #head_size_recurse)*;
-                        fn decode(data: &'a [u8], offset: usize) -> Result<Self, ::zabi_rs::ZError> {
-                            let mut offset = offset;
-                            Ok(#name {
-                                #(#field_recurse),*
-                            })
-                        }
-                    }
+
+    let (generics, lifetime) = decode_generics(&input.generics);
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    let fields = struct_fields(input.data)?;
+
+    let struct_attrs = parse_struct_attrs(&input.attrs)?;
+    let struct_lenient = struct_attrs.lenient;
+    if struct_attrs.transparent && struct_attrs.tuple {
+        return Err(syn::Error::new(name.span(), "#[zabi(transparent)] cannot be combined with #[zabi(tuple)]"));
+    }
+    if struct_attrs.transparent && fields.len() != 1 {
+        return Err(syn::Error::new(name.span(), "#[zabi(transparent)] requires exactly one field"));
+    }
+    let outputs = match &struct_attrs.outputs {
+        Some(fields) => expand_outputs(&name, &input.vis, &generics, &input.generics, &lifetime, fields, &struct_attrs.outputs_derive),
+        None => quote! {},
+    };
+    let owned = match &struct_attrs.owned {
+        Some(derives) => expand_owned(&name, &input.vis, &generics, &input.generics, &lifetime, &fields, derives)?,
+        None => quote! {},
+    };
+
+    let decode_body = match &fields {
+        Fields::Unit => {
+            quote! {
+                const HEAD_SIZE: usize = 0;
+                const SOL_TYPE: &'static str = "()";
+                fn decode(_data: &#lifetime [u8], _offset: usize) -> Result<Self, ::zabi_rs::ZError> {
+                    Ok(#name)
                 }
-                Fields::Unnamed(fields) => {
-                    let field_recurse = fields.unnamed.iter().map(|f| {
-                        let ty = &f.ty;
-                        quote! {
-                            {
-                                let val = <#ty as ::zabi_rs::ZDecode>::decode(data, offset)?;
-                                offset += <#ty as ::zabi_rs::ZDecode>::HEAD_SIZE;
-                                val
-                            }
-                        }
-                    });
-                    let head_size_recurse = fields.unnamed.iter().map(|f| {
-                        let ty = &f.ty;
-                        quote! { <#ty as ::zabi_rs::ZDecode>::HEAD_SIZE }
-                    });
-                    quote! {
-                        const HEAD_SIZE: usize = 0 #(+ #head_size_recurse)*;
-                        fn decode(data: &'a [u8], offset: usize) -> Result<Self, ::zabi_rs::ZError> {
-                            let mut offset = offset;
-                            Ok(#name (
-                                #(#field_recurse),*
-                            ))
-                        }
+            }
+        }
+        Fields::Named(_) | Fields::Unnamed(_) => {
+            let codecs = fields.iter().map(field_codec).collect::<syn::Result<Vec<_>>>()?;
+            // A transparent struct reads as its field, so errors are not wrapped.
+            let in_field = !struct_attrs.transparent;
+            let strict = construct(&name, &fields, &codecs, struct_lenient, in_field);
+            let lenient = construct(&name, &fields, &codecs, true, in_field);
+            let head_size_recurse = codecs.iter().map(|c| &c.head_size);
+            let sol_field_recurse = codecs.iter().map(|c| &c.sol_field);
+            let sol_type = if struct_attrs.transparent {
+                codecs[0].sol_type.clone()
+            } else {
+                quote! {
+                    ::zabi_rs::signature::SolTypeBuf::new()
+                        .push_fields(&[#(#sol_field_recurse),*])
+                        .as_str()
+                }
+            };
+
+            if struct_attrs.tuple {
+                // Same layout as the built-in tuple impls: `decode` reads the
+                // members inline, `decode_member` follows the offset word of
+                // a dynamic tuple.
+                let is_dynamic_recurse = codecs.iter().map(|c| &c.is_dynamic);
+                quote! {
+                    const HEAD_SIZE: usize = if <Self as ::zabi_rs::ZDecode>::IS_DYNAMIC { 32 } else { <Self as ::zabi_rs::ZDecode>::INLINE_HEAD_SIZE };
+                    const INLINE_HEAD_SIZE: usize = 0 #(+ #head_size_recurse)*;
+                    const SOL_TYPE: &'static str = #sol_type;
+                    const IS_DYNAMIC: bool = false #(|| #is_dynamic_recurse)*;
+                    #[allow(clippy::needless_question_mark)]
+                    fn decode(data: &#lifetime [u8], offset: usize) -> Result<Self, ::zabi_rs::ZError> {
+                        let mut offset = offset;
+                        Ok(#strict)
+                    }
+                    #[allow(clippy::needless_question_mark)]
+                    fn decode_lenient(data: &#lifetime [u8], offset: usize) -> Result<Self, ::zabi_rs::ZError> {
+                        let mut offset = offset;
+                        Ok(#lenient)
+                    }
+                    fn decode_member(data: &#lifetime [u8], offset: usize) -> Result<Self, ::zabi_rs::ZError> {
+                        let data = ::zabi_rs::decoder::read_tuple_body(data, offset, <Self as ::zabi_rs::ZDecode>::IS_DYNAMIC)?;
+                        <Self as ::zabi_rs::ZDecode>::decode(data, 0)
+                    }
+                    fn decode_member_lenient(data: &#lifetime [u8], offset: usize) -> Result<Self, ::zabi_rs::ZError> {
+                        let data = ::zabi_rs::decoder::read_tuple_body(data, offset, <Self as ::zabi_rs::ZDecode>::IS_DYNAMIC)?;
+                        <Self as ::zabi_rs::ZDecode>::decode_lenient(data, 0)
                     }
                 }
-                Fields::Unit => {
-                    quote! { 
-                        const HEAD_SIZE: usize = 0;
-                        fn decode(data: &'a [u8], _offset: usize) -> Result<Self, ::zabi_rs::ZError> {
-                            Ok(#name)
-                        }
+            } else {
+                quote! {
+                    const HEAD_SIZE: usize = 0 #(+ #head_size_recurse)*;
+                    const SOL_TYPE: &'static str = #sol_type;
+                    #[allow(clippy::needless_question_mark)]
+                    fn decode(data: &#lifetime [u8], offset: usize) -> Result<Self, ::zabi_rs::ZError> {
+                        let mut offset = offset;
+                        Ok(#strict)
+                    }
+                    #[allow(clippy::needless_question_mark)]
+                    fn decode_lenient(data: &#lifetime [u8], offset: usize) -> Result<Self, ::zabi_rs::ZError> {
+                        let mut offset = offset;
+                        Ok(#lenient)
                     }
                 }
             }
         }
-        _ => panic!("ZDecode can only be derived for structs"),
+    };
+
+    let signature = match struct_attrs.signature {
+        Some(signature) => quote! { #signature },
+        None => {
+            let name_str = name.to_string();
+            let sol_type = quote! { <Self as ::zabi_rs::ZDecode<#lifetime>>::SOL_TYPE };
+            // A transparent newtype still reads as a one-element tuple here.
+            let params = if struct_attrs.transparent {
+                quote! { .push_tuple(&[#sol_type]) }
+            } else {
+                quote! { .push_str(#sol_type) }
+            };
+            quote! {
+                ::zabi_rs::signature::SolTypeBuf::new()
+                    .push_str(#name_str)
+                    #params
+                    .as_str()
+            }
+        }
     };
 
     let expanded = quote! {
-        impl #impl_generics ::zabi_rs::ZDecode<'a> for #name #ty_generics #where_clause {
+        impl #impl_generics ::zabi_rs::ZDecode<#lifetime> for #name #ty_generics #where_clause {
             #decode_body
         }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Canonical signature: the type name followed by its Solidity tuple type.
+            pub const SIGNATURE: &'static str = #signature;
+        }
+
+        #outputs
+
+        #owned
+    };
+
+    Ok(expanded)
+}
+
+/// `#[zabi(outputs(...))]`: a `<Name>Outputs` struct deriving `ZDecode` for the
+/// return values, plus `<Name>::decode_returns`. Fields keep their declared
+/// visibility; further derives come from `#[zabi(outputs_derive(...))]`.
+fn expand_outputs(
+    name: &Ident,
+    vis: &Visibility,
+    decode_generics: &Generics,
+    generics: &Generics,
+    lifetime: &Lifetime,
+    fields: &Punctuated<Field, Token![,]>,
+    derives: &[Path],
+) -> TokenStream2 {
+    let outputs_name = format_ident!("{}Outputs", name);
+    let borrows = fields.iter().any(|f| mentions_lifetime(f.ty.to_token_stream(), lifetime));
+    let outputs_generics = if borrows { quote! { <#lifetime> } } else { quote! {} };
+    let (impl_generics, _, where_clause) = decode_generics.split_for_impl();
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let doc = format!("Return values of [`{}`].", name);
+    let fields = fields.iter().map(|f| {
+        let attrs = &f.attrs;
+        let field_vis = &f.vis;
+        let ident = &f.ident;
+        let ty = &f.ty;
+        quote! { #(#attrs)* #field_vis #ident: #ty }
+    });
+
+    quote! {
+        #[doc = #doc]
+        #[derive(::zabi_rs::ZDecode #(, #derives)*)]
+        #vis struct #outputs_name #outputs_generics {
+            #(#fields),*
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Decode the ABI-encoded return data of this call.
+            #[inline]
+            pub fn decode_returns(data: &#lifetime [u8]) -> Result<#outputs_name #outputs_generics, ::zabi_rs::ZError> {
+                <#outputs_name #outputs_generics as ::zabi_rs::ZDecode<#lifetime>>::decode(data, 0)
+            }
+        }
+    }
+}
+
+/// `#[zabi(owned)]`: a `<Name>Owned` struct with each field's
+/// `IntoOwned::Owned` type, and the `IntoOwned` impl converting into it.
+/// Fields keep their visibility and docs; derives come from
+/// `#[zabi(owned(...))]`.
+fn expand_owned(
+    name: &Ident,
+    vis: &Visibility,
+    decode_generics: &Generics,
+    generics: &Generics,
+    lifetime: &Lifetime,
+    fields: &Fields,
+    derives: &[Path],
+) -> syn::Result<TokenStream2> {
+    if let Some(param) = generics.type_params().next() {
+        return Err(syn::Error::new(
+            param.ident.span(),
+            "#[zabi(owned)] does not support type parameters: the owned struct names each field's owned type",
+        ));
+    }
+    let owned_name = format_ident!("{}Owned", name);
+    let (impl_generics, _, where_clause) = decode_generics.split_for_impl();
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let doc = format!("Owned copy of [`{}`], which no longer borrows the input.", name);
+    let owned_fields = fields.iter().map(|f| {
+        let docs = f.attrs.iter().filter(|a| a.path().is_ident("doc"));
+        let field_vis = &f.vis;
+        let ty = replace_lifetime(f.ty.to_token_stream(), lifetime);
+        let ty = quote! { <#ty as ::zabi_rs::owned::IntoOwned>::Owned };
+        match &f.ident {
+            Some(ident) => quote! { #(#docs)* #field_vis #ident: #ty },
+            None => quote! { #(#docs)* #field_vis #ty },
+        }
+    });
+    let convert = fields.iter().enumerate().map(|(index, f)| {
+        let member = match &f.ident {
+            Some(ident) => quote! { #ident },
+            None => {
+                let index = syn::Index::from(index);
+                quote! { #index }
+            }
+        };
+        let value = quote! {
+            ::zabi_rs::error::in_field(#index, || ::zabi_rs::owned::IntoOwned::into_owned(self.#member))?
+        };
+        match &f.ident {
+            Some(ident) => quote! { #ident: #value },
+            None => value,
+        }
+    });
+    let (definition, construct) = match fields {
+        Fields::Named(_) => (
+            quote! { #vis struct #owned_name { #(#owned_fields),* } },
+            quote! { #owned_name { #(#convert),* } },
+        ),
+        Fields::Unnamed(_) => (
+            quote! { #vis struct #owned_name ( #(#owned_fields),* ); },
+            quote! { #owned_name ( #(#convert),* ) },
+        ),
+        Fields::Unit => (quote! { #vis struct #owned_name; }, quote! { #owned_name }),
+    };
+
+    Ok(quote! {
+        #[doc = #doc]
+        #(#[derive(#derives)])*
+        #definition
+
+        impl #impl_generics ::zabi_rs::owned::IntoOwned for #name #ty_generics #where_clause {
+            type Owned = #owned_name;
+
+            #[allow(clippy::needless_question_mark)]
+            fn into_owned(self) -> Result<#owned_name, ::zabi_rs::ZError> {
+                Ok(#construct)
+            }
+        }
+    })
+}
+
+/// `tokens` with every use of `lifetime` replaced by `'static`.
+fn replace_lifetime(tokens: TokenStream2, lifetime: &Lifetime) -> TokenStream2 {
+    let mut out = Vec::new();
+    let mut iter = tokens.into_iter().peekable();
+    while let Some(tt) = iter.next() {
+        match tt {
+            TokenTree::Punct(p) if p.as_char() == '\'' => {
+                if let Some(TokenTree::Ident(ident)) = iter.peek() {
+                    if *ident == lifetime.ident {
+                        let span = ident.span();
+                        iter.next();
+                        out.push(TokenTree::Punct(p));
+                        out.push(TokenTree::Ident(Ident::new("static", span)));
+                        continue;
+                    }
+                }
+                out.push(TokenTree::Punct(p));
+            }
+            TokenTree::Group(g) => {
+                let mut group = proc_macro2::Group::new(g.delimiter(), replace_lifetime(g.stream(), lifetime));
+                group.set_span(g.span());
+                out.push(TokenTree::Group(group));
+            }
+            tt => out.push(tt),
+        }
+    }
+    out.into_iter().collect()
+}
+
+fn mentions_lifetime(tokens: TokenStream2, lifetime: &Lifetime) -> bool {
+    let mut iter = tokens.into_iter().peekable();
+    while let Some(tt) = iter.next() {
+        match tt {
+            TokenTree::Punct(p) if p.as_char() == '\'' => {
+                if let Some(TokenTree::Ident(ident)) = iter.peek() {
+                    if *ident == lifetime.ident {
+                        return true;
+                    }
+                }
+            }
+            TokenTree::Group(g) if mentions_lifetime(g.stream(), lifetime) => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Struct construction from `codecs`, read in order from `offset`.
+/// With `in_field`, a field's error is wrapped with the field's index.
+fn construct(name: &Ident, fields: &Fields, codecs: &[FieldCodec], lenient: bool, in_field: bool) -> TokenStream2 {
+    let field_recurse = fields.iter().zip(codecs).enumerate().map(|(index, (f, codec))| {
+        let decode = codec.value(lenient);
+        let decode = if in_field {
+            quote! { ::zabi_rs::error::in_field(#index, || Ok(#decode))? }
+        } else {
+            decode
+        };
+        let head_size = &codec.head_size;
+        let value = quote! {
+            {
+                let val = #decode;
+                offset += #head_size;
+                val
+            }
+        };
+        match &f.ident {
+            Some(ident) => quote! { #ident: #value },
+            None => value,
+        }
+    });
+    match fields {
+        Fields::Named(_) => quote! { #name { #(#field_recurse),* } },
+        _ => quote! { #name ( #(#field_recurse),* ) },
+    }
+}
+
+/// Impl generics for a decode impl, and the decode lifetime.
+///
+/// The decode lifetime is the struct's first lifetime parameter; structs
+/// without one get a fresh `'a` on the impl only.
+fn decode_generics(struct_generics: &Generics) -> (Generics, Lifetime) {
+    let mut generics = struct_generics.clone();
+    let lifetime: Lifetime = match struct_generics.lifetimes().next() {
+        Some(param) => param.lifetime.clone(),
+        None => {
+            let lt: Lifetime = parse_quote!('a);
+            generics.params.insert(0, GenericParam::Lifetime(LifetimeParam::new(lt.clone())));
+            lt
+        }
+    };
+    // Every type parameter must itself be decodable.
+    for param in struct_generics.type_params() {
+        let ident = &param.ident;
+        generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote!(#ident: ::zabi_rs::ZDecode<#lifetime>));
+    }
+    (generics, lifetime)
+}
+
+fn struct_fields(data: Data) -> syn::Result<Fields> {
+    match data {
+        Data::Struct(data) => Ok(data.fields),
+        Data::Enum(data) => Err(syn::Error::new(
+            data.enum_token.span,
+            "zabi derives are not supported for enums: ABI values have no variant tag to select a variant from",
+        )),
+        Data::Union(data) => Err(syn::Error::new(
+            data.union_token.span,
+            "zabi derives are not supported for unions: ABI decoding needs every field to be read",
+        )),
+    }
+}
+
+fn expand_zview(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = input.ident;
+    let vis = input.vis;
+    let view_name = format_ident!("{}View", name);
+    let (generics, lifetime) = decode_generics(&input.generics);
+    let (impl_generics, view_ty_generics, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    let fields = struct_fields(input.data)?;
+    let struct_lenient = parse_struct_attrs(&input.attrs)?.lenient;
+    let codecs = fields.iter().map(field_codec).collect::<syn::Result<Vec<_>>>()?;
+
+    let mut getters = Vec::new();
+    let mut preceding = Vec::new();
+    for (i, (field, codec)) in fields.iter().zip(&codecs).enumerate() {
+        let getter = match &field.ident {
+            Some(ident) => ident.clone(),
+            None => format_ident!("_{}", i),
+        };
+        let ty = &field.ty;
+        let decode = codec.value(struct_lenient);
+        let doc = format!("Decode `{}` on demand.", getter);
+        getters.push(quote! {
+            #[doc = #doc]
+            #[inline]
+            #[allow(clippy::needless_question_mark)]
+            pub fn #getter(&self) -> Result<#ty, ::zabi_rs::ZError> {
+                let data = self.data;
+                let offset = self.offset #(+ #preceding)*;
+                Ok(#decode)
+            }
+        });
+        preceding.push(codec.head_size.clone());
+    }
+    let head_sizes = codecs.iter().map(|c| &c.head_size);
+    let doc = format!("Lazy view over an encoded `{}`; fields are decoded by their getters.", name);
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Clone, Copy)]
+        #vis struct #view_name #generics {
+            data: &#lifetime [u8],
+            offset: usize,
+            _marker: ::core::marker::PhantomData<fn() -> #name #ty_generics>,
+        }
+
+        impl #impl_generics #view_name #view_ty_generics #where_clause {
+            /// Size of the head section covered by the view.
+            pub const HEAD_SIZE: usize = 0 #(+ #head_sizes)*;
+
+            /// Create a view over the value whose head starts at `offset`.
+            /// Only the head bounds are checked here.
+            #[inline]
+            pub fn new(data: &#lifetime [u8], offset: usize) -> Result<Self, ::zabi_rs::ZError> {
+                match offset.checked_add(Self::HEAD_SIZE) {
+                    Some(end) if end <= data.len() => {}
+                    _ => return Err(::zabi_rs::ZError::OutOfBounds(offset.saturating_add(Self::HEAD_SIZE), data.len())),
+                }
+                Ok(Self { data, offset, _marker: ::core::marker::PhantomData })
+            }
+
+            #(#getters)*
+        }
+    })
+}
+
+/// Tokens describing how one field is decoded.
+/// The decode expressions are evaluated with `data` and `offset` in scope.
+struct FieldCodec {
+    decode: TokenStream2,
+    decode_lenient: TokenStream2,
+    head_size: TokenStream2,
+    sol_type: TokenStream2,
+    /// `(SOL_TYPE, flatten)` for `SolTypeBuf::push_fields`.
+    sol_field: TokenStream2,
+    is_dynamic: TokenStream2,
+    /// Field-level `#[zabi(lenient)]`.
+    lenient: bool,
+    /// Field-level `#[zabi(validate = "path")]`.
+    validate: Option<Path>,
+}
+
+impl FieldCodec {
+    /// The decode expression for this field, followed by its validator if any.
+    fn value(&self, struct_lenient: bool) -> TokenStream2 {
+        let decode = if struct_lenient || self.lenient { &self.decode_lenient } else { &self.decode };
+        match &self.validate {
+            Some(validate) => quote_spanned! {validate.span()=>
+                {
+                    let val = #decode;
+                    #validate(&val)?;
+                    val
+                }
+            },
+            None => decode.clone(),
+        }
+    }
+}
+
+fn field_codec(field: &Field) -> syn::Result<FieldCodec> {
+    let attrs = parse_field_attrs(field)?;
+    let ty = &field.ty;
+    if attrs.flatten {
+        if attrs.abi.is_some() {
+            return Err(syn::Error::new(ty.span(), "#[zabi(flatten)] cannot be combined with #[zabi(as = \"...\")]"));
+        }
+        if let Type::Tuple(_) = ty {
+            return Err(syn::Error::new(ty.span(), "#[zabi(flatten)] expects a struct deriving ZDecode"));
+        }
+    }
+    if let Some(abi) = attrs.abi {
+        let mut codec = forced_codec(&abi)?;
+        codec.lenient = attrs.lenient;
+        codec.validate = attrs.validate;
+        return Ok(codec);
+    }
+    if let Type::Reference(_) = ty {
+        let what = match &field.ident {
+            Some(ident) => format!("field `{}`", ident),
+            None => "this field".to_string(),
+        };
+        return Err(syn::Error::new(
+            ty.span(),
+            format!(
+                "{} is a plain reference, which has no ABI type on its own; \
+                 use a zabi view type (e.g. `ZBytesN<'a, 32>`) or add #[zabi(as = \"...\")]",
+                what
+            ),
+        ));
+    }
+    // A flattened struct's members are spliced into the enclosing tuple: they
+    // are read inline, even for a dynamic `#[zabi(tuple)]` struct, and its
+    // type contributes members instead of a nested tuple.
+    // Spanned on the field type so a missing `ZDecode` impl is reported there.
+    let (decode, decode_lenient, head_size, sol_type) = if attrs.flatten {
+        (
+            quote_spanned! {ty.span()=> <#ty as ::zabi_rs::ZDecode>::decode(data, offset)? },
+            quote_spanned! {ty.span()=> <#ty as ::zabi_rs::ZDecode>::decode_lenient(data, offset)? },
+            quote_spanned! {ty.span()=> <#ty as ::zabi_rs::ZDecode>::INLINE_HEAD_SIZE },
+            quote_spanned! {ty.span()=> ::zabi_rs::signature::tuple_members(<#ty as ::zabi_rs::ZDecode>::SOL_TYPE) },
+        )
+    } else {
+        (
+            quote_spanned! {ty.span()=> <#ty as ::zabi_rs::ZDecode>::decode_member(data, offset)? },
+            quote_spanned! {ty.span()=> <#ty as ::zabi_rs::ZDecode>::decode_member_lenient(data, offset)? },
+            quote_spanned! {ty.span()=> <#ty as ::zabi_rs::ZDecode>::HEAD_SIZE },
+            quote_spanned! {ty.span()=> <#ty as ::zabi_rs::ZDecode>::SOL_TYPE },
+        )
     };
+    Ok(FieldCodec {
+        decode,
+        decode_lenient,
+        head_size,
+        sol_type,
+        sol_field: {
+            let flatten = attrs.flatten;
+            quote_spanned! {ty.span()=> (<#ty as ::zabi_rs::ZDecode>::SOL_TYPE, #flatten) }
+        },
+        is_dynamic: quote_spanned! {ty.span()=> <#ty as ::zabi_rs::ZDecode>::IS_DYNAMIC },
+        lenient: attrs.lenient,
+        validate: attrs.validate,
+    })
+}
+
+/// Parsed `#[zabi(...)]` options on a field.
+struct FieldAttrs {
+    abi: Option<LitStr>,
+    lenient: bool,
+    validate: Option<Path>,
+    flatten: bool,
+}
+
+/// Reads `#[zabi(as = "...")]`, `#[zabi(lenient)]`, `#[zabi(flatten)]` and
+/// `#[zabi(validate = "path")]` from a field.
+///
+/// A validator is called as `path(&value) -> Result<(), ZError>` right after
+/// the field is decoded.
+fn parse_field_attrs(field: &Field) -> syn::Result<FieldAttrs> {
+    let mut attrs = FieldAttrs { abi: None, lenient: false, validate: None, flatten: false };
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("zabi")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("as") {
+                if attrs.abi.is_some() {
+                    return Err(meta.error("duplicate `as` in zabi attributes"));
+                }
+                attrs.abi = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else if meta.path.is_ident("lenient") {
+                attrs.lenient = true;
+                Ok(())
+            } else if meta.path.is_ident("flatten") {
+                attrs.flatten = true;
+                Ok(())
+            } else if meta.path.is_ident("validate") {
+                if attrs.validate.is_some() {
+                    return Err(meta.error("duplicate `validate` in zabi attributes"));
+                }
+                attrs.validate = Some(meta.value()?.parse::<LitStr>()?.parse::<Path>()?);
+                Ok(())
+            } else {
+                Err(meta.error(
+                    "unsupported zabi field attribute; expected `as = \"<abi type>\"`, `lenient`, `flatten` or `validate = \"path\"`",
+                ))
+            }
+        })?;
+    }
+    Ok(attrs)
+}
+
+/// Parsed `#[zabi(...)]` options on the struct itself.
+struct StructAttrs {
+    lenient: bool,
+    /// `#[zabi(transparent)]`: a newtype whose ABI type is its only field's.
+    transparent: bool,
+    /// `#[zabi(tuple)]`: a Solidity tuple, which as a member sits behind an
+    /// offset word when dynamic rather than inline at the head offset.
+    tuple: bool,
+    /// Overrides the generated `SIGNATURE`.
+    signature: Option<LitStr>,
+    /// Return values declared with `#[zabi(outputs(name: Type, ...))]`.
+    outputs: Option<Punctuated<Field, Token![,]>>,
+    /// Extra derives for the outputs struct, from `#[zabi(outputs_derive(...))]`.
+    outputs_derive: Vec<Path>,
+    /// `#[zabi(owned)]` or `#[zabi(owned(Derive, ...))]`: generate an owned
+    /// mirror struct with these derives.
+    owned: Option<Vec<Path>>,
+}
+
+/// Reads struct-level `#[zabi(lenient)]`, `#[zabi(transparent)]`,
+/// `#[zabi(signature = "...")]`, `#[zabi(outputs(...))]`,
+/// `#[zabi(outputs_derive(...))]` and `#[zabi(owned(...))]`.
+fn parse_struct_attrs(attrs: &[Attribute]) -> syn::Result<StructAttrs> {
+    let mut parsed = StructAttrs {
+        lenient: false,
+        transparent: false,
+        tuple: false,
+        signature: None,
+        outputs: None,
+        outputs_derive: Vec::new(),
+        owned: None,
+    };
+    for attr in attrs.iter().filter(|a| a.path().is_ident("zabi")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("lenient") {
+                parsed.lenient = true;
+                Ok(())
+            } else if meta.path.is_ident("transparent") {
+                parsed.transparent = true;
+                Ok(())
+            } else if meta.path.is_ident("tuple") {
+                parsed.tuple = true;
+                Ok(())
+            } else if meta.path.is_ident("signature") {
+                parsed.signature = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else if meta.path.is_ident("outputs") {
+                let content;
+                parenthesized!(content in meta.input);
+                parsed.outputs = Some(Punctuated::parse_terminated_with(&content, Field::parse_named)?);
+                Ok(())
+            } else if meta.path.is_ident("outputs_derive") {
+                let content;
+                parenthesized!(content in meta.input);
+                parsed.outputs_derive.extend(Punctuated::<Path, Token![,]>::parse_terminated(&content)?);
+                Ok(())
+            } else if meta.path.is_ident("owned") {
+                let derives = parsed.owned.get_or_insert_with(Vec::new);
+                if meta.input.peek(syn::token::Paren) {
+                    let content;
+                    parenthesized!(content in meta.input);
+                    derives.extend(Punctuated::<Path, Token![,]>::parse_terminated(&content)?);
+                }
+                Ok(())
+            } else {
+                Err(meta.error(
+                    "unsupported zabi struct attribute; expected `lenient`, `transparent`, `tuple`, `signature = \"...\"`, \
+                     `outputs(...)`, `outputs_derive(...)` or `owned(...)`",
+                ))
+            }
+        })?;
+    }
+    if parsed.outputs.is_none() && !parsed.outputs_derive.is_empty() {
+        return Err(syn::Error::new(
+            attrs[0].span(),
+            "#[zabi(outputs_derive(...))] requires #[zabi(outputs(...))]",
+        ));
+    }
+    Ok(parsed)
+}
+
+/// Decodes a field with the reader for an explicit ABI type, bypassing the
+/// field's own `ZDecode` impl. The raw value (e.g. `&[u8; 32]`, `u64`) is
+/// assigned to the field directly; `uintN`/`intN` widths without a native
+/// integer (e.g. `uint24`) assign a `ZU256`/`ZInt256`.
+fn forced_codec(abi: &LitStr) -> syn::Result<FieldCodec> {
+    let ty = abi.value();
+    // (strict reader, lenient reader); address/bytes/string have no padding rule.
+    let (decode, decode_lenient) = match ty.as_str() {
+        "address" => same(quote! { ::zabi_rs::decoder::read_address_from_word(data, offset)?.0 }),
+        "bytes" => same(quote! { ::zabi_rs::decoder::read_bytes(data, offset)?.0 }),
+        "string" => same(quote! { ::zabi_rs::decoder::read_string(data, offset)?.0 }),
+        "bool" => (
+            quote! { ::zabi_rs::decoder::read_bool(data, offset)?.0 },
+            quote! { ::zabi_rs::decoder::read_bool_lenient(data, offset)?.0 },
+        ),
+        "uint8" => int_readers("u8"),
+        "uint16" => int_readers("u16"),
+        "uint32" => int_readers("u32"),
+        "uint64" => int_readers("u64"),
+        "uint128" => int_readers("u128"),
+        "int8" => int_readers("i8"),
+        "int16" => int_readers("i16"),
+        "int32" => int_readers("i32"),
+        "int64" => int_readers("i64"),
+        "int128" => int_readers("i128"),
+        other => match other_width_readers(other) {
+            Some(readers) => readers,
+            None => {
+                return Err(syn::Error::new(
+                    abi.span(),
+                    format!(
+                        "unsupported ABI type `{}` in #[zabi(as = ...)]; expected address, bool, \
+                         string, bytes, bytes1..bytes32, uint8..uint256 or int8..int256",
+                        other
+                    ),
+                ))
+            }
+        },
+    };
+    let is_dynamic = ty == "bytes" || ty == "string";
+    Ok(FieldCodec {
+        decode,
+        decode_lenient,
+        head_size: quote! { 32usize },
+        sol_type: quote! { #ty },
+        sol_field: quote! { (#ty, false) },
+        is_dynamic: quote! { #is_dynamic },
+        lenient: false,
+        validate: None,
+    })
+}
+
+/// Readers for `bytesN`, and for `uintN`/`intN` widths without a native Rust
+/// integer, which read into `ZU256`/`ZInt256` with the padding checked for
+/// the declared width.
+fn other_width_readers(ty: &str) -> Option<(TokenStream2, TokenStream2)> {
+    let width = |digits: &str, max: usize| {
+        digits.parse::<usize>().ok().filter(|n| !digits.starts_with('0') && (1..=max).contains(n))
+    };
+    if let Some(n) = ty.strip_prefix("bytes").and_then(|n| width(n, 32)) {
+        return Some((
+            quote! { ::zabi_rs::zbytes_fixed::read_bytes_n::<#n>(data, offset)?.0 },
+            quote! { ::zabi_rs::zbytes_fixed::read_bytes_n_lenient::<#n>(data, offset)?.0 },
+        ));
+    }
+    if let Some(bits) = ty.strip_prefix("uint").and_then(|n| width(n, 256)).filter(|n| n.is_multiple_of(8)) {
+        return Some((
+            quote! { ::zabi_rs::decoder::read_uint_bits(data, offset, #bits)? },
+            quote! { ::zabi_rs::decoder::read_u256(data, offset)? },
+        ));
+    }
+    let bits = ty.strip_prefix("int").and_then(|n| width(n, 256)).filter(|n| n.is_multiple_of(8))?;
+    Some((
+        quote! { ::zabi_rs::decoder::read_int_bits(data, offset, #bits)? },
+        quote! { ::zabi_rs::decoder::read_int256(data, offset)? },
+    ))
+}
+
+fn same(decode: TokenStream2) -> (TokenStream2, TokenStream2) {
+    (decode.clone(), decode)
+}
 
-    TokenStream::from(expanded)
+/// `decoder::read_<suffix>` and `decoder::read_<suffix>_lenient`.
+fn int_readers(suffix: &str) -> (TokenStream2, TokenStream2) {
+    let strict = Ident::new(&format!("read_{}", suffix), proc_macro2::Span::call_site());
+    let lenient = Ident::new(&format!("read_{}_lenient", suffix), proc_macro2::Span::call_site());
+    (
+        quote! { ::zabi_rs::decoder::#strict(data, offset)? },
+        quote! { ::zabi_rs::decoder::#lenient(data, offset)? },
+    )
 }
@@ -0,0 +1,38 @@
+//! `selector!` and `event_topic!` — keccak hashes of signatures computed at
+//! compile time.
+//!
+//! ```ignore
+//! const APPROVE: [u8; 4] = selector!("approve(address,uint256)");
+//! const TRANSFER: [u8; 32] = event_topic!("Transfer(address,address,uint256)");
+//! ```
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::LitStr;
+
+use crate::sol::keccak256;
+
+/// Hashes the signature with whitespace removed, so `"f(uint256, bool)"` and
+/// `"f(uint256,bool)"` agree.
+pub(crate) fn hash_signature(sig: &LitStr) -> syn::Result<[u8; 32]> {
+    let canonical: String = sig.value().chars().filter(|c| !c.is_whitespace()).collect();
+    let open = canonical.find('(');
+    if open.is_none_or(|i| i == 0) || !canonical.ends_with(')') {
+        return Err(syn::Error::new(
+            sig.span(),
+            "expected a signature like `transfer(address,uint256)`",
+        ));
+    }
+    Ok(keccak256(canonical.as_bytes()))
+}
+
+pub fn expand_selector(sig: LitStr) -> syn::Result<TokenStream2> {
+    let hash = hash_signature(&sig)?;
+    let bytes = &hash[..4];
+    Ok(quote! { [#(#bytes),*] })
+}
+
+pub fn expand_event_topic(sig: LitStr) -> syn::Result<TokenStream2> {
+    let hash = hash_signature(&sig)?;
+    Ok(quote! { [#(#hash),*] })
+}
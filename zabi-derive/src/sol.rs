@@ -0,0 +1,732 @@
+//! `sol!` — Solidity declarations to zero-copy view types.
+//!
+//! Supported items:
+//! - `struct Name { type field; ... }`
+//! - `function name(type arg, ...) [modifiers] [returns (type, ...)];`
+//!   Overloads get `_1`, `_2`, ... suffixes on the generated names, as in
+//!   `abigen!`.
+//! - `event Name(type [indexed] arg, ...) [anonymous];`
+//! - `error Name(type arg, ...);`
+
+use std::collections::HashMap;
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::{braced, bracketed, parenthesized, Ident, LitInt, Token};
+use tiny_keccak::{Hasher, Keccak};
+
+pub fn keccak256(bytes: &[u8]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(bytes);
+    hasher.finalize(&mut out);
+    out
+}
+
+/// A Solidity type as written in the declaration.
+#[derive(Clone)]
+pub(crate) enum SolType {
+    /// Canonical elementary type name, e.g. `uint256`, `bytes4`.
+    Elementary(String),
+    /// A struct declared in the same `sol!` block.
+    Struct(Ident),
+    Array(Box<SolType>),
+    FixedArray(Box<SolType>, usize),
+}
+
+pub(crate) struct Param {
+    pub(crate) ty: SolType,
+    pub(crate) name: Option<Ident>,
+    pub(crate) indexed: bool,
+}
+
+pub(crate) struct SolStruct {
+    pub(crate) name: Ident,
+    pub(crate) fields: Vec<Param>,
+}
+
+pub(crate) struct SolFunction {
+    /// Solidity name, used in the signature.
+    pub(crate) name: Ident,
+    /// Prefix for generated item names; differs from `name` for overloads.
+    pub(crate) rust_name: Ident,
+    pub(crate) inputs: Vec<Param>,
+    pub(crate) outputs: Vec<Param>,
+}
+
+pub(crate) struct SolEvent {
+    pub(crate) name: Ident,
+    pub(crate) inputs: Vec<Param>,
+    /// Anonymous events have no signature topic.
+    pub(crate) anonymous: bool,
+}
+
+pub(crate) struct SolError {
+    pub(crate) name: Ident,
+    pub(crate) inputs: Vec<Param>,
+}
+
+pub(crate) enum Item {
+    Struct(SolStruct),
+    Function(SolFunction),
+    Event(SolEvent),
+    Error(SolError),
+}
+
+pub struct SolInput {
+    pub(crate) items: Vec<Item>,
+}
+
+impl Parse for SolType {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident = Ident::parse_any(input)?;
+        let mut ty = match elementary(&ident.to_string()) {
+            Some(name) => SolType::Elementary(name),
+            None => SolType::Struct(ident),
+        };
+        while input.peek(syn::token::Bracket) {
+            let content;
+            bracketed!(content in input);
+            ty = if content.is_empty() {
+                SolType::Array(Box::new(ty))
+            } else {
+                let len: LitInt = content.parse()?;
+                SolType::FixedArray(Box::new(ty), len.base10_parse()?)
+            };
+        }
+        Ok(ty)
+    }
+}
+
+/// Canonicalizes an elementary type name, or returns `None` for a struct name.
+pub(crate) fn elementary(name: &str) -> Option<String> {
+    let canonical = match name {
+        "address" | "bool" | "string" | "bytes" => name.to_string(),
+        "uint" => "uint256".to_string(),
+        "int" => "int256".to_string(),
+        "byte" => "bytes1".to_string(),
+        _ => {
+            let (prefix, bits) = if let Some(n) = name.strip_prefix("uint") {
+                ("uint", n)
+            } else if let Some(n) = name.strip_prefix("int") {
+                ("int", n)
+            } else if let Some(n) = name.strip_prefix("bytes") {
+                ("bytes", n)
+            } else {
+                return None;
+            };
+            let n: usize = bits.parse().ok()?;
+            let valid = match prefix {
+                "bytes" => (1..=32).contains(&n),
+                _ => n.is_multiple_of(8) && (8..=256).contains(&n),
+            };
+            if !valid {
+                return None;
+            }
+            name.to_string()
+        }
+    };
+    Some(canonical)
+}
+
+impl Parse for Param {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ty: SolType = input.parse()?;
+        let mut indexed = false;
+        let mut name = None;
+        while input.peek(Ident::peek_any) {
+            let ident = Ident::parse_any(input)?;
+            match ident.to_string().as_str() {
+                "indexed" => indexed = true,
+                "memory" | "calldata" | "storage" => {}
+                _ => name = Some(ident),
+            }
+        }
+        Ok(Param { ty, name, indexed })
+    }
+}
+
+fn parse_params(input: ParseStream) -> syn::Result<Vec<Param>> {
+    let content;
+    parenthesized!(content in input);
+    let params = content.parse_terminated(Param::parse, Token![,])?;
+    Ok(params.into_iter().collect())
+}
+
+impl Parse for SolInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut items = Vec::new();
+        let mut overloads: HashMap<String, usize> = HashMap::new();
+        while !input.is_empty() {
+            if input.peek(Token![struct]) {
+                input.parse::<Token![struct]>()?;
+                let name: Ident = input.parse()?;
+                let content;
+                braced!(content in input);
+                let mut fields = Vec::new();
+                while !content.is_empty() {
+                    let field: Param = content.parse()?;
+                    content.parse::<Token![;]>()?;
+                    fields.push(field);
+                }
+                items.push(Item::Struct(SolStruct { name, fields }));
+                continue;
+            }
+
+            let keyword = Ident::parse_any(input)?;
+            match keyword.to_string().as_str() {
+                "function" => {
+                    let name = Ident::parse_any(input)?;
+                    let inputs = parse_params(input)?;
+                    let mut outputs = Vec::new();
+                    // Skip visibility and mutability modifiers.
+                    while !input.peek(Token![;]) {
+                        let modifier = Ident::parse_any(input)?;
+                        if modifier == "returns" {
+                            outputs = parse_params(input)?;
+                        }
+                    }
+                    input.parse::<Token![;]>()?;
+                    let count = overloads.entry(name.unraw().to_string()).or_insert(0);
+                    let rust_name = match *count {
+                        0 => name.clone(),
+                        n => format_ident!("{}_{}", name.unraw(), n, span = name.span()),
+                    };
+                    *count += 1;
+                    items.push(Item::Function(SolFunction {
+                        name,
+                        rust_name,
+                        inputs,
+                        outputs,
+                    }));
+                }
+                "event" => {
+                    let name: Ident = input.parse()?;
+                    let inputs = parse_params(input)?;
+                    let anonymous =
+                        input.peek(Ident) && input.fork().parse::<Ident>()? == "anonymous";
+                    if anonymous {
+                        input.parse::<Ident>()?;
+                    }
+                    input.parse::<Token![;]>()?;
+                    items.push(Item::Event(SolEvent {
+                        name,
+                        inputs,
+                        anonymous,
+                    }));
+                }
+                "error" => {
+                    let name: Ident = input.parse()?;
+                    let inputs = parse_params(input)?;
+                    input.parse::<Token![;]>()?;
+                    items.push(Item::Error(SolError { name, inputs }));
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        keyword.span(),
+                        "expected `struct`, `function`, `event` or `error`",
+                    ))
+                }
+            }
+        }
+        Ok(SolInput { items })
+    }
+}
+
+/// Lookup of structs declared in the block.
+pub(crate) struct Ctx<'s> {
+    structs: HashMap<String, &'s SolStruct>,
+}
+
+impl<'s> Ctx<'s> {
+    /// Fails if a struct contains itself, directly or through other structs,
+    /// which no ABI type can.
+    pub(crate) fn new(items: &'s [Item]) -> syn::Result<Self> {
+        let structs = items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Struct(s) => Some((s.name.to_string(), s)),
+                _ => None,
+            })
+            .collect();
+        let ctx = Ctx { structs };
+        let mut visiting = Vec::new();
+        for item in items {
+            if let Item::Struct(s) = item {
+                ctx.check_acyclic(s, &mut visiting)?;
+            }
+        }
+        Ok(ctx)
+    }
+
+    /// Depth-first walk over the structs reachable from `s`; `visiting` holds
+    /// the current path.
+    fn check_acyclic(&self, s: &'s SolStruct, visiting: &mut Vec<String>) -> syn::Result<()> {
+        visiting.push(s.name.to_string());
+        for field in &s.fields {
+            let mut ty = &field.ty;
+            while let SolType::Array(inner) | SolType::FixedArray(inner, ..) = ty {
+                ty = inner;
+            }
+            let SolType::Struct(name) = ty else { continue };
+            if visiting.contains(&name.to_string()) {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!(
+                        "recursive struct `{}`; an ABI type cannot contain itself",
+                        name
+                    ),
+                ));
+            }
+            // Unknown names are reported where the type is used.
+            if let Some(inner) = self.structs.get(&name.to_string()) {
+                self.check_acyclic(inner, visiting)?;
+            }
+        }
+        visiting.pop();
+        Ok(())
+    }
+
+    fn get(&self, name: &Ident) -> syn::Result<&'s SolStruct> {
+        self.structs.get(&name.to_string()).copied().ok_or_else(|| {
+            syn::Error::new(
+                name.span(),
+                format!(
+                    "unknown type `{}`; structs must be declared in the same sol! block",
+                    name
+                ),
+            )
+        })
+    }
+
+    /// Canonical ABI type string used in signatures.
+    fn canonical(&self, ty: &SolType) -> syn::Result<String> {
+        Ok(match ty {
+            SolType::Elementary(name) => name.clone(),
+            SolType::Struct(name) => {
+                let fields = self.get(name)?.fields.iter().map(|f| self.canonical(&f.ty));
+                format!("({})", fields.collect::<syn::Result<Vec<_>>>()?.join(","))
+            }
+            SolType::Array(inner) => format!("{}[]", self.canonical(inner)?),
+            SolType::FixedArray(inner, n) => format!("{}[{}]", self.canonical(inner)?, n),
+        })
+    }
+
+    fn signature(&self, name: &Ident, params: &[Param]) -> syn::Result<String> {
+        let types = params
+            .iter()
+            .map(|p| self.canonical(&p.ty))
+            .collect::<syn::Result<Vec<_>>>()?;
+        Ok(format!("{}({})", name.unraw(), types.join(",")))
+    }
+
+    /// Whether the Rust view type borrows from the input.
+    fn borrows(&self, ty: &SolType) -> syn::Result<bool> {
+        Ok(match ty {
+            SolType::Elementary(name) => native_int(name).is_none() && name != "bool",
+            SolType::Struct(name) => self.struct_borrows(self.get(name)?)?,
+            SolType::Array(_) | SolType::FixedArray(..) => true,
+        })
+    }
+
+    fn struct_borrows(&self, s: &SolStruct) -> syn::Result<bool> {
+        self.params_borrow(&s.fields)
+    }
+
+    /// Like [`Ctx::params_borrow`], counting indexed hashes as borrowed.
+    pub(crate) fn event_borrows(&self, e: &SolEvent) -> syn::Result<bool> {
+        for p in &e.inputs {
+            if (p.indexed && is_dynamic_or_composite(&p.ty)) || self.borrows(&p.ty)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    pub(crate) fn params_borrow(&self, params: &[Param]) -> syn::Result<bool> {
+        for p in params {
+            if self.borrows(&p.ty)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// The zabi-rs view type for a Solidity type.
+    fn rust_type(&self, ty: &SolType) -> syn::Result<TokenStream2> {
+        Ok(match ty {
+            SolType::Elementary(name) => {
+                if let Some(int) = native_int(name) {
+                    let int = Ident::new(int, Span::call_site());
+                    quote! { #int }
+                } else if name == "bool" {
+                    quote! { ::zabi_rs::ZBool }
+                } else if name == "address" {
+                    quote! { ::zabi_rs::ZAddress<'a> }
+                } else if name == "string" {
+                    quote! { ::zabi_rs::ZString<'a> }
+                } else if name == "bytes" {
+                    quote! { ::zabi_rs::ZBytes<'a> }
+                } else if let Some(n) = name.strip_prefix("bytes") {
+                    let n: usize = n.parse().expect("validated bytesN");
+                    quote! { ::zabi_rs::ZBytesN<'a, #n> }
+                } else if name.starts_with("int") {
+                    // Widths without a native Rust integer use the full word,
+                    // read with the padding checked for the declared width.
+                    quote! { ::zabi_rs::ZInt256<'a> }
+                } else {
+                    quote! { ::zabi_rs::ZU256<'a> }
+                }
+            }
+            SolType::Struct(name) => {
+                if self.struct_borrows(self.get(name)?)? {
+                    quote! { #name<'a> }
+                } else {
+                    quote! { #name }
+                }
+            }
+            SolType::Array(inner) => {
+                let inner = self.rust_type(inner)?;
+                quote! { ::zabi_rs::ZArray<'a, #inner> }
+            }
+            SolType::FixedArray(inner, n) => {
+                let inner = self.rust_type(inner)?;
+                quote! { ::zabi_rs::ZFixedArray<'a, #inner, #n> }
+            }
+        })
+    }
+}
+
+/// Native Rust integer for `uintN`/`intN` widths zabi-rs decodes directly.
+fn native_int(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "uint8" => "u8",
+        "uint16" => "u16",
+        "uint32" => "u32",
+        "uint64" => "u64",
+        "uint128" => "u128",
+        "int8" => "i8",
+        "int16" => "i16",
+        "int32" => "i32",
+        "int64" => "i64",
+        "int128" => "i128",
+        _ => return None,
+    })
+}
+
+/// `("uint", N)` or `("int", N)` for a `uintN`/`intN` held in a full-word
+/// view, i.e. a width without a native Rust integer other than 256.
+fn word_width(ty: &SolType) -> Option<(&'static str, usize)> {
+    let SolType::Elementary(name) = ty else {
+        return None;
+    };
+    if native_int(name).is_some() {
+        return None;
+    }
+    let (kind, bits) = match name.strip_prefix("uint") {
+        Some(bits) => ("uint", bits),
+        None => ("int", name.strip_prefix("int")?),
+    };
+    let bits: usize = bits.parse().expect("validated intN");
+    (bits < 256).then_some((kind, bits))
+}
+
+/// `#[zabi(as = "uint24")]` for a field held in a full-word view, so the
+/// padding is checked for the declared width and `SOL_TYPE` names it.
+/// Array elements keep the plain word reader.
+fn word_width_attr(ty: &SolType) -> TokenStream2 {
+    match (word_width(ty), ty) {
+        (Some(_), SolType::Elementary(name)) => quote! { #[zabi(as = #name)] },
+        _ => quote! {},
+    }
+}
+
+/// The padding-checked reader for a field held in a full-word view.
+fn word_width_read(ty: &SolType, data: TokenStream2, offset: TokenStream2) -> Option<TokenStream2> {
+    Some(match word_width(ty)? {
+        ("uint", bits) => quote! { ::zabi_rs::decoder::read_uint_bits(#data, #offset, #bits)? },
+        (_, bits) => quote! { ::zabi_rs::decoder::read_int_bits(#data, #offset, #bits)? },
+    })
+}
+
+fn is_dynamic_or_composite(ty: &SolType) -> bool {
+    match ty {
+        SolType::Elementary(name) => name == "string" || name == "bytes",
+        _ => true,
+    }
+}
+
+fn field_ident(param: &Param, index: usize) -> Ident {
+    match &param.name {
+        Some(name) => name.clone(),
+        None => format_ident!("_{}", index),
+    }
+}
+
+/// `struct Name<'a> { pub field: Type, ... }` deriving `ZDecode`.
+/// `signature` becomes the derived `SIGNATURE`, so it keeps the declared
+/// Solidity types even inside arrays, where the view type is wider.
+fn view_struct(
+    ctx: &Ctx,
+    name: &Ident,
+    params: &[Param],
+    doc: &str,
+    signature: &str,
+    tuple: bool,
+) -> syn::Result<TokenStream2> {
+    let generics = if ctx.params_borrow(params)? {
+        quote! { <'a> }
+    } else {
+        quote! {}
+    };
+    let fields = params
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let ident = field_ident(p, i);
+            let ty = ctx.rust_type(&p.ty)?;
+            let width = word_width_attr(&p.ty);
+            Ok(quote! { #width pub #ident: #ty })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    let tuple = if tuple {
+        quote! { #[zabi(tuple)] }
+    } else {
+        quote! {}
+    };
+    Ok(quote! {
+        #[doc = #doc]
+        #[allow(non_camel_case_types, non_snake_case)]
+        #[derive(Debug, Clone, Copy, ::zabi_rs::ZDecode)]
+        #[zabi(signature = #signature)]
+        #tuple
+        pub struct #name #generics {
+            #(#fields),*
+        }
+    })
+}
+
+fn expand_struct(ctx: &Ctx, s: &SolStruct) -> syn::Result<TokenStream2> {
+    let signature = ctx.signature(&s.name, &s.fields)?;
+    let doc = format!("Solidity struct `{}`.", s.name);
+    view_struct(ctx, &s.name, &s.fields, &doc, &signature, true)
+}
+
+fn expand_function(ctx: &Ctx, f: &SolFunction) -> syn::Result<TokenStream2> {
+    let signature = ctx.signature(&f.name, &f.inputs)?;
+    let hash = keccak256(signature.as_bytes());
+    let selector = &hash[..4];
+
+    let call_name = format_ident!("{}Call", f.rust_name.unraw());
+    let call_doc = format!("Arguments of `{}`.", signature);
+    let call_struct = view_struct(ctx, &call_name, &f.inputs, &call_doc, &signature, false)?;
+    let call_generics = if ctx.params_borrow(&f.inputs)? {
+        quote! { <'a> }
+    } else {
+        quote! {}
+    };
+
+    let returns = if f.outputs.is_empty() {
+        quote! {}
+    } else {
+        let return_name = format_ident!("{}Return", f.rust_name.unraw());
+        let return_doc = format!("Return values of `{}`.", signature);
+        let return_signature = ctx.signature(&return_name, &f.outputs)?;
+        let return_struct = view_struct(
+            ctx,
+            &return_name,
+            &f.outputs,
+            &return_doc,
+            &return_signature,
+            false,
+        )?;
+        let return_generics = if ctx.params_borrow(&f.outputs)? {
+            quote! { <'a> }
+        } else {
+            quote! {}
+        };
+        quote! {
+            #return_struct
+
+            impl<'a> #return_name #return_generics {
+                /// Decode ABI-encoded return data.
+                #[inline]
+                pub fn decode_returns(data: &'a [u8]) -> Result<Self, ::zabi_rs::ZError> {
+                    <Self as ::zabi_rs::ZDecode<'a>>::decode(data, 0)
+                }
+            }
+        }
+    };
+
+    Ok(quote! {
+        #call_struct
+
+        impl<'a> #call_name #call_generics {
+            /// First four bytes of `keccak256(SIGNATURE)`.
+            pub const SELECTOR: [u8; 4] = [#(#selector),*];
+
+            /// Decode calldata, checking the selector first.
+            #[inline]
+            pub fn decode_calldata(calldata: &'a [u8]) -> Result<Self, ::zabi_rs::ZError> {
+                let selector = ::zabi_rs::decoder::read_selector(calldata)?;
+                if selector != &Self::SELECTOR {
+                    return Err(::zabi_rs::ZError::SelectorMismatch { expected: Self::SELECTOR, found: *selector });
+                }
+                <Self as ::zabi_rs::ZDecode<'a>>::decode(&calldata[4..], 0)
+            }
+        }
+
+        #returns
+    })
+}
+
+fn expand_event(ctx: &Ctx, e: &SolEvent) -> syn::Result<TokenStream2> {
+    let signature = ctx.signature(&e.name, &e.inputs)?;
+    let topic0 = keccak256(signature.as_bytes());
+    let name = &e.name;
+    let generics = if ctx.event_borrows(e)? {
+        quote! { <'a> }
+    } else {
+        quote! {}
+    };
+
+    let max_indexed = if e.anonymous { 4 } else { 3 };
+    if e.inputs.iter().filter(|p| p.indexed).count() > max_indexed {
+        return Err(syn::Error::new(
+            name.span(),
+            format!(
+                "an event can have at most {} indexed parameters",
+                max_indexed
+            ),
+        ));
+    }
+
+    let mut fields = Vec::new();
+    let mut values = Vec::new();
+    // Indexed values follow the signature topic, or start at topic 0 when anonymous.
+    let mut topic_index = if e.anonymous { 0usize } else { 1 };
+    for (i, p) in e.inputs.iter().enumerate() {
+        let ident = field_ident(p, i);
+        if p.indexed {
+            // Dynamic and composite indexed values are stored as their keccak256 hash.
+            let ty = if is_dynamic_or_composite(&p.ty) {
+                quote! { ::zabi_rs::ZIndexedHash<'a> }
+            } else {
+                ctx.rust_type(&p.ty)?
+            };
+            let topic = quote! { &log.raw_topic(#topic_index)?[..] };
+            let read = word_width_read(&p.ty, topic.clone(), quote! { 0 })
+                .unwrap_or_else(|| quote! { <#ty as ::zabi_rs::ZDecode<'a>>::decode(#topic, 0)? });
+            values.push(quote! { #ident: #read });
+            fields.push(quote! { pub #ident: #ty });
+            topic_index += 1;
+        } else {
+            let ty = ctx.rust_type(&p.ty)?;
+            let read = word_width_read(&p.ty, quote! { data }, quote! { offset }).unwrap_or_else(
+                || quote! { <#ty as ::zabi_rs::ZDecode<'a>>::decode_member(data, offset)? },
+            );
+            values.push(quote! {
+                #ident: {
+                    let val = #read;
+                    offset += <#ty as ::zabi_rs::ZDecode<'a>>::HEAD_SIZE;
+                    val
+                }
+            });
+            fields.push(quote! { pub #ident: #ty });
+        }
+    }
+    let doc = if e.anonymous {
+        format!("Anonymous event `{}`.", signature)
+    } else {
+        format!("Event `{}`.", signature)
+    };
+    let anonymous = e.anonymous;
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[allow(non_snake_case)]
+        #[derive(Debug, Clone, Copy)]
+        pub struct #name #generics {
+            #(#fields),*
+        }
+
+        impl<'a> #name #generics {
+            /// Canonical event signature.
+            pub const SIGNATURE: &'static str = #signature;
+            /// `keccak256(SIGNATURE)`, expected in topic 0 unless the event is anonymous.
+            pub const TOPIC0: [u8; 32] = [#(#topic0),*];
+
+            /// Decode a log, checking topic 0 against [`Self::TOPIC0`]
+            /// unless the event is anonymous.
+            #[inline]
+            pub fn decode_log(log: &::zabi_rs::ZEventLog<'a>) -> Result<Self, ::zabi_rs::ZError> {
+                log.try_decode()
+            }
+        }
+
+        impl<'a> ::zabi_rs::ZEventDecode<'a> for #name #generics {
+            const TOPIC0: [u8; 32] = [#(#topic0),*];
+            const ANONYMOUS: bool = #anonymous;
+
+            #[allow(unused_mut, unused_variables, unused_assignments)]
+            fn decode(log: &::zabi_rs::ZEventLog<'a>) -> Result<Self, ::zabi_rs::ZError> {
+                let data = log.data();
+                let mut offset = 0usize;
+                Ok(Self { #(#values),* })
+            }
+        }
+    })
+}
+
+fn expand_error(ctx: &Ctx, e: &SolError) -> syn::Result<TokenStream2> {
+    let signature = ctx.signature(&e.name, &e.inputs)?;
+    let hash = keccak256(signature.as_bytes());
+    let selector = &hash[..4];
+    let name = &e.name;
+    let doc = format!("Custom error `{}`.", signature);
+    let error_struct = view_struct(ctx, name, &e.inputs, &doc, &signature, false)?;
+    let generics = if ctx.params_borrow(&e.inputs)? {
+        quote! { <'a> }
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {
+        #error_struct
+
+        impl<'a> #name #generics {
+            /// First four bytes of `keccak256(SIGNATURE)`.
+            pub const SELECTOR: [u8; 4] = [#(#selector),*];
+
+            /// Decode revert data, checking the selector first.
+            #[inline]
+            pub fn decode_revert(data: &'a [u8]) -> Result<Self, ::zabi_rs::ZError> {
+                let selector = ::zabi_rs::decoder::read_selector(data)?;
+                if selector != &Self::SELECTOR {
+                    return Err(::zabi_rs::ZError::SelectorMismatch { expected: Self::SELECTOR, found: *selector });
+                }
+                <Self as ::zabi_rs::ZDecode<'a>>::decode(&data[4..], 0)
+            }
+        }
+    })
+}
+
+pub(crate) fn expand_items(ctx: &Ctx, items: &[Item]) -> syn::Result<TokenStream2> {
+    let mut out = TokenStream2::new();
+    for item in items {
+        out.extend(match item {
+            Item::Struct(s) => expand_struct(ctx, s)?,
+            Item::Function(f) => expand_function(ctx, f)?,
+            Item::Event(e) => expand_event(ctx, e)?,
+            Item::Error(e) => expand_error(ctx, e)?,
+        });
+    }
+    Ok(out)
+}
+
+pub fn expand(input: SolInput) -> syn::Result<TokenStream2> {
+    let ctx = Ctx::new(&input.items)?;
+    expand_items(&ctx, &input.items)
+}
@@ -0,0 +1,77 @@
+/*
+ * C interface to zabi-rs, a zero-copy EVM ABI decoder.
+ *
+ * Link against libzabi_ffi.a or libzabi_ffi.so, built with
+ *     cargo build --release --manifest-path zabi-ffi/Cargo.toml
+ *
+ * Every function returns ZABI_OK on success or an error code, and writes its
+ * result through out-parameters, which are left untouched on error. Offsets
+ * are byte offsets of a 32-byte head word in `data`. Byte-string results
+ * point into `data` and are only valid while it is.
+ */
+#ifndef ZABI_H
+#define ZABI_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define ZABI_OK 0
+/*
+ * Decode failures; the same numbers as ZError::code(). The entry points
+ * below only produce 1-6 and 255 today; the rest are listed so callers can
+ * name every code ZError::code() defines.
+ */
+#define ZABI_ERR_INVALID_LENGTH 1
+#define ZABI_ERR_OUT_OF_BOUNDS 2
+#define ZABI_ERR_INVALID_PADDING 3
+#define ZABI_ERR_INVALID_BOOL 4
+#define ZABI_ERR_INVALID_UTF8 5
+#define ZABI_ERR_OFFSET_OUT_OF_RANGE 6
+#define ZABI_ERR_SELECTOR_MISMATCH 7
+#define ZABI_ERR_TYPE_MISMATCH 8
+#define ZABI_ERR_FIELD 9
+#define ZABI_ERR_MESSAGE 10
+#define ZABI_ERR_NAMED_FIELD 11
+#define ZABI_ERR_AT_OFFSET 12
+#define ZABI_ERR_UNKNOWN_SELECTOR 13
+#define ZABI_ERR_UNKNOWN_TOPIC 14
+#define ZABI_ERR_UNKNOWN_SCHEMA 15
+#define ZABI_ERR_OVERFLOW 16
+#define ZABI_ERR_CUSTOM 255
+/* Decoding panicked; indicates a bug in zabi-rs. */
+#define ZABI_ERR_PANIC 253
+/* A required pointer was null, or `len` exceeds PTRDIFF_MAX. */
+#define ZABI_ERR_NULL 254
+
+/* Copy the 4-byte selector at the start of calldata into `out` (4 bytes). */
+int32_t zabi_read_selector(const uint8_t *data, size_t len, uint8_t *out);
+
+/* Copy the uint256 word at `offset` into `out` (32 bytes, big-endian). */
+int32_t zabi_decode_u256(const uint8_t *data, size_t len, size_t offset, uint8_t *out);
+
+/* Copy the address at `offset` into `out` (20 bytes). */
+int32_t zabi_decode_address(const uint8_t *data, size_t len, size_t offset, uint8_t *out);
+
+/* Decode a uint64 at `offset`, rejecting dirty high bytes. */
+int32_t zabi_decode_u64(const uint8_t *data, size_t len, size_t offset, uint64_t *out);
+
+/* Decode a bool at `offset`. */
+int32_t zabi_decode_bool(const uint8_t *data, size_t len, size_t offset, bool *out);
+
+/*
+ * Locate the dynamic bytes or string whose offset word is at `offset`.
+ * `*out_ptr` receives a pointer into `data`, `*out_len` its length.
+ */
+int32_t zabi_decode_bytes(const uint8_t *data, size_t len, size_t offset,
+                          const uint8_t **out_ptr, size_t *out_len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ZABI_H */
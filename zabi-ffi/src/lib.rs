@@ -0,0 +1,5 @@
+//! Static (`libzabi_ffi.a`) and shared (`libzabi_ffi.so`, `.dylib`, `.dll`)
+//! library builds of the zabi-rs C ABI. The declarations are in
+//! `include/zabi.h`; see [`zabi_rs::ffi`] for the calling conventions.
+
+pub use zabi_rs::ffi::*;